@@ -2,14 +2,14 @@ use {
     crate::{domain::fee::FeeFactor, infra},
     anyhow::Context,
     clap::ValueEnum,
-    primitive_types::H160,
+    primitive_types::{H160, U256},
     shared::{
-        arguments::{display_list, display_option, ExternalSolver},
+        arguments::{display_list, display_option},
         bad_token::token_owner_finder,
         http_client,
         price_estimation::{self, NativePriceEstimators},
     },
-    std::{net::SocketAddr, num::NonZeroUsize, str::FromStr, time::Duration},
+    std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf, str::FromStr, time::Duration},
     url::Url,
 };
 
@@ -128,6 +128,15 @@ pub struct Arguments {
     #[clap(long, env, default_value = "0")]
     pub limit_order_price_factor: f64,
 
+    /// The maximum number of limit orders in an auction. Limit orders that
+    /// are least likely to get executed are dropped first.
+    #[clap(long, env)]
+    pub max_limit_orders_per_auction: Option<usize>,
+
+    /// The maximum number of limit orders of a single owner in an auction.
+    #[clap(long, env)]
+    pub max_limit_orders_per_owner: Option<usize>,
+
     /// The time between auction updates.
     #[clap(long, env, default_value = "10s", value_parser = humantime::parse_duration)]
     pub auction_update_interval: Duration,
@@ -151,12 +160,14 @@ pub struct Arguments {
     )]
     pub trusted_tokens_update_interval: Duration,
 
-    /// A list of drivers in the following format: `<NAME>|<URL>,<NAME>|<URL>`
+    /// A list of drivers in the following format:
+    /// `<NAME>|<URL>[|<VIEW>],<NAME>|<URL>[|<VIEW>]`, see [`Driver`].
     #[clap(long, env, use_value_delimiter = true)]
-    pub drivers: Vec<ExternalSolver>,
+    pub drivers: Vec<Driver>,
 
     /// The maximum number of blocks to wait for a settlement to appear on
-    /// chain.
+    /// chain. This deadline is shared by the winner and all runner-up
+    /// solutions that get asked to settle if the winner fails to.
     #[clap(long, env, default_value = "5")]
     pub submission_deadline: usize,
 
@@ -167,6 +178,48 @@ pub struct Arguments {
     #[clap(long, env, default_value = "5")]
     pub additional_deadline_for_rewards: usize,
 
+    /// The maximum number of solutions that can win an auction. Values greater
+    /// than 1 enable combinatorial auctions where the best solutions with
+    /// non-overlapping orders and consistent clearing prices all win and get
+    /// settled in parallel (at most one per solver). With a single winner, the
+    /// runner-up solutions get asked to settle if the winner fails to.
+    #[clap(long, env, default_value = "1")]
+    pub max_winners_per_auction: NonZeroUsize,
+
+    /// Enables the fairness check which excludes solutions from the ranking
+    /// if they execute an order at a worse exchange rate than another
+    /// solution does. The value is the relative shortfall (e.g. 0.01 for 1%)
+    /// that is still tolerated.
+    #[clap(long, env)]
+    pub fairness_tolerance: Option<f64>,
+
+    /// Number of most recent outcomes of each driver request (solve, reveal
+    /// and settle) the driver's success rates are computed over.
+    #[clap(long, env, default_value = "20")]
+    pub driver_reputation_window: usize,
+
+    /// Drivers get excluded from auctions for a cooling-off period once the
+    /// success rate of any of their requests drops below this value. 0
+    /// disables the deny-listing. Drivers never all get excluded at once.
+    #[clap(long, env, default_value = "0")]
+    pub driver_min_success_rate: f64,
+
+    /// How long drivers with a low success rate are excluded from auctions.
+    #[clap(
+        long,
+        env,
+        default_value = "10m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub driver_cooldown: Duration,
+
+    /// Address of the admin API which exposes the driver reputations and
+    /// allows lifting their deny-listing. The API is not authenticated so it
+    /// is only served if configured and should only be reachable from trusted
+    /// networks, e.g. `127.0.0.1:9591`.
+    #[clap(long, env)]
+    pub admin_address: Option<SocketAddr>,
+
     /// The amount of time that the autopilot waits looking for a settlement
     /// transaction onchain after the driver acknowledges the receipt of a
     /// settlement.
@@ -189,6 +242,23 @@ pub struct Arguments {
     #[clap(long, env)]
     pub shadow: Option<Url>,
 
+    /// Run the autopilot in replay mode for the auction with the given ID. The
+    /// stored auction gets sent to the configured drivers and their solutions
+    /// are compared with the recorded solver competition. The autopilot exits
+    /// once the report is written.
+    #[clap(long, env)]
+    pub replay: Option<i64>,
+
+    /// URL of an anvil node the drivers simulate against. In replay mode it
+    /// gets reset to a fork of `node_url` at the block the replayed auction
+    /// started at.
+    #[clap(long, env)]
+    pub replay_fork_url: Option<Url>,
+
+    /// File the replay report gets written to. If unset the report is logged.
+    #[clap(long, env)]
+    pub replay_report: Option<PathBuf>,
+
     /// Time solvers have to compute a score per auction.
     #[clap(
         long,
@@ -216,6 +286,16 @@ pub struct Arguments {
     #[clap(long, env, use_value_delimiter = true)]
     pub protocol_fee_exempt_addresses: Vec<H160>,
 
+    /// Path to a TOML file that can override `drivers`, `solve_deadline`,
+    /// `fee_policies` and `protocol_fee_exempt_addresses`. The file is watched
+    /// for changes, which get applied between auctions without a restart.
+    #[clap(long, env)]
+    pub config: Option<PathBuf>,
+
+    /// How often the config file gets checked for changes.
+    #[clap(long, env, default_value = "10s", value_parser = humantime::parse_duration)]
+    pub config_update_interval: Duration,
+
     /// Arguments for uploading information to S3.
     #[clap(flatten)]
     pub s3: infra::persistence::cli::S3,
@@ -229,6 +309,34 @@ pub struct Arguments {
     /// `order_events` database table.
     #[clap(long, env, default_value = "30d", value_parser = humantime::parse_duration)]
     pub order_events_cleanup_threshold: Duration,
+
+    /// First block of the first accounting period for which solver reward
+    /// statements get computed. Rewards are only computed if this is set.
+    #[clap(long, env)]
+    pub solver_rewards_start_block: Option<u64>,
+
+    /// Length of a reward accounting period in blocks.
+    #[clap(long, env, default_value = "50400")]
+    pub solver_rewards_period: u64,
+
+    /// Number of blocks to wait after an accounting period ended before its
+    /// rewards get computed.
+    #[clap(long, env, default_value = "64")]
+    pub solver_rewards_confirmations: u64,
+
+    /// Number of blocks to wait on top of the confirmations for settlements
+    /// that still haven't been observed. Auctions whose settlement isn't
+    /// observed by then get left out of the accounting period.
+    #[clap(long, env, default_value = "300")]
+    pub solver_rewards_max_observation_delay: u64,
+
+    /// Maximum reward a solver can earn per auction in ETH.
+    #[clap(long, env, default_value = "0.012", value_parser = shared::arguments::wei_from_ether)]
+    pub solver_rewards_upper_cap: U256,
+
+    /// Maximum penalty a solver can get per auction in ETH.
+    #[clap(long, env, default_value = "0.01", value_parser = shared::arguments::wei_from_ether)]
+    pub solver_rewards_lower_cap: U256,
 }
 
 impl std::fmt::Display for Arguments {
@@ -253,19 +361,36 @@ impl std::fmt::Display for Arguments {
             banned_users,
             max_auction_age,
             limit_order_price_factor,
+            max_limit_orders_per_auction,
+            max_limit_orders_per_owner,
             trusted_tokens_url,
             trusted_tokens,
             trusted_tokens_update_interval,
             drivers,
             submission_deadline,
             additional_deadline_for_rewards,
+            max_winners_per_auction,
+            fairness_tolerance,
+            driver_reputation_window,
+            driver_min_success_rate,
+            driver_cooldown,
+            admin_address,
             shadow,
+            replay,
+            replay_fork_url,
+            replay_report,
             solve_deadline,
             fee_policies,
             enable_multiple_fees,
             fee_policy_max_partner_fee,
             order_events_cleanup_interval,
             order_events_cleanup_threshold,
+            solver_rewards_start_block,
+            solver_rewards_period,
+            solver_rewards_confirmations,
+            solver_rewards_max_observation_delay,
+            solver_rewards_upper_cap,
+            solver_rewards_lower_cap,
             db_url,
             insert_batch_size,
             native_price_estimation_results_required,
@@ -273,6 +398,8 @@ impl std::fmt::Display for Arguments {
             max_settlement_transaction_wait,
             s3,
             protocol_fee_exempt_addresses,
+            config,
+            config_update_interval,
         } = self;
 
         write!(f, "{}", shared)?;
@@ -308,6 +435,12 @@ impl std::fmt::Display for Arguments {
             "limit_order_price_factor: {:?}",
             limit_order_price_factor
         )?;
+        display_option(
+            f,
+            "max_limit_orders_per_auction",
+            max_limit_orders_per_auction,
+        )?;
+        display_option(f, "max_limit_orders_per_owner", max_limit_orders_per_owner)?;
         display_option(f, "trusted_tokens_url", trusted_tokens_url)?;
         writeln!(f, "trusted_tokens: {:?}", trusted_tokens)?;
         writeln!(
@@ -322,7 +455,16 @@ impl std::fmt::Display for Arguments {
             "additional_deadline_for_rewards: {}",
             additional_deadline_for_rewards
         )?;
+        writeln!(f, "max_winners_per_auction: {}", max_winners_per_auction)?;
+        display_option(f, "fairness_tolerance", fairness_tolerance)?;
+        writeln!(f, "driver_reputation_window: {}", driver_reputation_window)?;
+        writeln!(f, "driver_min_success_rate: {}", driver_min_success_rate)?;
+        writeln!(f, "driver_cooldown: {:?}", driver_cooldown)?;
+        display_option(f, "admin_address", admin_address)?;
         display_option(f, "shadow", shadow)?;
+        display_option(f, "replay", replay)?;
+        display_option(f, "replay_fork_url", replay_fork_url)?;
+        writeln!(f, "replay_report: {:?}", replay_report)?;
         writeln!(f, "solve_deadline: {:?}", solve_deadline)?;
         writeln!(f, "fee_policies: {:?}", fee_policies)?;
         writeln!(
@@ -330,6 +472,8 @@ impl std::fmt::Display for Arguments {
             "protocol_fee_exempt_addresses: {:?}",
             protocol_fee_exempt_addresses
         )?;
+        writeln!(f, "config: {:?}", config)?;
+        writeln!(f, "config_update_interval: {:?}", config_update_interval)?;
         writeln!(f, "enable_multiple_fees: {:?}", enable_multiple_fees)?;
         writeln!(
             f,
@@ -346,6 +490,20 @@ impl std::fmt::Display for Arguments {
             "order_events_cleanup_threshold: {:?}",
             order_events_cleanup_threshold
         )?;
+        display_option(f, "solver_rewards_start_block", solver_rewards_start_block)?;
+        writeln!(f, "solver_rewards_period: {}", solver_rewards_period)?;
+        writeln!(
+            f,
+            "solver_rewards_confirmations: {}",
+            solver_rewards_confirmations
+        )?;
+        writeln!(
+            f,
+            "solver_rewards_max_observation_delay: {}",
+            solver_rewards_max_observation_delay
+        )?;
+        writeln!(f, "solver_rewards_upper_cap: {}", solver_rewards_upper_cap)?;
+        writeln!(f, "solver_rewards_lower_cap: {}", solver_rewards_lower_cap)?;
         writeln!(f, "insert_batch_size: {}", insert_batch_size)?;
         writeln!(
             f,
@@ -363,6 +521,120 @@ impl std::fmt::Display for Arguments {
     }
 }
 
+/// A driver participating in the solver competition in the following format:
+/// `<NAME>|<URL>[|<VIEW>]`. The optional view restricts the part of the
+/// auction that gets sent to the driver, see [`AuctionView`].
+/// Examples:
+/// - A driver receiving the full auction:
+/// mysolver|http://localhost:11088/mysolver
+///
+/// - A driver only receiving market orders trading stablecoins:
+/// stables|http://localhost:11088/stables|tokens=0xA0b8...;0x6B17...:classes=market
+#[derive(Debug, Clone, PartialEq)]
+pub struct Driver {
+    pub name: String,
+    pub url: Url,
+    pub view: AuctionView,
+}
+
+/// Conditions restricting the orders of the auction that get sent to a driver.
+/// Conditions are `key=value` pairs separated by `:`, lists are separated by
+/// `;`. Unset conditions don't restrict the auction.
+///
+/// Supported conditions:
+/// - `tokens=<token>;...`: only orders trading exclusively these tokens
+/// - `deniedTokens=<token>;...`: no orders trading any of these tokens
+/// - `classes=<class>;...`: only orders of these classes (`market`, `limit`)
+/// - `maxOrders=<count>`: at most this many orders, preferring market orders
+/// - `liquidityOrders=<bool>`: whether liquidity orders are included, `true` by
+///   default
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuctionView {
+    pub tokens: Vec<H160>,
+    pub denied_tokens: Vec<H160>,
+    pub classes: Vec<AuctionViewOrderClass>,
+    pub max_orders: Option<usize>,
+    pub liquidity_orders: bool,
+}
+
+impl Default for AuctionView {
+    fn default() -> Self {
+        Self {
+            tokens: Vec::new(),
+            denied_tokens: Vec::new(),
+            classes: Vec::new(),
+            max_orders: None,
+            liquidity_orders: true,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuctionViewOrderClass {
+    Market,
+    Limit,
+}
+
+impl Conditions for AuctionView {
+    fn add_condition(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "tokens" => self
+                .tokens
+                .extend(parse_list("token", value, H160::from_str)?),
+            "deniedTokens" => {
+                self.denied_tokens
+                    .extend(parse_list("denied token", value, H160::from_str)?)
+            }
+            "classes" => self
+                .classes
+                .extend(parse_list("order class", value, |class| {
+                    AuctionViewOrderClass::from_str(class, true)
+                })?),
+            "maxOrders" => {
+                self.max_orders = Some(parse_value("max orders", value, usize::from_str)?)
+            }
+            "liquidityOrders" => {
+                self.liquidity_orders = parse_value("liquidity flag", value, bool::from_str)?
+            }
+            _ => anyhow::bail!("invalid auction view condition: {key}"),
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Driver {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '|');
+        let name = parts.next().context("missing driver name")?;
+        let url = parts
+            .next()
+            .context("not enough arguments for driver")?
+            .parse()?;
+        let view = match parts.next() {
+            Some(conditions) => AuctionView::from_conditions(conditions.split(':'))?,
+            None => AuctionView::default(),
+        };
+        Ok(Self {
+            name: name.to_owned(),
+            url,
+            view,
+        })
+    }
+}
+
+impl std::fmt::Display for Driver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.name, self.url)?;
+        if self.view != AuctionView::default() {
+            write!(f, " {:?}", self.view)?;
+        }
+        Ok(())
+    }
+}
+
 /// A fee policy to be used for orders base on it's class.
 /// Examples:
 /// - Surplus with a high enough cap for limit orders
@@ -379,10 +651,16 @@ impl std::fmt::Display for Arguments {
 ///
 /// - Volume based fee for any order class:
 /// volume:0.1:any
+///
+/// A policy can optionally be restricted to a scope by appending `key=value`
+/// conditions, see [`FeePolicyScope`]:
+/// - Lower volume based fee for trades between two stablecoins:
+/// volume:0.0001:any:sellToken=0xA0b8...;0x6B17...:buyToken=0xA0b8...;0x6B17...
 #[derive(Debug, Clone)]
 pub struct FeePolicy {
     pub fee_policy_kind: FeePolicyKind,
     pub fee_policy_order_class: FeePolicyOrderClass,
+    pub fee_policy_scope: FeePolicyScope,
 }
 
 #[derive(clap::Parser, Debug, Clone)]
@@ -413,6 +691,104 @@ pub enum FeePolicyOrderClass {
     Any,
 }
 
+/// Conditions an order has to satisfy for a fee policy to apply to it. Unset
+/// conditions match every order. Lists are separated by `;`.
+///
+/// Supported conditions:
+/// - `sellToken=<token>;...`: the order sells one of the tokens
+/// - `buyToken=<token>;...`: the order buys one of the tokens
+/// - `pair=<token>/<token>;...`: the order trades one of the token pairs, in
+///   either direction
+/// - `minNativeValue=<wei>`: the sell amount is worth at least this much in the
+///   native token
+/// - `maxNativeValue=<wei>`: the sell amount is worth at most this much in the
+///   native token
+/// - `appCode=<code>;...`: the order's app data has one of the app codes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeePolicyScope {
+    pub sell_tokens: Vec<H160>,
+    pub buy_tokens: Vec<H160>,
+    pub pairs: Vec<(H160, H160)>,
+    pub min_native_value: Option<U256>,
+    pub max_native_value: Option<U256>,
+    pub app_codes: Vec<String>,
+}
+
+impl Conditions for FeePolicyScope {
+    fn add_condition(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "sellToken" => {
+                self.sell_tokens
+                    .extend(parse_list("sell token", value, H160::from_str)?)
+            }
+            "buyToken" => self
+                .buy_tokens
+                .extend(parse_list("buy token", value, H160::from_str)?),
+            "pair" => self.pairs.extend(parse_list("token pair", value, |pair| {
+                let (token0, token1) = pair.split_once('/').context("missing '/'")?;
+                anyhow::Ok((token0.parse::<H160>()?, token1.parse::<H160>()?))
+            })?),
+            "minNativeValue" => {
+                self.min_native_value =
+                    Some(parse_value("min native value", value, U256::from_dec_str)?)
+            }
+            "maxNativeValue" => {
+                self.max_native_value =
+                    Some(parse_value("max native value", value, U256::from_dec_str)?)
+            }
+            "appCode" => self
+                .app_codes
+                .extend(value.split(';').map(ToString::to_string)),
+            _ => anyhow::bail!("invalid fee policy scope condition: {key}"),
+        }
+        Ok(())
+    }
+}
+
+/// Settings made up of `key=value` conditions separated by `:`, like
+/// [`AuctionView`] and [`FeePolicyScope`]. Unset conditions don't restrict
+/// anything.
+pub trait Conditions: Default + PartialEq {
+    /// Restricts the settings further by a single condition.
+    fn add_condition(&mut self, key: &str, value: &str) -> anyhow::Result<()>;
+
+    fn from_conditions<'a>(conditions: impl IntoIterator<Item = &'a str>) -> anyhow::Result<Self> {
+        let mut settings = Self::default();
+        for condition in conditions {
+            let (key, value) = condition
+                .split_once('=')
+                .with_context(|| format!("invalid condition: {condition}"))?;
+            settings.add_condition(key, value)?;
+        }
+        Ok(settings)
+    }
+
+    /// Whether no condition is set.
+    fn is_unrestricted(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn parse_value<T, E: std::fmt::Display>(
+    name: &str,
+    value: &str,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> anyhow::Result<T> {
+    parse(value).map_err(|err| anyhow::anyhow!("invalid {name} {value}: {err}"))
+}
+
+/// Parses a list of values separated by `;`.
+fn parse_list<T, E: std::fmt::Display>(
+    name: &str,
+    value: &str,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> anyhow::Result<Vec<T>> {
+    value
+        .split(';')
+        .map(|value| parse_value(name, value, &parse))
+        .collect()
+}
+
 impl FromStr for FeePolicy {
     type Err = anyhow::Error;
 
@@ -471,10 +847,12 @@ impl FromStr for FeePolicy {
             true,
         )
         .map_err(|e| anyhow::anyhow!("invalid fee policy order class: {}", e))?;
+        let fee_policy_scope = FeePolicyScope::from_conditions(parts)?;
 
         Ok(FeePolicy {
             fee_policy_kind,
             fee_policy_order_class,
+            fee_policy_scope,
         })
     }
 }
@@ -506,4 +884,74 @@ mod test {
                 .contains("Factor must be in the range [0, 1)"),)
         }
     }
+
+    #[test]
+    fn test_fee_policy_scope() {
+        let policy = FeePolicy::from_str(
+            "volume:0.0001:any:sellToken=0x0000000000000000000000000000000000000001;\
+             0x0000000000000000000000000000000000000002:\
+             pair=0x0000000000000000000000000000000000000003/\
+             0x0000000000000000000000000000000000000004:minNativeValue=1000:appCode=CoW Swap",
+        )
+        .unwrap();
+        let scope = policy.fee_policy_scope;
+        assert_eq!(
+            scope.sell_tokens,
+            vec![H160::from_low_u64_be(1), H160::from_low_u64_be(2)]
+        );
+        assert!(scope.buy_tokens.is_empty());
+        assert_eq!(
+            scope.pairs,
+            vec![(H160::from_low_u64_be(3), H160::from_low_u64_be(4))]
+        );
+        assert_eq!(scope.min_native_value, Some(1000.into()));
+        assert_eq!(scope.max_native_value, None);
+        assert_eq!(scope.app_codes, vec!["CoW Swap".to_string()]);
+
+        for policy in [
+            "volume:0.1:any:sellToken",
+            "volume:0.1:any:buyToken=0x01",
+            "volume:0.1:any:pair=0x0000000000000000000000000000000000000001",
+            "volume:0.1:any:minNativeValue=1.5",
+            "volume:0.1:any:owner=0x0000000000000000000000000000000000000001",
+        ] {
+            assert!(FeePolicy::from_str(policy).is_err(), "{policy}");
+        }
+    }
+
+    #[test]
+    fn test_driver_auction_view() {
+        let driver = Driver::from_str("solver|http://localhost:11088/solver").unwrap();
+        assert_eq!(driver.name, "solver");
+        assert_eq!(driver.view, AuctionView::default());
+
+        let driver = Driver::from_str(
+            "stables|http://localhost:11088/stables|tokens=0x0000000000000000000000000000000000000001;\
+             0x0000000000000000000000000000000000000002:deniedTokens=\
+             0x0000000000000000000000000000000000000003:classes=market;Limit:maxOrders=100:\
+             liquidityOrders=false",
+        )
+        .unwrap();
+        assert_eq!(driver.url.as_str(), "http://localhost:11088/stables");
+        assert_eq!(
+            driver.view,
+            AuctionView {
+                tokens: vec![H160::from_low_u64_be(1), H160::from_low_u64_be(2)],
+                denied_tokens: vec![H160::from_low_u64_be(3)],
+                classes: vec![AuctionViewOrderClass::Market, AuctionViewOrderClass::Limit],
+                max_orders: Some(100),
+                liquidity_orders: false,
+            }
+        );
+
+        for driver in [
+            "solver",
+            "solver|localhost",
+            "solver|http://localhost|classes=liquidity",
+            "solver|http://localhost|maxOrders=-1",
+            "solver|http://localhost|owner=0x0000000000000000000000000000000000000001",
+        ] {
+            assert!(Driver::from_str(driver).is_err(), "{driver}");
+        }
+    }
 }
@@ -2,7 +2,7 @@ use {crate::domain, shared::remaining_amounts};
 
 pub fn to_domain(
     order: model::order::Order,
-    protocol_fees: Vec<domain::fee::Policy>,
+    protocol_fees: Vec<domain::fee::ScopedPolicy>,
 ) -> domain::Order {
     let remaining_order = remaining_amounts::Order::from(order.clone());
     let order_is_untouched = remaining_order.executed_amount.is_zero();
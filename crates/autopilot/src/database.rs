@@ -4,7 +4,7 @@ use {
     tracing::Instrument,
 };
 
-mod auction;
+pub mod auction;
 pub mod competition;
 pub mod ethflow_events;
 pub mod events;
@@ -14,6 +14,7 @@ pub mod onchain_order_events;
 pub mod order_events;
 mod quotes;
 pub mod recent_settlements;
+pub mod solver_rewards;
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -3,16 +3,32 @@ use {
     crate::{boundary, infra::persistence::dto},
     anyhow::{Context, Result},
     chrono::{DateTime, Utc},
+    database::byte_array::ByteArray,
     futures::{StreamExt, TryStreamExt},
-    model::{order::Order, quote::QuoteId},
+    model::{order::Order, quote::QuoteId, solver_competition::SolverCompetitionAPI},
+    number::conversions::big_decimal_to_u256,
+    primitive_types::{H160, U256},
     shared::{
         db_order_conversions::full_order_into_model_order,
         event_storing_helpers::{create_db_search_parameters, create_quote_row},
         order_quoting::{QuoteData, QuoteSearchParameters, QuoteStoring},
     },
-    std::ops::DerefMut,
+    std::{
+        collections::{BTreeMap, HashMap},
+        ops::DerefMut,
+    },
 };
 
+/// An auction from the past together with the record of its competition.
+pub struct HistoricalAuction {
+    /// The orders of the auction with the amounts they were executed with at
+    /// the time of the auction.
+    pub orders: boundary::SolvableOrders,
+    /// The native prices of the tokens traded in the auction.
+    pub prices: BTreeMap<H160, U256>,
+    pub competition: SolverCompetitionAPI,
+}
+
 #[async_trait::async_trait]
 impl QuoteStoring for Postgres {
     async fn save(&self, data: QuoteData) -> Result<QuoteId> {
@@ -105,4 +121,84 @@ impl Postgres {
         ex.commit().await?;
         Ok(id)
     }
+
+    /// Loads a past auction by its ID. Trades that happened after the auction
+    /// started are not counted towards the executed amounts of its orders so
+    /// the auction can be solved again.
+    pub async fn historical_auction(
+        &self,
+        auction_id: dto::AuctionId,
+    ) -> Result<Option<HistoricalAuction>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["historical_auction"])
+            .start_timer();
+
+        let mut ex = self.pool.begin().await?;
+        let Some(competition) = Self::find_competition(auction_id, &mut ex).await? else {
+            return Ok(None);
+        };
+        let block = competition.common.auction_start_block;
+        let uids = match database::auction_orders::fetch(&mut ex, auction_id).await? {
+            Some(uids) => uids,
+            None => competition
+                .common
+                .auction
+                .orders
+                .iter()
+                .map(|uid| ByteArray(uid.0))
+                .collect(),
+        };
+
+        let prices = database::auction_prices::fetch(&mut ex, auction_id).await?;
+        // Fall back to the prices recorded with the competition for auctions
+        // whose prices were not stored separately.
+        let prices = if prices.is_empty() {
+            competition.common.auction.prices.clone()
+        } else {
+            prices
+                .into_iter()
+                .map(|price| {
+                    Ok((
+                        H160(price.token.0),
+                        big_decimal_to_u256(&price.price).context("price is not a u256")?,
+                    ))
+                })
+                .collect::<Result<_>>()?
+        };
+
+        let executed_after: HashMap<_, _> =
+            database::trades::executed_after(&mut ex, &uids, block.try_into()?)
+                .await?
+                .into_iter()
+                .map(|executed| (executed.order_uid, executed))
+                .collect();
+        let orders: Vec<Order> = database::orders::many_full_orders(&mut ex, &uids)
+            .map(|result| {
+                let mut order = result?;
+                if let Some(executed) = executed_after.get(&order.uid) {
+                    order.sum_sell -= &executed.sell_amount;
+                    order.sum_buy -= &executed.buy_amount;
+                    order.sum_fee -= &executed.fee_amount;
+                }
+                full_order_into_model_order(order)
+            })
+            .try_collect()
+            .await?;
+        let quotes = self
+            .read_quotes(orders.iter().map(|order| &order.metadata.uid))
+            .await?;
+
+        Ok(Some(HistoricalAuction {
+            orders: boundary::SolvableOrders {
+                orders,
+                quotes,
+                // Only settlements up to the start of the auction are known to
+                // it.
+                latest_settlement_block: block,
+            },
+            prices,
+            competition,
+        }))
+    }
 }
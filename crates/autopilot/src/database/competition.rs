@@ -7,6 +7,8 @@ use {
         byte_array::ByteArray,
         settlement_call_data::SettlementCallData,
         settlement_scores::Score,
+        Address,
+        PgTransaction,
     },
     derivative::Derivative,
     model::solver_competition::{SolverCompetitionAPI, SolverCompetitionDB},
@@ -20,25 +22,33 @@ use {
 #[derivative(Debug)]
 pub struct Competition {
     pub auction_id: AuctionId,
-    pub winner: H160,
-    pub winning_score: U256,
-    pub reference_score: U256,
+    /// Solvers that got asked to settle their solution. Auctions can have
+    /// multiple winners if their solutions don't overlap.
+    pub winners: Vec<Winner>,
     /// Addresses to which the CIP20 participation rewards will be payed out.
     /// Usually the same as the solver addresses.
     pub participants: HashSet<H160>,
     /// External prices for auction.
     pub prices: BTreeMap<H160, U256>,
-    /// Winner receives performance rewards if a settlement is finalized on
+    /// Winners receive performance rewards if their settlement is finalized on
     /// chain before this block height.
     pub block_deadline: u64,
     pub competition_simulation_block: u64,
+    pub competition_table: SolverCompetitionDB,
+}
+
+#[derive(Clone, Default, Derivative)]
+#[derivative(Debug)]
+pub struct Winner {
+    pub solver: H160,
+    pub score: U256,
+    pub reference_score: U256,
     /// Winner settlement call data
     #[derivative(Debug(format_with = "shared::debug_bytes"))]
     pub call_data: Vec<u8>,
     /// Uninternalized winner settlement call data
     #[derivative(Debug(format_with = "shared::debug_bytes"))]
     pub uninternalized_call_data: Vec<u8>,
-    pub competition_table: SolverCompetitionDB,
 }
 
 impl super::Postgres {
@@ -56,25 +66,7 @@ impl super::Postgres {
             .await
             .context("solver_competition::save")?;
 
-        database::settlement_scores::insert(
-            &mut ex,
-            Score {
-                auction_id: competition.auction_id,
-                winner: ByteArray(competition.winner.0),
-                winning_score: u256_to_big_decimal(&competition.winning_score),
-                reference_score: u256_to_big_decimal(&competition.reference_score),
-                block_deadline: competition
-                    .block_deadline
-                    .try_into()
-                    .context("convert block deadline")?,
-                simulation_block: competition
-                    .competition_simulation_block
-                    .try_into()
-                    .context("convert simulation block")?,
-            },
-        )
-        .await
-        .context("settlement_scores::insert")?;
+        insert_winners(&mut ex, competition, |_| true).await?;
 
         database::auction_participants::insert(
             &mut ex,
@@ -91,32 +83,9 @@ impl super::Postgres {
         .await
         .context("auction_participants::insert")?;
 
-        database::auction_prices::insert(
-            &mut ex,
-            competition
-                .prices
-                .iter()
-                .map(|(token, price)| AuctionPrice {
-                    auction_id: competition.auction_id,
-                    token: ByteArray(token.0),
-                    price: u256_to_big_decimal(price),
-                })
-                .collect::<Vec<_>>()
-                .as_slice(),
-        )
-        .await
-        .context("auction_prices::insert")?;
-
-        database::settlement_call_data::insert(
-            &mut ex,
-            SettlementCallData {
-                auction_id: competition.auction_id,
-                call_data: competition.call_data.clone(),
-                uninternalized_call_data: competition.uninternalized_call_data.clone(),
-            },
-        )
-        .await
-        .context("settlement_call_data::insert")?;
+        database::auction_prices::insert(&mut ex, auction_prices(competition, |_| true).as_slice())
+            .await
+            .context("auction_prices::insert")?;
 
         database::auction_orders::insert(
             &mut ex,
@@ -136,6 +105,52 @@ impl super::Postgres {
         ex.commit().await.context("commit")
     }
 
+    /// Updates a previously saved competition after the auction got handed to
+    /// a different solver (or got settled). The auction itself and its
+    /// participants don't change, so only newly added winners and prices of
+    /// newly traded tokens get stored. Winners that failed to settle keep
+    /// their scores so that they get penalized.
+    pub async fn update_competition(&self, competition: &Competition) -> anyhow::Result<()> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["update_competition"])
+            .start_timer();
+
+        let json = &serde_json::to_value(&competition.competition_table)?;
+
+        let mut ex = self.pool.begin().await.context("begin")?;
+
+        database::solver_competition::update(&mut ex, competition.auction_id, json)
+            .await
+            .context("solver_competition::update")?;
+
+        let stored_winners = database::settlement_scores::fetch(&mut ex, competition.auction_id)
+            .await
+            .context("settlement_scores::fetch")?
+            .into_iter()
+            .map(|score| score.winner)
+            .collect::<HashSet<_>>();
+        insert_winners(&mut ex, competition, |winner| {
+            !stored_winners.contains(winner)
+        })
+        .await?;
+
+        let stored_prices = database::auction_prices::fetch(&mut ex, competition.auction_id)
+            .await
+            .context("auction_prices::fetch")?
+            .into_iter()
+            .map(|price| price.token)
+            .collect::<HashSet<_>>();
+        database::auction_prices::insert(
+            &mut ex,
+            auction_prices(competition, |token| !stored_prices.contains(token)).as_slice(),
+        )
+        .await
+        .context("auction_prices::insert")?;
+
+        ex.commit().await.context("commit")
+    }
+
     pub async fn find_competition(
         auction_id: AuctionId,
         ex: &mut PgConnection,
@@ -154,6 +169,68 @@ impl super::Postgres {
     }
 }
 
+/// Stores the scores and call data of the included winners.
+async fn insert_winners(
+    ex: &mut PgTransaction<'_>,
+    competition: &Competition,
+    include: impl Fn(&Address) -> bool,
+) -> anyhow::Result<()> {
+    for winner in competition
+        .winners
+        .iter()
+        .filter(|winner| include(&ByteArray(winner.solver.0)))
+    {
+        database::settlement_scores::insert(
+            ex,
+            Score {
+                auction_id: competition.auction_id,
+                winner: ByteArray(winner.solver.0),
+                winning_score: u256_to_big_decimal(&winner.score),
+                reference_score: u256_to_big_decimal(&winner.reference_score),
+                block_deadline: competition
+                    .block_deadline
+                    .try_into()
+                    .context("convert block deadline")?,
+                simulation_block: competition
+                    .competition_simulation_block
+                    .try_into()
+                    .context("convert simulation block")?,
+            },
+        )
+        .await
+        .context("settlement_scores::insert")?;
+
+        database::settlement_call_data::insert(
+            ex,
+            SettlementCallData {
+                auction_id: competition.auction_id,
+                solver: ByteArray(winner.solver.0),
+                call_data: winner.call_data.clone(),
+                uninternalized_call_data: winner.uninternalized_call_data.clone(),
+            },
+        )
+        .await
+        .context("settlement_call_data::insert")?;
+    }
+    Ok(())
+}
+
+fn auction_prices(
+    competition: &Competition,
+    include: impl Fn(&Address) -> bool,
+) -> Vec<AuctionPrice> {
+    competition
+        .prices
+        .iter()
+        .map(|(token, price)| AuctionPrice {
+            auction_id: competition.auction_id,
+            token: ByteArray(token.0),
+            price: u256_to_big_decimal(price),
+        })
+        .filter(|price| include(&price.token))
+        .collect()
+}
+
 fn deserialize_solver_competition(
     json: JsonValue,
     auction_id: model::auction::AuctionId,
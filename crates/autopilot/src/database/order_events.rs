@@ -22,6 +22,7 @@ pub async fn store_order_events(
     ex: &mut PgConnection,
     order_uids: Vec<domain::OrderUid>,
     label: OrderEventLabel,
+    reason: Option<&str>,
     timestamp: DateTime<Utc>,
 ) {
     let start = Instant::now();
@@ -35,6 +36,7 @@ pub async fn store_order_events(
                 order_uid: ByteArray(uid.0),
                 timestamp,
                 label,
+                reason: reason.map(str::to_owned),
             };
 
             order_events::insert_order_event(&mut ex, &event).await?;
@@ -44,7 +46,15 @@ pub async fn store_order_events(
     };
 
     match insert.await {
-        Ok(_) => tracing::debug!(?label, count, elapsed = ?start.elapsed(), "stored order events"),
-        Err(err) => tracing::warn!(?label, count, ?err, "failed to insert order events"),
+        Ok(_) => {
+            tracing::debug!(?label, ?reason, count, elapsed = ?start.elapsed(), "stored order events")
+        }
+        Err(err) => tracing::warn!(
+            ?label,
+            ?reason,
+            count,
+            ?err,
+            "failed to insert order events"
+        ),
     }
 }
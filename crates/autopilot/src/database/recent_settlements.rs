@@ -1,9 +1,14 @@
-use {anyhow::Context, primitive_types::H256};
+use {
+    anyhow::Context,
+    database::byte_array::ByteArray,
+    primitive_types::{H160, H256},
+};
 
 impl super::Postgres {
     pub async fn find_tx_hash_by_auction_id(
         &self,
         auction_id: i64,
+        solver: H160,
     ) -> anyhow::Result<Option<H256>> {
         let _timer = super::Metrics::get()
             .database_queries
@@ -11,9 +16,13 @@ impl super::Postgres {
             .start_timer();
 
         let mut ex = self.pool.acquire().await.context("acquire")?;
-        let hash = database::settlements::get_hash_by_auction_id(&mut ex, auction_id)
-            .await
-            .context("get_hash_by_auction_id")?;
+        let hash = database::settlements::get_hash_by_auction_id(
+            &mut ex,
+            auction_id,
+            &ByteArray(solver.0),
+        )
+        .await
+        .context("get_hash_by_auction_id")?;
         Ok(hash.map(|hash| H256(hash.0)))
     }
 }
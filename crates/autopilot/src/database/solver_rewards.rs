@@ -0,0 +1,47 @@
+use {
+    anyhow::{Context, Result},
+    database::solver_rewards::{self, AuctionOutcome, Period, Statement},
+};
+
+impl super::Postgres {
+    pub async fn latest_reward_period(&self) -> Result<Option<Period>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["latest_reward_period"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await.context("acquire")?;
+        solver_rewards::latest_period(&mut ex)
+            .await
+            .context("latest_period")
+    }
+
+    pub async fn reward_auction_outcomes(&self, period: &Period) -> Result<Vec<AuctionOutcome>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["reward_auction_outcomes"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await.context("acquire")?;
+        solver_rewards::auction_outcomes(&mut ex, period)
+            .await
+            .context("auction_outcomes")
+    }
+
+    pub async fn insert_reward_statements(
+        &self,
+        period: &Period,
+        statements: &[Statement],
+    ) -> Result<()> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["insert_reward_statements"])
+            .start_timer();
+
+        let mut ex = self.pool.begin().await.context("begin")?;
+        solver_rewards::insert(&mut ex, period, statements)
+            .await
+            .context("insert")?;
+        ex.commit().await.context("commit")
+    }
+}
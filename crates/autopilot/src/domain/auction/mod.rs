@@ -5,6 +5,9 @@ use {
 };
 
 pub mod order;
+mod view;
+
+pub use view::View;
 
 /// Replicates [`crate::model::Auction`].
 #[derive(Clone, Debug, PartialEq)]
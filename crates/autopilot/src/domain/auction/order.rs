@@ -11,7 +11,7 @@ pub struct Order {
     pub buy_token: H160,
     pub sell_amount: U256,
     pub buy_amount: U256,
-    pub protocol_fees: Vec<fee::Policy>,
+    pub protocol_fees: Vec<fee::ScopedPolicy>,
     pub side: Side,
     pub class: Class,
     pub valid_to: u32,
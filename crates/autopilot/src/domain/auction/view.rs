@@ -0,0 +1,175 @@
+//! Specialised solvers are only interested in a part of the auction. Sending
+//! them a trimmed auction keeps their requests small and avoids wasting their
+//! time on orders they would never settle.
+
+use {
+    super::{order::Class, Auction, Order},
+    crate::arguments,
+    primitive_types::H160,
+};
+
+/// The part of the auction that gets sent to a driver.
+#[derive(Clone, Debug, PartialEq)]
+pub struct View {
+    /// Only orders trading exclusively these tokens are included.
+    tokens: Vec<H160>,
+    /// Orders trading any of these tokens are excluded.
+    denied_tokens: Vec<H160>,
+    classes: Vec<Class>,
+    max_orders: Option<usize>,
+    liquidity_orders: bool,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        arguments::AuctionView::default().into()
+    }
+}
+
+impl View {
+    /// Whether the view covers the whole auction.
+    pub fn is_unrestricted(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Trims the auction down to the orders in view. Prices are kept for all
+    /// tokens.
+    pub fn apply(&self, auction: &Auction) -> Auction {
+        let mut orders: Vec<_> = auction
+            .orders
+            .iter()
+            .filter(|order| self.includes(order))
+            .cloned()
+            .collect();
+        if let Some(max_orders) = self.max_orders {
+            // Market orders are the most likely to get executed so they are kept
+            // first. The sort is stable to preserve the order within a class.
+            orders.sort_by_key(|order| match order.class {
+                Class::Market => 0,
+                Class::Limit => 1,
+                Class::Liquidity => 2,
+            });
+            orders.truncate(max_orders);
+        }
+        Auction {
+            block: auction.block,
+            latest_settlement_block: auction.latest_settlement_block,
+            orders,
+            prices: auction.prices.clone(),
+        }
+    }
+
+    fn includes(&self, order: &Order) -> bool {
+        let tokens = [order.sell_token, order.buy_token];
+        let class = match order.class {
+            Class::Liquidity => self.liquidity_orders,
+            _ => self.classes.is_empty() || self.classes.contains(&order.class),
+        };
+        class
+            && (self.tokens.is_empty() || tokens.iter().all(|token| self.tokens.contains(token)))
+            && !tokens
+                .iter()
+                .any(|token| self.denied_tokens.contains(token))
+    }
+}
+
+impl From<arguments::AuctionView> for View {
+    fn from(value: arguments::AuctionView) -> Self {
+        Self {
+            tokens: value.tokens,
+            denied_tokens: value.denied_tokens,
+            classes: value
+                .classes
+                .into_iter()
+                .map(|class| match class {
+                    arguments::AuctionViewOrderClass::Market => Class::Market,
+                    arguments::AuctionViewOrderClass::Limit => Class::Limit,
+                })
+                .collect(),
+            max_orders: value.max_orders,
+            liquidity_orders: value.liquidity_orders,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::domain::auction::order::{
+            AppDataHash,
+            BuyTokenDestination,
+            OrderUid,
+            SellTokenSource,
+            Side,
+            Signature,
+        },
+    };
+
+    fn order(uid: u8, sell_token: u64, buy_token: u64, class: Class) -> Order {
+        Order {
+            uid: OrderUid([uid; 56]),
+            sell_token: H160::from_low_u64_be(sell_token),
+            buy_token: H160::from_low_u64_be(buy_token),
+            sell_amount: 1.into(),
+            buy_amount: 1.into(),
+            protocol_fees: Vec::new(),
+            side: Side::Sell,
+            class,
+            valid_to: 0,
+            receiver: None,
+            owner: Default::default(),
+            partially_fillable: false,
+            executed: 0.into(),
+            pre_interactions: Vec::new(),
+            post_interactions: Vec::new(),
+            sell_token_balance: SellTokenSource::Erc20,
+            buy_token_balance: BuyTokenDestination::Erc20,
+            app_data: AppDataHash([0; 32]),
+            signature: Signature::PreSign,
+        }
+    }
+
+    fn uids(auction: &Auction) -> Vec<u8> {
+        auction.orders.iter().map(|order| order.uid.0[0]).collect()
+    }
+
+    #[test]
+    fn trims_auction_to_view() {
+        let auction = Auction {
+            block: 1,
+            latest_settlement_block: 0,
+            orders: vec![
+                order(1, 1, 2, Class::Limit),
+                order(2, 1, 2, Class::Market),
+                order(3, 1, 3, Class::Market),
+                order(4, 2, 1, Class::Liquidity),
+            ],
+            prices: Default::default(),
+        };
+
+        let view = View::default();
+        assert!(view.is_unrestricted());
+        assert_eq!(view.apply(&auction), auction);
+
+        let view = View::from(arguments::AuctionView {
+            tokens: vec![H160::from_low_u64_be(1), H160::from_low_u64_be(2)],
+            ..Default::default()
+        });
+        assert_eq!(uids(&view.apply(&auction)), [1, 2, 4]);
+
+        let view = View::from(arguments::AuctionView {
+            denied_tokens: vec![H160::from_low_u64_be(3)],
+            classes: vec![arguments::AuctionViewOrderClass::Market],
+            liquidity_orders: false,
+            ..Default::default()
+        });
+        assert_eq!(uids(&view.apply(&auction)), [2]);
+
+        let view = View::from(arguments::AuctionView {
+            max_orders: Some(3),
+            ..Default::default()
+        });
+        assert_eq!(uids(&view.apply(&auction)), [2, 3, 1]);
+    }
+}
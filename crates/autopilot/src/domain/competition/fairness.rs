@@ -0,0 +1,151 @@
+//! Ranking solutions by their total score alone allows a solution to win even
+//! though it executes some of its orders worse for the user than another
+//! solution would have. The fairness check compares the execution of every
+//! order with the best execution any other solution offered for it.
+
+use {super::Solution, crate::domain};
+
+/// Returns the orders the solution executes materially worse for the user
+/// than any of the other solutions does. Executions are compared by the
+/// exchange rate the user gets (bought per sold amount) which makes them
+/// comparable for partially filled orders as well. `tolerance` is the
+/// relative shortfall that is still considered fair.
+pub fn unfair_orders<'a>(
+    solution: &Solution,
+    others: impl Iterator<Item = &'a Solution> + Clone,
+    tolerance: f64,
+) -> Vec<domain::OrderUid> {
+    let mut unfair: Vec<_> = solution
+        .orders
+        .iter()
+        .filter(|(uid, executed)| {
+            others
+                .clone()
+                .filter_map(|other| other.orders.get(uid))
+                .any(|reference| shortfall(executed, reference) > tolerance)
+        })
+        .map(|(uid, _)| *uid)
+        .collect();
+    unfair.sort_by_key(|uid| uid.0);
+    unfair
+}
+
+/// Rejects the solutions that execute orders unfairly, see [`unfair_orders`].
+/// `ranked` is ordered by score, best first. Solutions only get compared with
+/// solutions that remain in the competition: the best ranked unfair solution
+/// gets rejected and the check is repeated without it. This way two solutions
+/// that each execute an order better than the other don't both get rejected
+/// and the last remaining solution is never rejected. Returns the indices of
+/// the rejected solutions together with their unfair orders.
+pub fn reject_unfair<'a>(
+    ranked: &[&'a Solution],
+    tolerance: f64,
+) -> Vec<(usize, Vec<domain::OrderUid>)> {
+    let mut remaining = (0..ranked.len()).collect::<Vec<_>>();
+    let mut rejected = Vec::new();
+    loop {
+        let unfair = remaining.iter().enumerate().find_map(|(position, index)| {
+            let others = remaining
+                .iter()
+                .filter(move |other| *other != index)
+                .map(|other| ranked[*other]);
+            let orders = unfair_orders(ranked[*index], others, tolerance);
+            (!orders.is_empty()).then_some((position, orders))
+        });
+        let Some((position, orders)) = unfair else {
+            return rejected;
+        };
+        rejected.push((remaining.remove(position), orders));
+    }
+}
+
+/// The relative amount by which the exchange rate of `executed` is worse than
+/// the one of `reference`.
+fn shortfall(executed: &super::TradedAmounts, reference: &super::TradedAmounts) -> f64 {
+    let executed_rate = executed.buy.0.full_mul(reference.sell.0).to_f64_lossy();
+    let reference_rate = reference.buy.0.full_mul(executed.sell.0).to_f64_lossy();
+    if reference_rate == 0. {
+        return 0.;
+    }
+    1. - executed_rate / reference_rate
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::domain::{
+            competition::{Score, TradedAmounts},
+            eth,
+        },
+    };
+
+    fn solution(solver: u8, orders: &[(u8, u64, u64)]) -> Solution {
+        Solution::new(
+            0,
+            eth::Address(eth::H160([solver; 20])),
+            Score::new(eth::Ether(1.into())).unwrap(),
+            orders
+                .iter()
+                .map(|(order, sell, buy)| {
+                    (
+                        domain::OrderUid([*order; 56]),
+                        TradedAmounts {
+                            sell: eth::TokenAmount((*sell).into()),
+                            buy: eth::TokenAmount((*buy).into()),
+                        },
+                    )
+                })
+                .collect(),
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn detects_orders_executed_worse_than_elsewhere() {
+        let winner = solution(1, &[(1, 100, 90), (2, 100, 100), (3, 100, 100)]);
+        let others = [
+            // Order 1 gets a 10% better rate.
+            solution(2, &[(1, 100, 100)]),
+            // Order 2 gets a 0.5% better rate on a partial fill.
+            solution(3, &[(2, 200, 201)]),
+            // Order 3 gets executed worse.
+            solution(4, &[(3, 100, 50)]),
+        ];
+
+        assert_eq!(
+            unfair_orders(&winner, others.iter(), 0.01),
+            vec![domain::OrderUid([1; 56])]
+        );
+        assert_eq!(
+            unfair_orders(&winner, others.iter(), 0.001),
+            vec![domain::OrderUid([1; 56]), domain::OrderUid([2; 56])]
+        );
+        assert!(unfair_orders(&winner, std::iter::empty(), 0.).is_empty());
+    }
+
+    #[test]
+    fn only_compares_with_remaining_solutions() {
+        // Each solution executes one order better than the other. Only the
+        // better ranked one gets rejected.
+        let a = solution(1, &[(1, 100, 100), (2, 100, 90)]);
+        let b = solution(2, &[(1, 100, 90), (2, 100, 100)]);
+        assert_eq!(
+            reject_unfair(&[&a, &b], 0.01),
+            vec![(0, vec![domain::OrderUid([2; 56])])]
+        );
+
+        // Once `a` is rejected for executing order 1 worse than `b`, `c` no
+        // longer has to match `a`'s better execution of order 2.
+        let a = solution(1, &[(1, 100, 90), (2, 100, 100)]);
+        let b = solution(2, &[(1, 100, 100)]);
+        let c = solution(3, &[(2, 100, 90)]);
+        assert_eq!(
+            reject_unfair(&[&a, &b, &c], 0.01),
+            vec![(0, vec![domain::OrderUid([1; 56])])]
+        );
+
+        assert!(reject_unfair(&[&a], 0.).is_empty());
+        assert!(reject_unfair(&[], 0.).is_empty());
+    }
+}
@@ -4,6 +4,10 @@ use {
     std::collections::HashMap,
 };
 
+mod fairness;
+
+pub use fairness::{reject_unfair, unfair_orders};
+
 type SolutionId = u64;
 
 pub struct Solution {
@@ -55,6 +59,130 @@ impl Solution {
     pub fn prices(&self) -> &HashMap<eth::TokenAddress, auction::Price> {
         &self.prices
     }
+
+    /// Whether both solutions can't be settled in the same auction because
+    /// they come from the same solver, trade the same orders or use
+    /// inconsistent clearing prices for the tokens they have in common.
+    pub fn conflicts_with(&self, other: &Solution) -> bool {
+        if self.solver == other.solver {
+            return true;
+        }
+        if self.orders.keys().any(|uid| other.orders.contains_key(uid)) {
+            return true;
+        }
+        // Clearing prices are only defined up to a common factor, so only the
+        // ratios of the prices of both solutions can be compared.
+        let common = self
+            .prices
+            .iter()
+            .filter_map(|(token, price)| Some((price.get().0, other.prices.get(token)?.get().0)))
+            .collect::<Vec<_>>();
+        common
+            .iter()
+            .zip(common.iter().skip(1))
+            .any(|((a, b), (c, d))| a.full_mul(*d) != c.full_mul(*b))
+    }
+}
+
+/// Selects the winners of an auction from solutions ranked by score (best
+/// first). Solutions are picked greedily as long as they don't conflict with
+/// any previously picked solution. Returns the indices of the winners.
+pub fn select_winners<'a>(
+    ranked: impl IntoIterator<Item = &'a Solution>,
+    max_winners: usize,
+) -> Vec<usize> {
+    let mut winners: Vec<(usize, &Solution)> = Vec::new();
+    for (index, solution) in ranked.into_iter().enumerate() {
+        if winners.len() >= max_winners {
+            break;
+        }
+        if winners
+            .iter()
+            .all(|(_, winner)| !solution.conflicts_with(winner))
+        {
+            winners.push((index, solution));
+        }
+    }
+    winners.into_iter().map(|(index, _)| index).collect()
+}
+
+/// Computes the reference score of every winner selected by
+/// [`select_winners`]. This is the total score the auction would have achieved
+/// without the winner's solver minus the scores of the other winners, i.e.
+/// the winner's marginal contribution to the auction.
+pub fn reference_scores(
+    ranked: &[&Solution],
+    winners: &[usize],
+    max_winners: usize,
+) -> Vec<eth::Ether> {
+    let total = |indices: &[usize], solutions: &[&Solution]| {
+        indices.iter().fold(eth::U256::zero(), |total, index| {
+            total.saturating_add(solutions[*index].score.0 .0)
+        })
+    };
+    winners
+        .iter()
+        .map(|winner| {
+            let solver = ranked[*winner].solver;
+            let without_solver = ranked
+                .iter()
+                .copied()
+                .filter(|solution| solution.solver != solver)
+                .collect::<Vec<_>>();
+            let counterfactual = total(
+                &select_winners(without_solver.iter().copied(), max_winners),
+                &without_solver,
+            );
+            let others = winners
+                .iter()
+                .copied()
+                .filter(|other| other != winner)
+                .collect::<Vec<_>>();
+            eth::Ether(counterfactual.saturating_sub(total(&others, ranked)))
+        })
+        .collect()
+}
+
+/// A solution that gets asked to settle the auction in case all solutions
+/// ranked higher failed to do so.
+#[derive(Debug, PartialEq)]
+pub struct Fallback {
+    /// Index of the solution in the ranking.
+    pub index: usize,
+    /// The score of the next solution in line, so rewards are computed as if
+    /// the solutions ranked higher never participated.
+    pub reference_score: eth::Ether,
+}
+
+/// Picks the next solution that gets asked to settle the auction from
+/// solutions ranked by score (best first), starting at index `next`. The best
+/// solution is always picked, the others only before the submission deadline
+/// and if all of their orders are still solvable.
+pub fn next_fallback(
+    ranked: &[&Solution],
+    next: usize,
+    block: u64,
+    submission_deadline: u64,
+    still_solvable: impl Fn(&Solution) -> bool,
+) -> Option<Fallback> {
+    for index in next..ranked.len() {
+        if index > 0 {
+            if block >= submission_deadline {
+                return None;
+            }
+            if !still_solvable(ranked[index]) {
+                continue;
+            }
+        }
+        return Some(Fallback {
+            index,
+            reference_score: ranked
+                .get(index + 1)
+                .map(|solution| solution.score.0)
+                .unwrap_or(eth::Ether(eth::U256::zero())),
+        });
+    }
+    None
 }
 
 pub struct TradedAmounts {
@@ -92,3 +220,146 @@ pub enum SolutionError {
     #[error(transparent)]
     InvalidPrice(#[from] auction::InvalidPrice),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solution(solver: u8, score: u64, orders: &[u8], prices: &[(u8, u64)]) -> Solution {
+        Solution::new(
+            0,
+            eth::Address(eth::H160([solver; 20])),
+            Score::new(eth::Ether(score.into())).unwrap(),
+            orders
+                .iter()
+                .map(|order| {
+                    (
+                        domain::OrderUid([*order; 56]),
+                        TradedAmounts {
+                            sell: eth::TokenAmount(1.into()),
+                            buy: eth::TokenAmount(1.into()),
+                        },
+                    )
+                })
+                .collect(),
+            prices
+                .iter()
+                .map(|(token, price)| {
+                    (
+                        eth::TokenAddress(eth::H160([*token; 20])),
+                        auction::Price::new(eth::Ether((*price).into())).unwrap(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn detects_conflicts() {
+        let base = solution(1, 10, &[1], &[(1, 100), (2, 200)]);
+        // Same solver.
+        assert!(base.conflicts_with(&solution(1, 10, &[2], &[(3, 100)])));
+        // Same order.
+        assert!(base.conflicts_with(&solution(2, 10, &[1], &[(3, 100)])));
+        // Inconsistent prices.
+        assert!(base.conflicts_with(&solution(2, 10, &[2], &[(1, 100), (2, 300)])));
+        // Prices that only differ by a common factor are consistent.
+        assert!(!base.conflicts_with(&solution(2, 10, &[2], &[(1, 50), (2, 100)])));
+        // A single shared token can't be priced inconsistently.
+        assert!(!base.conflicts_with(&solution(2, 10, &[2], &[(2, 1), (3, 100)])));
+    }
+
+    #[test]
+    fn selects_non_conflicting_winners() {
+        let solutions = [
+            solution(1, 30, &[1, 2], &[(1, 1), (2, 1)]),
+            solution(2, 20, &[2], &[(2, 1), (3, 1)]),
+            solution(3, 15, &[3], &[(3, 1), (4, 1)]),
+            solution(4, 10, &[4], &[(5, 1), (6, 1)]),
+        ];
+        assert_eq!(select_winners(&solutions, 1), vec![0]);
+        assert_eq!(select_winners(&solutions, 2), vec![0, 2]);
+        assert_eq!(select_winners(&solutions, 10), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn computes_reference_scores() {
+        let solutions = [
+            solution(1, 30, &[1, 2], &[]),
+            solution(2, 20, &[2], &[]),
+            solution(3, 15, &[3], &[]),
+            solution(1, 5, &[4], &[]),
+        ];
+        let ranked = solutions.iter().collect::<Vec<_>>();
+
+        let winners = select_winners(ranked.iter().copied(), 1);
+        assert_eq!(
+            reference_scores(&ranked, &winners, 1),
+            vec![eth::Ether(20.into())]
+        );
+
+        let winners = select_winners(ranked.iter().copied(), 10);
+        assert_eq!(winners, vec![0, 2]);
+        assert_eq!(
+            reference_scores(&ranked, &winners, 10),
+            // Without solver 1 the solutions of solvers 2 and 3 would have won
+            // (35) of which 15 are still achieved by the other winner.
+            // Without solver 3 the solution of solver 1 would have won (30) of
+            // which 30 are still achieved by the other winner.
+            vec![eth::Ether(20.into()), eth::Ether(0.into())]
+        );
+    }
+
+    #[test]
+    fn walks_down_the_ranking() {
+        let solutions = [
+            solution(1, 30, &[1], &[]),
+            solution(2, 20, &[2], &[]),
+            solution(3, 15, &[3], &[]),
+            solution(4, 10, &[4], &[]),
+        ];
+        let ranked = solutions.iter().collect::<Vec<_>>();
+        let fallback = |index, score: u64| {
+            Some(Fallback {
+                index,
+                reference_score: eth::Ether(score.into()),
+            })
+        };
+        let solvable = |_: &Solution| true;
+
+        // The reference score is the one of the next solution in line.
+        assert_eq!(next_fallback(&ranked, 0, 0, 10, solvable), fallback(0, 20));
+        assert_eq!(next_fallback(&ranked, 1, 0, 10, solvable), fallback(1, 15));
+        assert_eq!(next_fallback(&ranked, 3, 0, 10, solvable), fallback(3, 0));
+        assert_eq!(next_fallback(&ranked, 4, 0, 10, solvable), None);
+
+        // Solutions with orders that are no longer solvable are skipped but
+        // still serve as the reference of the solution ranked above them.
+        let third_unsolvable = |solution: &Solution| {
+            !solution
+                .order_ids()
+                .any(|uid| *uid == domain::OrderUid([3; 56]))
+        };
+        assert_eq!(
+            next_fallback(&ranked, 1, 0, 10, third_unsolvable),
+            fallback(1, 15)
+        );
+        assert_eq!(
+            next_fallback(&ranked, 2, 0, 10, third_unsolvable),
+            fallback(3, 0)
+        );
+
+        // Only the best solution gets picked after the submission deadline.
+        assert_eq!(next_fallback(&ranked, 0, 10, 10, solvable), fallback(0, 20));
+        assert_eq!(next_fallback(&ranked, 1, 10, 10, solvable), None);
+        assert_eq!(next_fallback(&ranked, 1, 9, 10, solvable), fallback(1, 15));
+
+        // The best solution doesn't need to be solvable anymore.
+        let nothing_solvable = |_: &Solution| false;
+        assert_eq!(
+            next_fallback(&ranked, 0, 0, 10, nothing_solvable),
+            fallback(0, 20)
+        );
+        assert_eq!(next_fallback(&ranked, 1, 0, 10, nothing_solvable), None);
+    }
+}
@@ -5,19 +5,25 @@
 //! parameters.
 
 mod policy;
+mod scope;
 
+pub use scope::Scope;
 use {
     crate::{
-        arguments::{self},
+        arguments::{self, Conditions},
         boundary::{self},
         domain::{self},
     },
     app_data::Validator,
     derive_more::Into,
     itertools::Itertools,
-    primitive_types::{H160, U256},
+    primitive_types::{H160, U256, U512},
     prometheus::core::Number,
-    std::{collections::HashSet, str::FromStr},
+    serde::Deserialize,
+    std::{
+        collections::{BTreeMap, HashSet},
+        str::FromStr,
+    },
 };
 
 enum OrderClass {
@@ -40,6 +46,9 @@ impl From<arguments::FeePolicyOrderClass> for OrderClass {
 pub struct ProtocolFee {
     policy: policy::Policy,
     order_class: OrderClass,
+    /// `None` if the policy applies to orders regardless of their tokens, size
+    /// and app code.
+    scope: Option<Scope>,
 }
 
 impl From<arguments::FeePolicy> for ProtocolFee {
@@ -47,12 +56,22 @@ impl From<arguments::FeePolicy> for ProtocolFee {
         Self {
             policy: value.fee_policy_kind.into(),
             order_class: value.fee_policy_order_class.into(),
+            scope: (!value.fee_policy_scope.is_unrestricted())
+                .then(|| value.fee_policy_scope.into()),
         }
     }
 }
 
 pub type ProtocolFeeExemptAddresses = HashSet<H160>;
 
+/// A fee policy applied to an order together with the scope it matched.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScopedPolicy {
+    pub policy: Policy,
+    /// `None` for policies that apply to all orders and for partner fees.
+    pub scope: Option<Scope>,
+}
+
 pub struct ProtocolFees {
     fee_policies: Vec<ProtocolFee>,
     max_partner_fee: FeeFactor,
@@ -84,9 +103,30 @@ impl ProtocolFees {
         }
     }
 
+    /// Returns a copy of the protocol fees with different fee policies and
+    /// exempt addresses.
+    pub fn with_policies(
+        &self,
+        fee_policies: &[arguments::FeePolicy],
+        protocol_fee_exempt_addresses: &[H160],
+    ) -> Self {
+        Self::new(
+            fee_policies,
+            self.max_partner_fee,
+            protocol_fee_exempt_addresses,
+            self.enable_protocol_fees,
+        )
+    }
+
     /// Converts an order from the boundary layer to the domain layer, applying
-    /// protocol fees if necessary.
-    pub fn apply(&self, order: boundary::Order, quote: &domain::Quote) -> domain::Order {
+    /// protocol fees if necessary. The native prices are used to match fee
+    /// policies scoped by order size.
+    pub fn apply(
+        &self,
+        order: boundary::Order,
+        quote: &domain::Quote,
+        native_prices: &BTreeMap<H160, U256>,
+    ) -> domain::Order {
         let partner_fee = order
             .metadata
             .full_app_data
@@ -105,6 +145,10 @@ impl ProtocolFees {
                         .unwrap(),
                     })
             })
+            .map(|policy| ScopedPolicy {
+                policy,
+                scope: None,
+            })
             .into_iter()
             .collect::<Vec<_>>();
 
@@ -126,54 +170,76 @@ impl ProtocolFees {
             fee: quote.fee,
         };
 
-        if self.enable_protocol_fees {
-            self.apply_multiple_policies(order, quote, order_, quote_, partner_fee)
+        let app_code = app_code(&order);
+        let subject = scope::Subject {
+            order: &order,
+            native_value: native_prices.get(&order.data.sell_token).and_then(|price| {
+                (order.data.sell_amount.full_mul(*price) / U512::exp10(18))
+                    .try_into()
+                    .ok()
+            }),
+            app_code: app_code.as_deref(),
+        };
+        let protocol_fees = if self.enable_protocol_fees {
+            self.apply_multiple_policies(&subject, quote, order_, quote_, partner_fee)
         } else {
-            self.apply_single_policy(order, quote, order_, quote_, partner_fee)
-        }
+            self.apply_single_policy(&subject, quote, order_, quote_, partner_fee)
+        };
+        boundary::order::to_domain(order, protocol_fees)
     }
 
     fn apply_single_policy(
         &self,
-        order: boundary::Order,
+        subject: &scope::Subject,
         quote: &domain::Quote,
         order_: boundary::Amounts,
         quote_: boundary::Amounts,
-        partner_fees: Vec<Policy>,
-    ) -> domain::Order {
-        if let Some(partner_fee) = partner_fees.first() {
-            return boundary::order::to_domain(order, vec![*partner_fee]);
+        mut partner_fees: Vec<ScopedPolicy>,
+    ) -> Vec<ScopedPolicy> {
+        if !partner_fees.is_empty() {
+            partner_fees.truncate(1);
+            return partner_fees;
         }
-        let protocol_fees = self
-            .fee_policies
+        self.fee_policies
             .iter()
             .find_map(|fee_policy| {
-                Self::protocol_fee_into_policy(&order, &order_, &quote_, fee_policy)
+                Self::protocol_fee_into_policy(subject, &order_, &quote_, fee_policy)
+            })
+            .and_then(|fee_policy| {
+                Self::variant_fee_apply(subject.order, quote, &fee_policy.policy).map(|policy| {
+                    ScopedPolicy {
+                        policy,
+                        scope: fee_policy.scope.clone(),
+                    }
+                })
             })
-            .and_then(|policy| Self::variant_fee_apply(&order, quote, policy))
             .into_iter()
-            .collect_vec();
-        boundary::order::to_domain(order, protocol_fees)
+            .collect_vec()
     }
 
     fn apply_multiple_policies(
         &self,
-        order: boundary::Order,
+        subject: &scope::Subject,
         quote: &domain::Quote,
         order_: boundary::Amounts,
         quote_: boundary::Amounts,
-        partner_fees: Vec<Policy>,
-    ) -> domain::Order {
-        let protocol_fees = self
-            .fee_policies
+        partner_fees: Vec<ScopedPolicy>,
+    ) -> Vec<ScopedPolicy> {
+        self.fee_policies
             .iter()
             .filter_map(|fee_policy| {
-                Self::protocol_fee_into_policy(&order, &order_, &quote_, fee_policy)
+                Self::protocol_fee_into_policy(subject, &order_, &quote_, fee_policy)
+            })
+            .flat_map(|fee_policy| {
+                Self::variant_fee_apply(subject.order, quote, &fee_policy.policy).map(|policy| {
+                    ScopedPolicy {
+                        policy,
+                        scope: fee_policy.scope.clone(),
+                    }
+                })
             })
-            .flat_map(|policy| Self::variant_fee_apply(&order, quote, policy))
             .chain(partner_fees)
-            .collect::<Vec<_>>();
-        boundary::order::to_domain(order, protocol_fees)
+            .collect::<Vec<_>>()
     }
 
     fn variant_fee_apply(
@@ -189,22 +255,41 @@ impl ProtocolFees {
     }
 
     fn protocol_fee_into_policy<'a>(
-        order: &boundary::Order,
+        subject: &scope::Subject,
         order_: &boundary::Amounts,
         quote_: &boundary::Amounts,
         protocol_fee: &'a ProtocolFee,
-    ) -> Option<&'a policy::Policy> {
+    ) -> Option<&'a ProtocolFee> {
+        if let Some(scope) = &protocol_fee.scope {
+            if !scope.matches(subject) {
+                return None;
+            }
+        }
         let outside_market_price =
-            boundary::is_order_outside_market_price(order_, quote_, order.data.kind);
+            boundary::is_order_outside_market_price(order_, quote_, subject.order.data.kind);
         match (outside_market_price, &protocol_fee.order_class) {
-            (_, OrderClass::Any) => Some(&protocol_fee.policy),
-            (true, OrderClass::Limit) => Some(&protocol_fee.policy),
-            (false, OrderClass::Market) => Some(&protocol_fee.policy),
+            (_, OrderClass::Any) => Some(protocol_fee),
+            (true, OrderClass::Limit) => Some(protocol_fee),
+            (false, OrderClass::Market) => Some(protocol_fee),
             _ => None,
         }
     }
 }
 
+/// Reads the `appCode` from the order's full app data.
+fn app_code(order: &boundary::Order) -> Option<String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct AppData {
+        app_code: Option<String>,
+    }
+
+    let full_app_data = order.metadata.full_app_data.as_ref()?;
+    serde_json::from_str::<AppData>(full_app_data)
+        .ok()?
+        .app_code
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Policy {
     /// If the order receives more than limit price, take the protocol fee as a
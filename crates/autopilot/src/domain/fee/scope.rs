@@ -0,0 +1,183 @@
+use {
+    crate::{arguments, boundary},
+    itertools::Itertools,
+    primitive_types::{H160, U256},
+    std::fmt::{self, Display},
+};
+
+/// Restricts the orders a configured fee policy applies to. An order is in
+/// scope if it satisfies every condition that is set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scope {
+    sell_tokens: Vec<H160>,
+    buy_tokens: Vec<H160>,
+    /// Token pairs, matching orders trading in either direction.
+    pairs: Vec<(H160, H160)>,
+    min_native_value: Option<U256>,
+    max_native_value: Option<U256>,
+    app_codes: Vec<String>,
+}
+
+/// The properties of an order a [`Scope`] gets matched against.
+pub struct Subject<'a> {
+    pub order: &'a boundary::Order,
+    /// Value of the order's sell amount in the native token. `None` if the
+    /// sell token has no native price.
+    pub native_value: Option<U256>,
+    /// The `appCode` of the order's app data.
+    pub app_code: Option<&'a str>,
+}
+
+impl Scope {
+    pub fn matches(&self, subject: &Subject) -> bool {
+        let (sell_token, buy_token) = (subject.order.data.sell_token, subject.order.data.buy_token);
+        (self.sell_tokens.is_empty() || self.sell_tokens.contains(&sell_token))
+            && (self.buy_tokens.is_empty() || self.buy_tokens.contains(&buy_token))
+            && (self.pairs.is_empty()
+                || self.pairs.iter().any(|&pair| {
+                    pair == (sell_token, buy_token) || pair == (buy_token, sell_token)
+                }))
+            && self.min_native_value.map_or(true, |min| {
+                subject.native_value.is_some_and(|value| value >= min)
+            })
+            && self.max_native_value.map_or(true, |max| {
+                subject.native_value.is_some_and(|value| value <= max)
+            })
+            && (self.app_codes.is_empty()
+                || subject
+                    .app_code
+                    .is_some_and(|app_code| self.app_codes.iter().any(|code| code == app_code)))
+    }
+}
+
+impl From<arguments::FeePolicyScope> for Scope {
+    fn from(value: arguments::FeePolicyScope) -> Self {
+        Self {
+            sell_tokens: value.sell_tokens,
+            buy_tokens: value.buy_tokens,
+            pairs: value.pairs,
+            min_native_value: value.min_native_value,
+            max_native_value: value.max_native_value,
+            app_codes: value.app_codes,
+        }
+    }
+}
+
+/// Formats the scope with the same syntax it gets configured with, e.g.
+/// `sellToken=0x..;0x..:minNativeValue=1000`.
+impl Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tokens = |tokens: &[H160]| tokens.iter().map(|token| format!("{token:?}")).join(";");
+        let mut conditions = Vec::new();
+        if !self.sell_tokens.is_empty() {
+            conditions.push(format!("sellToken={}", tokens(&self.sell_tokens)));
+        }
+        if !self.buy_tokens.is_empty() {
+            conditions.push(format!("buyToken={}", tokens(&self.buy_tokens)));
+        }
+        if !self.pairs.is_empty() {
+            let pairs = self
+                .pairs
+                .iter()
+                .map(|(token0, token1)| format!("{token0:?}/{token1:?}"))
+                .join(";");
+            conditions.push(format!("pair={pairs}"));
+        }
+        if let Some(min) = self.min_native_value {
+            conditions.push(format!("minNativeValue={min}"));
+        }
+        if let Some(max) = self.max_native_value {
+            conditions.push(format!("maxNativeValue={max}"));
+        }
+        if !self.app_codes.is_empty() {
+            conditions.push(format!("appCode={}", self.app_codes.join(";")));
+        }
+        write!(f, "{}", conditions.join(":"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, model::order::OrderData};
+
+    fn order(sell_token: u64, buy_token: u64) -> boundary::Order {
+        boundary::Order {
+            data: OrderData {
+                sell_token: H160::from_low_u64_be(sell_token),
+                buy_token: H160::from_low_u64_be(buy_token),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn subject<'a>(
+        order: &'a boundary::Order,
+        native_value: Option<u64>,
+        app_code: Option<&'a str>,
+    ) -> Subject<'a> {
+        Subject {
+            order,
+            native_value: native_value.map(U256::from),
+            app_code,
+        }
+    }
+
+    #[test]
+    fn matches_pairs_in_either_direction() {
+        let scope = Scope {
+            pairs: vec![(H160::from_low_u64_be(1), H160::from_low_u64_be(2))],
+            ..Default::default()
+        };
+
+        assert!(scope.matches(&subject(&order(1, 2), None, None)));
+        assert!(scope.matches(&subject(&order(2, 1), None, None)));
+        assert!(!scope.matches(&subject(&order(1, 3), None, None)));
+        assert!(!scope.matches(&subject(&order(3, 2), None, None)));
+    }
+
+    #[test]
+    fn matches_native_value_range() {
+        let scope = Scope {
+            min_native_value: Some(100.into()),
+            max_native_value: Some(1000.into()),
+            ..Default::default()
+        };
+        let order = order(1, 2);
+
+        assert!(scope.matches(&subject(&order, Some(100), None)));
+        assert!(scope.matches(&subject(&order, Some(1000), None)));
+        assert!(!scope.matches(&subject(&order, Some(99), None)));
+        assert!(!scope.matches(&subject(&order, Some(1001), None)));
+        // Orders without a native price never fall into a bounded range.
+        assert!(!scope.matches(&subject(&order, None, None)));
+        assert!(Scope::default().matches(&subject(&order, None, None)));
+    }
+
+    #[test]
+    fn matches_app_codes() {
+        let scope = Scope {
+            app_codes: vec!["CoW Swap".into(), "Partner".into()],
+            ..Default::default()
+        };
+        let order = order(1, 2);
+
+        assert!(scope.matches(&subject(&order, None, Some("CoW Swap"))));
+        assert!(scope.matches(&subject(&order, None, Some("Partner"))));
+        assert!(!scope.matches(&subject(&order, None, Some("cow swap"))));
+        assert!(!scope.matches(&subject(&order, None, None)));
+    }
+
+    #[test]
+    fn requires_all_conditions() {
+        let scope = Scope {
+            sell_tokens: vec![H160::from_low_u64_be(1)],
+            app_codes: vec!["CoW Swap".into()],
+            ..Default::default()
+        };
+
+        assert!(scope.matches(&subject(&order(1, 2), None, Some("CoW Swap"))));
+        assert!(!scope.matches(&subject(&order(2, 1), None, Some("CoW Swap"))));
+        assert!(!scope.matches(&subject(&order(1, 2), None, Some("Other"))));
+    }
+}
@@ -0,0 +1,32 @@
+//! HTTP API for inspecting and managing the state of a running autopilot.
+//!
+//! - `GET /drivers` returns the reputation of every driver.
+//! - `POST /drivers/{name}/reset` lifts the deny-listing of a driver and
+//!   forgets its past outcomes.
+
+use {
+    crate::infra::solvers::Reputation,
+    std::net::SocketAddr,
+    tokio::task::JoinHandle,
+    warp::{http::StatusCode, Filter},
+};
+
+pub fn serve(address: SocketAddr, reputation: Reputation) -> JoinHandle<()> {
+    let status = warp::path!("drivers").and(warp::get()).map({
+        let reputation = reputation.clone();
+        move || warp::reply::json(&reputation.status())
+    });
+    let reset = warp::path!("drivers" / String / "reset")
+        .and(warp::post())
+        .map(move |driver: String| {
+            let status = if reputation.reset(&driver) {
+                StatusCode::OK
+            } else {
+                StatusCode::NOT_FOUND
+            };
+            warp::reply::with_status(warp::reply(), status)
+        });
+
+    tracing::info!(%address, "serving admin api");
+    tokio::spawn(warp::serve(status.or(reset)).bind(address))
+}
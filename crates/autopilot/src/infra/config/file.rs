@@ -0,0 +1,45 @@
+use {
+    crate::arguments::{AuctionViewOrderClass, FeePolicy},
+    primitive_types::H160,
+    serde::Deserialize,
+    serde_with::{serde_as, DisplayFromStr},
+    std::time::Duration,
+    url::Url,
+};
+
+/// The contents of the config file. Every value that is not specified falls
+/// back to the one passed on the command line.
+#[serde_as]
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    #[serde(rename = "driver")]
+    pub drivers: Option<Vec<DriverConfig>>,
+
+    #[serde(default, with = "humantime_serde")]
+    pub solve_deadline: Option<Duration>,
+
+    /// Fee policies in the same format as the `--fee-policies` argument.
+    #[serde_as(as = "Option<Vec<DisplayFromStr>>")]
+    #[serde(default)]
+    pub fee_policies: Option<Vec<FeePolicy>>,
+
+    pub protocol_fee_exempt_addresses: Option<Vec<H160>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DriverConfig {
+    pub name: String,
+    pub url: Url,
+
+    /// The auction view of the driver, see `--drivers`.
+    #[serde(default)]
+    pub tokens: Vec<H160>,
+    #[serde(default)]
+    pub denied_tokens: Vec<H160>,
+    #[serde(default)]
+    pub classes: Vec<AuctionViewOrderClass>,
+    pub max_orders: Option<usize>,
+    pub liquidity_orders: Option<bool>,
+}
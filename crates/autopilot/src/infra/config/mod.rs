@@ -0,0 +1,267 @@
+//! The part of the autopilot configuration that can change while it is
+//! running. It is initialized from the command line arguments and optionally
+//! overridden by a TOML file which gets polled for changes.
+
+use {
+    crate::arguments::{Arguments, AuctionView, Driver, FeePolicy},
+    primitive_types::H160,
+    std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    },
+    tokio::sync::watch,
+};
+
+mod file;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub drivers: Vec<Driver>,
+    pub solve_deadline: Duration,
+    pub fee_policies: Vec<FeePolicy>,
+    pub protocol_fee_exempt_addresses: Vec<H160>,
+}
+
+impl Config {
+    pub fn new(args: &Arguments) -> Self {
+        Self {
+            drivers: args.drivers.clone(),
+            solve_deadline: args.solve_deadline,
+            fee_policies: args.fee_policies.clone(),
+            protocol_fee_exempt_addresses: args.protocol_fee_exempt_addresses.clone(),
+        }
+    }
+
+    /// Overrides the values that are specified in the config file.
+    fn with_file(&self, file: file::Config) -> Self {
+        Self {
+            drivers: file
+                .drivers
+                .map(|drivers| {
+                    drivers
+                        .into_iter()
+                        .map(|driver| Driver {
+                            name: driver.name,
+                            url: driver.url,
+                            view: AuctionView {
+                                tokens: driver.tokens,
+                                denied_tokens: driver.denied_tokens,
+                                classes: driver.classes,
+                                max_orders: driver.max_orders,
+                                liquidity_orders: driver.liquidity_orders.unwrap_or(true),
+                            },
+                        })
+                        .collect()
+                })
+                .unwrap_or_else(|| self.drivers.clone()),
+            solve_deadline: file.solve_deadline.unwrap_or(self.solve_deadline),
+            fee_policies: file
+                .fee_policies
+                .unwrap_or_else(|| self.fee_policies.clone()),
+            protocol_fee_exempt_addresses: file
+                .protocol_fee_exempt_addresses
+                .unwrap_or_else(|| self.protocol_fee_exempt_addresses.clone()),
+        }
+    }
+
+    /// Returns `(field, old, new)` for every value that differs from `other`.
+    fn diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        // Not all values implement `PartialEq` so they get compared by their
+        // debug representation.
+        let fields = [
+            (
+                "drivers",
+                format!("{:?}", self.drivers),
+                format!("{:?}", other.drivers),
+            ),
+            (
+                "solve_deadline",
+                format!("{:?}", self.solve_deadline),
+                format!("{:?}", other.solve_deadline),
+            ),
+            (
+                "fee_policies",
+                format!("{:?}", self.fee_policies),
+                format!("{:?}", other.fee_policies),
+            ),
+            (
+                "protocol_fee_exempt_addresses",
+                format!("{:?}", self.protocol_fee_exempt_addresses),
+                format!("{:?}", other.protocol_fee_exempt_addresses),
+            ),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, old, new)| old != new)
+            .collect()
+    }
+}
+
+/// A configuration together with its version. The version starts at 0 and
+/// gets incremented every time a changed config file gets loaded.
+#[derive(Debug)]
+pub struct Versioned {
+    pub version: u64,
+    pub config: Config,
+}
+
+/// Provides the latest version of the configuration.
+#[derive(Clone)]
+pub struct Watcher(watch::Receiver<Arc<Versioned>>);
+
+impl Watcher {
+    /// Loads the config file on top of the given defaults and spawns a
+    /// background task which reloads it every `interval`. Without a config
+    /// file the defaults never change.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the initial config file can't be loaded.
+    pub async fn new(defaults: Config, path: Option<PathBuf>, interval: Duration) -> Self {
+        let Some(path) = path else {
+            let (_, receiver) = watch::channel(Arc::new(Versioned {
+                version: 0,
+                config: defaults,
+            }));
+            return Self(receiver);
+        };
+
+        let (contents, file) = load(&path)
+            .await
+            .unwrap_or_else(|err| panic!("failed to load config file {path:?}: {err:?}"));
+        let config = defaults.with_file(file);
+        tracing::info!(?path, ?config, "loaded config file");
+        let (sender, receiver) = watch::channel(Arc::new(Versioned { version: 0, config }));
+        tokio::spawn(reload(sender, defaults, path, contents, interval));
+        Self(receiver)
+    }
+
+    /// The currently active configuration.
+    pub fn current(&self) -> Arc<Versioned> {
+        self.0.borrow().clone()
+    }
+
+    /// Returns the latest configuration if it changed since the last time it
+    /// was returned by this method.
+    pub fn changed(&mut self) -> Option<Arc<Versioned>> {
+        match self.0.has_changed() {
+            Ok(true) => Some(self.0.borrow_and_update().clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Reads and parses the config file. Returns the raw file contents as well so
+/// reloads can skip unchanged files.
+async fn load(path: &Path) -> anyhow::Result<(String, file::Config)> {
+    let contents = tokio::fs::read_to_string(path).await?;
+    let file = toml::de::from_str(&contents)?;
+    Ok((contents, file))
+}
+
+async fn reload(
+    sender: watch::Sender<Arc<Versioned>>,
+    defaults: Config,
+    path: PathBuf,
+    mut contents: String,
+    interval: Duration,
+) {
+    // Stop reloading once nobody is interested in the config anymore.
+    while !sender.is_closed() {
+        tokio::time::sleep(interval).await;
+        let (new_contents, file) = match load(&path).await {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                tracing::error!(
+                    ?err,
+                    ?path,
+                    "failed to reload config file, keeping the current config"
+                );
+                continue;
+            }
+        };
+        if new_contents == contents {
+            continue;
+        }
+        contents = new_contents;
+
+        let current = sender.borrow().clone();
+        let config = defaults.with_file(file);
+        if config.drivers.is_empty() {
+            tracing::error!(
+                ?path,
+                "config file has no drivers, keeping the current config"
+            );
+            continue;
+        }
+        let diff = current.config.diff(&config);
+        if diff.is_empty() {
+            continue;
+        }
+        let version = current.version + 1;
+        for (field, old, new) in diff {
+            tracing::info!(version, field, old, new, "config changed");
+        }
+        sender.send_replace(Arc::new(Versioned { version, config }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_config_overrides_defaults() {
+        let defaults = Config {
+            drivers: Vec::new(),
+            solve_deadline: Duration::from_secs(1),
+            fee_policies: Vec::new(),
+            protocol_fee_exempt_addresses: Vec::new(),
+        };
+        let file =
+            std::fs::read_to_string(std::env::current_dir().unwrap().join("example.toml")).unwrap();
+        let config = defaults.with_file(toml::de::from_str(&file).unwrap());
+
+        assert_eq!(config.drivers.len(), 2);
+        assert!(config.drivers[1].view.max_orders.is_some());
+        assert_eq!(config.solve_deadline, Duration::from_secs(15));
+        assert_eq!(config.fee_policies.len(), 2);
+        assert_eq!(config.protocol_fee_exempt_addresses.len(), 1);
+        assert_eq!(
+            defaults
+                .diff(&config)
+                .into_iter()
+                .map(|(field, _, _)| field)
+                .collect::<Vec<_>>(),
+            [
+                "drivers",
+                "solve_deadline",
+                "fee_policies",
+                "protocol_fee_exempt_addresses"
+            ],
+        );
+    }
+
+    #[test]
+    fn unspecified_values_fall_back_to_defaults() {
+        let defaults = Config {
+            drivers: vec!["solver|http://localhost:11088/solver".parse().unwrap()],
+            solve_deadline: Duration::from_secs(1),
+            fee_policies: vec!["volume:0.1:any".parse().unwrap()],
+            protocol_fee_exempt_addresses: Vec::new(),
+        };
+        let config = defaults.with_file(toml::de::from_str(r#"solve-deadline = "2s""#).unwrap());
+
+        assert_eq!(config.solve_deadline, Duration::from_secs(2));
+        assert_eq!(
+            defaults
+                .diff(&config)
+                .into_iter()
+                .map(|(field, _, _)| field)
+                .collect::<Vec<_>>(),
+            ["solve_deadline"],
+        );
+        assert!(toml::de::from_str::<file::Config>("unknown = 1").is_err());
+    }
+}
@@ -1,4 +1,6 @@
+pub mod admin;
 pub mod blockchain;
+pub mod config;
 pub mod persistence;
 pub mod shadow;
 pub mod solvers;
@@ -7,7 +7,9 @@ pub fn from_domain(
     auction_id: domain::auction::Id,
     order_uid: domain::OrderUid,
     policy: domain::fee::Policy,
+    scope: Option<domain::fee::Scope>,
 ) -> FeePolicy {
+    let scope = scope.map(|scope| scope.to_string());
     match policy {
         domain::fee::Policy::Surplus {
             factor,
@@ -21,6 +23,7 @@ pub fn from_domain(
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            scope,
         },
         domain::fee::Policy::Volume { factor } => FeePolicy {
             auction_id,
@@ -31,6 +34,7 @@ pub fn from_domain(
             volume_factor: Some(factor.into()),
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            scope,
         },
         domain::fee::Policy::PriceImprovement {
             factor,
@@ -45,6 +49,7 @@ pub fn from_domain(
             volume_factor: None,
             price_improvement_factor: Some(factor.into()),
             price_improvement_max_volume_factor: Some(max_volume_factor.into()),
+            scope,
         },
     }
 }
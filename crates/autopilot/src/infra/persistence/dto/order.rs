@@ -47,7 +47,11 @@ pub fn from_domain(order: domain::Order) -> Order {
         buy_token: order.buy_token,
         sell_amount: order.sell_amount,
         buy_amount: order.buy_amount,
-        protocol_fees: order.protocol_fees.into_iter().map(Into::into).collect(),
+        protocol_fees: order
+            .protocol_fees
+            .into_iter()
+            .map(|fee| fee.policy.into())
+            .collect(),
         valid_to: order.valid_to,
         kind: order.side.into(),
         receiver: order.receiver,
@@ -75,7 +79,16 @@ pub fn to_domain(order: Order) -> domain::Order {
         buy_token: order.buy_token,
         sell_amount: order.sell_amount,
         buy_amount: order.buy_amount,
-        protocol_fees: order.protocol_fees.into_iter().map(Into::into).collect(),
+        // Scopes are only needed when storing the fee policies of a freshly
+        // built auction and are not part of the persisted order.
+        protocol_fees: order
+            .protocol_fees
+            .into_iter()
+            .map(|policy| domain::fee::ScopedPolicy {
+                policy: policy.into(),
+                scope: None,
+            })
+            .collect(),
         valid_to: order.valid_to,
         side: order.kind.into(),
         receiver: order.receiver,
@@ -6,7 +6,7 @@ use {
     },
     anyhow::Context,
     chrono::Utc,
-    primitive_types::H256,
+    primitive_types::{H160, H256},
     std::sync::Arc,
     tracing::Instrument,
 };
@@ -61,6 +61,17 @@ impl Persistence {
             .map_err(Error::DbError)
     }
 
+    /// Loads a past auction and the record of its competition.
+    pub async fn historical_auction(
+        &self,
+        auction_id: domain::auction::Id,
+    ) -> Result<Option<crate::database::auction::HistoricalAuction>, Error> {
+        self.postgres
+            .historical_auction(auction_id)
+            .await
+            .map_err(Error::DbError)
+    }
+
     /// Saves the given auction to storage for debugging purposes.
     ///
     /// There is no intention to retrieve this data programmatically.
@@ -91,6 +102,17 @@ impl Persistence {
             .map_err(Error::DbError)
     }
 
+    /// Updates the previously saved competition data in the DB.
+    pub async fn update_competition(
+        &self,
+        competition: &boundary::Competition,
+    ) -> Result<(), Error> {
+        self.postgres
+            .update_competition(competition)
+            .await
+            .map_err(Error::DbError)
+    }
+
     /// Inserts an order event for each order uid in the given set.
     /// Unique order uids are required to avoid inserting events with the same
     /// label within the same order_uid. If this function encounters an error it
@@ -100,12 +122,32 @@ impl Persistence {
         &self,
         order_uids: Vec<domain::OrderUid>,
         label: boundary::OrderEventLabel,
+    ) {
+        self.spawn_store_order_events(order_uids, label, None);
+    }
+
+    /// Like [`Self::store_order_events`] but additionally records why the
+    /// events happened (e.g. which filter removed the orders from the auction).
+    pub fn store_order_events_with_reason(
+        &self,
+        order_uids: Vec<domain::OrderUid>,
+        label: boundary::OrderEventLabel,
+        reason: &'static str,
+    ) {
+        self.spawn_store_order_events(order_uids, label, Some(reason));
+    }
+
+    fn spawn_store_order_events(
+        &self,
+        order_uids: Vec<domain::OrderUid>,
+        label: boundary::OrderEventLabel,
+        reason: Option<&'static str>,
     ) {
         let db = self.postgres.clone();
         tokio::spawn(
             async move {
                 let mut tx = db.pool.acquire().await.expect("failed to acquire tx");
-                store_order_events(&mut tx, order_uids, label, Utc::now()).await;
+                store_order_events(&mut tx, order_uids, label, reason, Utc::now()).await;
             }
             .instrument(tracing::Span::current()),
         );
@@ -115,7 +157,7 @@ impl Persistence {
     pub async fn store_fee_policies(
         &self,
         auction_id: domain::auction::Id,
-        fee_policies: Vec<(domain::OrderUid, Vec<domain::fee::Policy>)>,
+        fee_policies: Vec<(domain::OrderUid, Vec<domain::fee::ScopedPolicy>)>,
     ) -> anyhow::Result<()> {
         let mut ex = self.postgres.pool.begin().await.context("begin")?;
         for chunk in fee_policies.chunks(self.postgres.config.insert_batch_size.get()) {
@@ -127,11 +169,15 @@ impl Persistence {
         ex.commit().await.context("commit")
     }
 
-    /// Retrieves the transaction hash for the settlement with the given
-    /// auction_id.
-    pub async fn find_tx_hash_by_auction_id(&self, auction_id: i64) -> Result<Option<H256>, Error> {
+    /// Retrieves the transaction hash for the settlement of the given solver
+    /// with the given auction_id.
+    pub async fn find_tx_hash_by_auction_id(
+        &self,
+        auction_id: i64,
+        solver: H160,
+    ) -> Result<Option<H256>, Error> {
         self.postgres
-            .find_tx_hash_by_auction_id(auction_id)
+            .find_tx_hash_by_auction_id(auction_id, solver)
             .await
             .map_err(Error::DbError)
     }
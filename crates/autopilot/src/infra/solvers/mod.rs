@@ -1,6 +1,6 @@
 use {
     self::dto::{reveal, settle, solve},
-    crate::util,
+    crate::{domain, util},
     anyhow::{anyhow, Context, Result},
     reqwest::{Client, StatusCode},
     std::time::Duration,
@@ -8,6 +8,9 @@ use {
 };
 
 pub mod dto;
+pub mod reputation;
+
+pub use reputation::Reputation;
 
 const RESPONSE_SIZE_LIMIT: usize = 10_000_000;
 const RESPONSE_TIME_LIMIT: Duration = Duration::from_secs(60);
@@ -15,6 +18,8 @@ const RESPONSE_TIME_LIMIT: Duration = Duration::from_secs(60);
 pub struct Driver {
     pub name: String,
     pub url: Url,
+    /// The part of the auction that gets sent to the driver.
+    pub view: domain::auction::View,
     client: Client,
 }
 
@@ -23,6 +28,7 @@ impl Driver {
         Self {
             name,
             url,
+            view: Default::default(),
             client: Client::builder()
                 .timeout(RESPONSE_TIME_LIMIT)
                 .build()
@@ -30,6 +36,10 @@ impl Driver {
         }
     }
 
+    pub fn with_view(self, view: domain::auction::View) -> Self {
+        Self { view, ..self }
+    }
+
     pub async fn solve(&self, request: &solve::Request) -> Result<solve::Response> {
         self.request_response("solve", request, None).await
     }
@@ -0,0 +1,337 @@
+//! Keeps track of how reliably drivers handle the requests they get sent and
+//! temporarily excludes drivers that keep failing from the competition.
+
+use {
+    ::observe::metrics,
+    serde::Serialize,
+    std::{
+        collections::{BTreeMap, HashMap, VecDeque},
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+};
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Number of most recent outcomes per request the success rates are
+    /// computed over.
+    pub window: usize,
+    /// Drivers get deny-listed once the success rate of any request drops
+    /// below this value. 0 disables the deny-listing.
+    pub min_success_rate: f64,
+    /// How long deny-listed drivers are excluded from auctions.
+    pub cooldown: Duration,
+}
+
+/// The driver requests whose outcomes are tracked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Request {
+    Solve,
+    Reveal,
+    Settle,
+}
+
+impl Request {
+    fn label(&self) -> &'static str {
+        match self {
+            Request::Solve => "solve",
+            Request::Reveal => "reveal",
+            Request::Settle => "settle",
+        }
+    }
+}
+
+/// Tracks the reputation of all drivers. Clones share the same state.
+#[derive(Clone)]
+pub struct Reputation {
+    config: Arc<Config>,
+    drivers: Arc<Mutex<HashMap<String, State>>>,
+}
+
+#[derive(Default)]
+struct State {
+    outcomes: BTreeMap<Request, VecDeque<bool>>,
+    denied_until: Option<Instant>,
+}
+
+impl State {
+    fn success_rates(&self) -> BTreeMap<Request, f64> {
+        self.outcomes
+            .iter()
+            .map(|(request, outcomes)| (*request, success_rate(outcomes)))
+            .collect()
+    }
+}
+
+/// The reputation of a single driver.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Status {
+    pub driver: String,
+    pub success_rates: BTreeMap<Request, f64>,
+    /// Remaining time of the cooling-off period of a deny-listed driver.
+    #[serde(with = "humantime_serde")]
+    pub denied_for: Option<Duration>,
+}
+
+impl Reputation {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config: Arc::new(config),
+            drivers: Default::default(),
+        }
+    }
+
+    /// Records the outcome of a request sent to a driver. The driver gets
+    /// deny-listed if this drops its success rate below the configured
+    /// minimum.
+    pub fn record(&self, driver: &str, request: Request, success: bool) {
+        self.record_at(driver, request, success, Instant::now())
+    }
+
+    /// The drivers that are allowed to participate in the next auction.
+    /// Drivers whose cooling-off period is over start again with a clean
+    /// record. If every driver is deny-listed none of them get excluded
+    /// because an auction without participants can't be settled at all.
+    pub fn allowed<'a, D>(&self, drivers: &'a [D], name: impl Fn(&D) -> &str) -> Vec<&'a D> {
+        self.allowed_at(drivers, name, Instant::now())
+    }
+
+    /// Lifts the deny-listing of a driver and forgets its past outcomes.
+    /// Returns `false` if the driver is not known.
+    pub fn reset(&self, driver: &str) -> bool {
+        let mut drivers = self.drivers.lock().unwrap();
+        if drivers.remove(driver).is_none() {
+            return false;
+        }
+        tracing::info!(driver, "reset driver reputation");
+        Metrics::reset(driver);
+        true
+    }
+
+    /// The reputation of every driver that has been sent a request.
+    pub fn status(&self) -> Vec<Status> {
+        let now = Instant::now();
+        let drivers = self.drivers.lock().unwrap();
+        let mut status: Vec<_> = drivers
+            .iter()
+            .map(|(driver, state)| Status {
+                driver: driver.clone(),
+                success_rates: state.success_rates(),
+                denied_for: state
+                    .denied_until
+                    .map(|until| until.saturating_duration_since(now)),
+            })
+            .collect();
+        status.sort_by(|a, b| a.driver.cmp(&b.driver));
+        status
+    }
+
+    fn record_at(&self, driver: &str, request: Request, success: bool, now: Instant) {
+        let mut drivers = self.drivers.lock().unwrap();
+        let state = drivers.entry(driver.to_string()).or_default();
+        let outcomes = state.outcomes.entry(request).or_default();
+        outcomes.push_back(success);
+        if outcomes.len() > self.config.window {
+            outcomes.pop_front();
+        }
+
+        let rate = success_rate(outcomes);
+        Metrics::success_rate(driver, request, rate);
+        // Only judge drivers once there are enough outcomes for the success
+        // rate to be meaningful.
+        if outcomes.len() >= self.config.window
+            && rate < self.config.min_success_rate
+            && state.denied_until.is_none()
+        {
+            tracing::warn!(
+                driver,
+                request = request.label(),
+                rate,
+                cooldown = ?self.config.cooldown,
+                "deny-listing driver"
+            );
+            state.denied_until = Some(now + self.config.cooldown);
+            Metrics::denied(driver, true);
+        }
+    }
+
+    fn allowed_at<'a, D>(
+        &self,
+        drivers: &'a [D],
+        name: impl Fn(&D) -> &str,
+        now: Instant,
+    ) -> Vec<&'a D> {
+        let allowed: Vec<_> = drivers
+            .iter()
+            .filter(|driver| {
+                let allowed = self.is_allowed_at(name(driver), now);
+                if !allowed {
+                    tracing::debug!(driver = name(driver), "skipping deny-listed driver");
+                }
+                allowed
+            })
+            .collect();
+        if allowed.is_empty() && !drivers.is_empty() {
+            tracing::warn!("all drivers are deny-listed, not excluding any of them");
+            return drivers.iter().collect();
+        }
+        allowed
+    }
+
+    fn is_allowed_at(&self, driver: &str, now: Instant) -> bool {
+        let mut drivers = self.drivers.lock().unwrap();
+        let Some(state) = drivers.get_mut(driver) else {
+            return true;
+        };
+        match state.denied_until {
+            Some(until) if now < until => false,
+            Some(_) => {
+                tracing::info!(driver, "cooling-off period is over, allowing driver again");
+                *state = State::default();
+                Metrics::reset(driver);
+                true
+            }
+            None => true,
+        }
+    }
+}
+
+fn success_rate(outcomes: &VecDeque<bool>) -> f64 {
+    if outcomes.is_empty() {
+        return 1.;
+    }
+    let successes = outcomes.iter().filter(|success| **success).count();
+    successes as f64 / outcomes.len() as f64
+}
+
+#[derive(prometheus_metric_storage::MetricStorage)]
+#[metric(subsystem = "driver_reputation")]
+struct Metrics {
+    /// Tracks the success rate of the recent requests to every driver.
+    #[metric(labels("driver", "request"))]
+    success_rate: prometheus::GaugeVec,
+
+    /// Tracks which drivers are currently deny-listed.
+    #[metric(labels("driver"))]
+    denied: prometheus::IntGaugeVec,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Metrics::instance(metrics::get_storage_registry()).unwrap()
+    }
+
+    fn success_rate(driver: &str, request: Request, rate: f64) {
+        Self::get()
+            .success_rate
+            .with_label_values(&[driver, request.label()])
+            .set(rate);
+    }
+
+    fn denied(driver: &str, denied: bool) {
+        Self::get()
+            .denied
+            .with_label_values(&[driver])
+            .set(denied.into());
+    }
+
+    fn reset(driver: &str) {
+        for request in [Request::Solve, Request::Reveal, Request::Settle] {
+            Self::success_rate(driver, request, 1.);
+        }
+        Self::denied(driver, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reputation() -> Reputation {
+        Reputation::new(Config {
+            window: 4,
+            min_success_rate: 0.5,
+            cooldown: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    fn denies_failing_drivers_until_cooldown_is_over() {
+        let reputation = reputation();
+        let now = Instant::now();
+
+        // Not enough outcomes to judge the driver yet.
+        for _ in 0..3 {
+            reputation.record_at("driver", Request::Reveal, false, now);
+        }
+        assert!(reputation.is_allowed_at("driver", now));
+
+        reputation.record_at("driver", Request::Reveal, false, now);
+        assert!(!reputation.is_allowed_at("driver", now));
+        assert!(!reputation.is_allowed_at("driver", now + Duration::from_secs(59)));
+        assert!(reputation.is_allowed_at("other", now));
+
+        // The driver starts with a clean record after the cooling-off period.
+        assert!(reputation.is_allowed_at("driver", now + Duration::from_secs(60)));
+        assert!(reputation.status()[0].success_rates.is_empty());
+    }
+
+    #[test]
+    fn success_rate_is_computed_over_window() {
+        let reputation = reputation();
+        let now = Instant::now();
+
+        for success in [false, false, true, true, true] {
+            reputation.record_at("driver", Request::Solve, success, now);
+        }
+        reputation.record_at("driver", Request::Settle, true, now);
+
+        assert!(reputation.is_allowed_at("driver", now));
+        let status = reputation.status();
+        assert_eq!(
+            status[0].success_rates,
+            BTreeMap::from([(Request::Solve, 0.75), (Request::Settle, 1.)])
+        );
+        assert_eq!(status[0].denied_for, None);
+    }
+
+    #[test]
+    fn reset_lifts_deny_listing() {
+        let reputation = reputation();
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            reputation.record_at("driver", Request::Settle, false, now);
+        }
+        assert!(!reputation.is_allowed_at("driver", now));
+
+        assert!(reputation.reset("driver"));
+        assert!(reputation.is_allowed_at("driver", now));
+        assert!(!reputation.reset("unknown"));
+    }
+
+    #[test]
+    fn never_denies_every_driver() {
+        let reputation = reputation();
+        let now = Instant::now();
+        let drivers = ["a", "b"];
+
+        for _ in 0..4 {
+            reputation.record_at("a", Request::Solve, false, now);
+        }
+        assert_eq!(reputation.allowed_at(&drivers, |d| *d, now), vec![&"b"]);
+
+        for _ in 0..4 {
+            reputation.record_at("b", Request::Solve, false, now);
+        }
+        assert_eq!(
+            reputation.allowed_at(&drivers, |d| *d, now),
+            vec![&"a", &"b"]
+        );
+        assert!(reputation
+            .allowed_at(&[] as &[&str], |d| *d, now)
+            .is_empty());
+    }
+}
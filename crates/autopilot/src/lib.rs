@@ -7,10 +7,12 @@ pub mod event_updater;
 pub mod infra;
 pub mod on_settlement_event_updater;
 pub mod periodic_db_cleanup;
+pub mod replay;
 pub mod run;
 pub mod run_loop;
 pub mod shadow;
 pub mod solvable_orders;
+pub mod solver_rewards;
 pub mod util;
 
 pub use self::run::{run, start};
@@ -38,7 +38,7 @@ use {
         infra,
     },
     anyhow::{Context, Result},
-    database::PgTransaction,
+    database::{byte_array::ByteArray, PgTransaction},
     primitive_types::H256,
     shared::external_prices::ExternalPrices,
     sqlx::PgConnection,
@@ -269,34 +269,39 @@ impl Inner {
             }
         };
 
-        let score = database::settlement_scores::fetch(ex, auction_id).await?;
+        let solver = ByteArray(tx_from.0);
+        let winners = database::settlement_scores::fetch(ex, auction_id)
+            .await?
+            .into_iter()
+            .map(|score| score.winner)
+            .collect::<Vec<_>>();
         let data_already_recorded =
-            database::settlements::already_processed(ex, auction_id).await?;
-        match (score, data_already_recorded) {
-            (None, _) => {
+            database::settlements::already_processed(ex, auction_id, &solver).await?;
+        match (winners.as_slice(), data_already_recorded) {
+            ([], _) => {
                 tracing::debug!(
                     auction_id,
                     "calldata claims to settle auction that has no competition"
                 );
                 Ok(AuctionIdRecoveryStatus::DoNotAddAuctionData(auction_id))
             }
-            (Some(score), _) if score.winner.0 != tx_from.0 => {
+            (winners, _) if !winners.contains(&solver) => {
                 tracing::warn!(
                     auction_id,
                     ?tx_from,
-                    winner = ?score.winner,
-                    "solution submitted by solver other than the winner"
+                    ?winners,
+                    "solution submitted by solver other than the winners"
                 );
                 Ok(AuctionIdRecoveryStatus::DoNotAddAuctionData(auction_id))
             }
-            (Some(_), true) => {
+            (_, true) => {
                 tracing::warn!(
                     auction_id,
                     "settlement data already recorded for this auction"
                 );
                 Ok(AuctionIdRecoveryStatus::DoNotAddAuctionData(auction_id))
             }
-            (Some(_), false) => Ok(AuctionIdRecoveryStatus::AddAuctionData(
+            (_, false) => Ok(AuctionIdRecoveryStatus::AddAuctionData(
                 auction_id, settlement,
             )),
         }
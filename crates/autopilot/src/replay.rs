@@ -0,0 +1,327 @@
+//! This module implements the replay mode of the autopilot.
+//!
+//! The replay mode loads an auction from the past by its ID, sends it to the
+//! configured drivers and compares their solutions with the solver competition
+//! that was recorded for the auction. This makes it possible to evaluate solver
+//! and driver changes against historical auctions. Just like in shadow mode no
+//! settlements get executed.
+
+use {
+    crate::{
+        boundary,
+        domain,
+        infra,
+        shadow::{self, Participant},
+    },
+    anyhow::{Context, Result},
+    model::solver_competition::SolverCompetitionAPI,
+    number::serialization::HexOrDecimalU256,
+    primitive_types::{H160, U256},
+    serde::Serialize,
+    serde_json::json,
+    serde_with::serde_as,
+    std::collections::{BTreeMap, BTreeSet},
+    url::Url,
+    web3::Transport,
+};
+
+pub struct Replay {
+    persistence: infra::persistence::Persistence,
+    competition: shadow::Competition,
+    protocol_fees: domain::ProtocolFees,
+    fork: Option<Fork>,
+}
+
+/// An anvil node the drivers simulate against. It gets reset to a fork of the
+/// upstream node at the block the replayed auction started at, so solutions
+/// get computed on the historical chain state.
+pub struct Fork {
+    pub web3: ethrpc::Web3,
+    pub upstream: Url,
+}
+
+impl Replay {
+    pub fn new(
+        persistence: infra::persistence::Persistence,
+        competition: shadow::Competition,
+        protocol_fees: domain::ProtocolFees,
+        fork: Option<Fork>,
+    ) -> Self {
+        Self {
+            persistence,
+            competition,
+            protocol_fees,
+            fork,
+        }
+    }
+
+    /// Runs the competition for the given auction again and compares the
+    /// result with the recorded one.
+    pub async fn run(&self, id: domain::auction::Id) -> Result<Report> {
+        let historical = self
+            .persistence
+            .historical_auction(id)
+            .await?
+            .with_context(|| format!("no competition stored for auction {id}"))?;
+        let auction = self.auction(
+            historical.orders,
+            historical.prices,
+            &historical.competition,
+        );
+        tracing::info!(
+            block = auction.block,
+            orders = auction.orders.len(),
+            "replaying auction"
+        );
+
+        if let Some(fork) = &self.fork {
+            fork.reset(auction.block).await?;
+        }
+
+        let participants = self.competition.run(id, &auction).await;
+        Ok(Report::new(
+            &historical.competition,
+            auction.orders.len(),
+            &participants,
+        ))
+    }
+
+    /// Builds the auction like it was sent to the solvers originally. The
+    /// protocol fees are the currently configured ones.
+    fn auction(
+        &self,
+        orders: boundary::SolvableOrders,
+        prices: BTreeMap<H160, U256>,
+        competition: &SolverCompetitionAPI,
+    ) -> domain::Auction {
+        domain::Auction {
+            block: competition.common.auction_start_block,
+            latest_settlement_block: orders.latest_settlement_block,
+            orders: orders
+                .orders
+                .into_iter()
+                .filter_map(|order| {
+                    if let Some(quote) = orders.quotes.get(&order.metadata.uid.into()) {
+                        Some(self.protocol_fees.apply(order, quote, &prices))
+                    } else {
+                        tracing::warn!(order_uid = %order.metadata.uid, "order is skipped, quote is missing");
+                        None
+                    }
+                })
+                .collect(),
+            prices,
+        }
+    }
+}
+
+impl Fork {
+    async fn reset(&self, block: u64) -> Result<()> {
+        self.web3
+            .transport()
+            .execute(
+                "anvil_reset",
+                vec![json!({
+                    "forking": {
+                        "jsonRpcUrl": self.upstream.to_string(),
+                        "blockNumber": block,
+                    }
+                })],
+            )
+            .await
+            .context("failed to reset fork")?;
+        Ok(())
+    }
+}
+
+/// Compares the replayed competition with the recorded one.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub auction_id: domain::auction::Id,
+    pub block: u64,
+    /// Number of orders the replayed auction contained.
+    pub orders: usize,
+    pub historical: Vec<HistoricalSolution>,
+    pub replayed: Vec<ReplayedSolution>,
+    pub historical_winner: Option<String>,
+    pub replayed_winner: Option<String>,
+    /// Orders executed by the historical winner but not by the replayed one.
+    pub only_historical_orders: Vec<boundary::OrderUid>,
+    /// Orders executed by the replayed winner but not by the historical one.
+    pub only_replayed_orders: Vec<boundary::OrderUid>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalSolution {
+    pub solver: String,
+    pub ranking: usize,
+    #[serde_as(as = "Option<HexOrDecimalU256>")]
+    pub score: Option<U256>,
+    pub settled: bool,
+    pub orders: Vec<boundary::OrderUid>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayedSolution {
+    pub driver: String,
+    #[serde_as(as = "Option<HexOrDecimalU256>")]
+    pub score: Option<U256>,
+    pub orders: Vec<boundary::OrderUid>,
+    pub error: Option<String>,
+}
+
+impl Report {
+    /// Expects the participants to be sorted by score, best first.
+    fn new(
+        competition: &SolverCompetitionAPI,
+        orders: usize,
+        participants: &[Participant<'_>],
+    ) -> Self {
+        let mut historical: Vec<_> = competition
+            .common
+            .solutions
+            .iter()
+            .map(|solution| HistoricalSolution {
+                solver: solution.solver.clone(),
+                ranking: solution.ranking,
+                score: solution.score.map(|score| score.score()),
+                settled: competition
+                    .common
+                    .settled_rankings
+                    .contains(&solution.ranking),
+                orders: solution.orders.iter().map(|order| *order.id()).collect(),
+            })
+            .collect();
+        historical.sort_by_key(|solution| solution.ranking);
+        let replayed: Vec<_> = participants
+            .iter()
+            .map(|participant| match &participant.solution {
+                Ok(solution) => ReplayedSolution {
+                    driver: participant.driver.name.clone(),
+                    score: Some(solution.score.get()),
+                    orders: solution.orders.clone(),
+                    error: None,
+                },
+                Err(err) => ReplayedSolution {
+                    driver: participant.driver.name.clone(),
+                    score: None,
+                    orders: Vec::new(),
+                    error: Some(err.to_string()),
+                },
+            })
+            .collect();
+
+        // The best ranked solution won the auction.
+        let historical_winner = historical.first();
+        let replayed_winner = replayed.iter().find(|solution| solution.error.is_none());
+        let historical_orders: BTreeSet<_> = historical_winner
+            .map(|winner| winner.orders.iter().copied().collect())
+            .unwrap_or_default();
+        let replayed_orders: BTreeSet<_> = replayed_winner
+            .map(|winner| winner.orders.iter().copied().collect())
+            .unwrap_or_default();
+
+        Self {
+            auction_id: competition.auction_id,
+            block: competition.common.auction_start_block,
+            orders,
+            historical_winner: historical_winner.map(|winner| winner.solver.clone()),
+            replayed_winner: replayed_winner.map(|winner| winner.driver.clone()),
+            only_historical_orders: historical_orders
+                .difference(&replayed_orders)
+                .copied()
+                .collect(),
+            only_replayed_orders: replayed_orders
+                .difference(&historical_orders)
+                .copied()
+                .collect(),
+            historical,
+            replayed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        model::solver_competition::{Order, Score, SolverCompetitionDB, SolverSettlement},
+        number::nonzero::U256 as NonZeroU256,
+    };
+
+    #[test]
+    fn report_compares_winners() {
+        let uid = |byte| boundary::OrderUid([byte; 56]);
+        let executed = |byte| Order::Colocated {
+            id: uid(byte),
+            sell_amount: 1.into(),
+            buy_amount: 1.into(),
+        };
+        let competition = SolverCompetitionAPI {
+            auction_id: 1,
+            transaction_hash: None,
+            common: SolverCompetitionDB {
+                auction_start_block: 10,
+                solutions: vec![
+                    SolverSettlement {
+                        solver: "second".to_string(),
+                        score: Some(Score::Solver(1.into())),
+                        ranking: 2,
+                        orders: vec![executed(3)],
+                        ..Default::default()
+                    },
+                    SolverSettlement {
+                        solver: "first".to_string(),
+                        score: Some(Score::Solver(2.into())),
+                        ranking: 1,
+                        orders: vec![executed(1), executed(2)],
+                        ..Default::default()
+                    },
+                ],
+                settled_rankings: vec![1],
+                ..Default::default()
+            },
+        };
+        let driver =
+            |name: &str| infra::Driver::new("http://localhost".parse().unwrap(), name.to_string());
+        let (solver, failing) = (driver("solver"), driver("failing"));
+        let participants = [
+            Participant {
+                driver: &solver,
+                solution: Ok(shadow::Solution {
+                    score: NonZeroU256::new(3.into()).unwrap(),
+                    account: Default::default(),
+                    orders: vec![uid(2), uid(4)],
+                    calldata: Default::default(),
+                }),
+            },
+            Participant {
+                driver: &failing,
+                solution: Err(shadow::Error::Timeout),
+            },
+        ];
+
+        let report = Report::new(&competition, 4, &participants);
+
+        assert_eq!(
+            report
+                .historical
+                .iter()
+                .map(|solution| (solution.solver.as_str(), solution.settled))
+                .collect::<Vec<_>>(),
+            [("first", true), ("second", false)],
+        );
+        assert_eq!(report.historical_winner.as_deref(), Some("first"));
+        assert_eq!(report.replayed_winner.as_deref(), Some("solver"));
+        assert_eq!(
+            report.replayed[1].error.as_deref(),
+            Some("the solver timed out")
+        );
+        assert_eq!(report.only_historical_orders, [uid(1)]);
+        assert_eq!(report.only_replayed_orders, [uid(4)]);
+    }
+}
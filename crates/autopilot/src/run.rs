@@ -14,6 +14,7 @@ use {
         domain,
         event_updater::EventUpdater,
         infra::{self, blockchain::ChainId},
+        replay,
         run_loop::RunLoop,
         shadow,
         solvable_orders::SolvableOrdersCache,
@@ -106,25 +107,40 @@ pub async fn start(args: impl Iterator<Item = String>) {
     tracing::info!("running autopilot with validated arguments:\n{}", args);
     observe::metrics::setup_registry(Some("gp_v2_autopilot".into()), None);
 
-    if args.drivers.is_empty() {
-        panic!("colocation is enabled but no drivers are configured");
-    }
-
     if args.shadow.is_some() {
         shadow_mode(args).await;
+    } else if args.replay.is_some() {
+        replay_mode(args).await;
     } else {
         run(args).await;
     }
 }
 
+/// Loads the hot-reloadable part of the configuration.
+async fn load_config(args: &Arguments) -> infra::config::Watcher {
+    let config = infra::config::Watcher::new(
+        infra::config::Config::new(args),
+        args.config.clone(),
+        args.config_update_interval,
+    )
+    .await;
+    if config.current().config.drivers.is_empty() {
+        panic!("colocation is enabled but no drivers are configured");
+    }
+    config
+}
+
 /// Assumes tracing and metrics registry have already been set up.
 pub async fn run(args: Arguments) {
     assert!(args.shadow.is_none(), "cannot run in shadow mode");
+    assert!(args.replay.is_none(), "cannot run in replay mode");
 
     let db = Postgres::new(args.db_url.as_str(), args.insert_batch_size)
         .await
         .unwrap();
     crate::database::run_database_metrics_work(db.clone());
+    let config = load_config(&args).await;
+    let initial_config = config.current();
 
     let http_factory = HttpClientFactory::new(&args.http_client);
     let web3 = shared::ethrpc::web3(
@@ -452,10 +468,15 @@ pub async fn run(args: Arguments) {
         args.limit_order_price_factor
             .try_into()
             .expect("limit order price factor can't be converted to BigDecimal"),
+        args.max_limit_orders_per_auction,
+        args.max_limit_orders_per_owner,
         domain::ProtocolFees::new(
-            &args.fee_policies,
+            &initial_config.config.fee_policies,
             args.fee_policy_max_partner_fee,
-            args.protocol_fee_exempt_addresses.as_slice(),
+            initial_config
+                .config
+                .protocol_fee_exempt_addresses
+                .as_slice(),
             args.enable_multiple_fees,
         ),
     );
@@ -463,6 +484,26 @@ pub async fn run(args: Arguments) {
     let liveness = Arc::new(Liveness::new(args.max_auction_age));
     shared::metrics::serve_metrics(liveness.clone(), args.metrics_address);
 
+    if let Some(start_block) = args.solver_rewards_start_block {
+        let solver_rewards = crate::solver_rewards::SolverRewards::new(
+            crate::solver_rewards::Config {
+                start_block,
+                period: args.solver_rewards_period,
+                confirmations: args.solver_rewards_confirmations,
+                max_observation_delay: args.solver_rewards_max_observation_delay,
+                upper_cap: args.solver_rewards_upper_cap,
+                lower_cap: args.solver_rewards_lower_cap,
+            },
+            db.clone(),
+            eth.current_block().clone(),
+        );
+        tokio::task::spawn(
+            solver_rewards
+                .run_forever()
+                .instrument(tracing::info_span!("solver_rewards")),
+        );
+    }
+
     let order_events_cleaner_config = crate::periodic_db_cleanup::OrderEventsCleanerConfig::new(
         args.order_events_cleanup_interval,
         args.order_events_cleanup_threshold,
@@ -487,27 +528,45 @@ pub async fn run(args: Arguments) {
     let market_makable_token_list =
         AutoUpdatingTokenList::from_configuration(market_makable_token_list_configuration).await;
 
+    let reputation = infra::solvers::Reputation::new(infra::solvers::reputation::Config {
+        window: args.driver_reputation_window,
+        min_success_rate: args.driver_min_success_rate,
+        cooldown: args.driver_cooldown,
+    });
+    if let Some(address) = args.admin_address {
+        infra::admin::serve(address, reputation.clone());
+    }
+
     let run = RunLoop {
         eth,
         solvable_orders_cache,
-        drivers: args
+        drivers: initial_config
+            .config
             .drivers
-            .into_iter()
-            .map(|driver| infra::Driver::new(driver.url, driver.name))
+            .iter()
+            .map(|driver| {
+                infra::Driver::new(driver.url.clone(), driver.name.clone())
+                    .with_view(driver.view.clone().into())
+            })
             .collect(),
         market_makable_token_list,
         submission_deadline: args.submission_deadline as u64,
         additional_deadline_for_rewards: args.additional_deadline_for_rewards as u64,
+        max_winners_per_auction: args.max_winners_per_auction.get(),
+        fairness_tolerance: args.fairness_tolerance,
         max_settlement_transaction_wait: args.max_settlement_transaction_wait,
-        solve_deadline: args.solve_deadline,
+        solve_deadline: initial_config.config.solve_deadline,
         in_flight_orders: Default::default(),
         persistence: persistence.clone(),
         liveness: liveness.clone(),
-        surplus_capturing_jit_order_owners: args
+        reputation,
+        surplus_capturing_jit_order_owners: initial_config
+            .config
             .protocol_fee_exempt_addresses
             .iter()
             .cloned()
             .collect::<HashSet<_>>(),
+        config,
     };
     run.run_forever().await;
     unreachable!("run loop exited");
@@ -521,42 +580,19 @@ async fn shadow_mode(args: Arguments) -> ! {
         args.shadow.expect("missing shadow mode configuration"),
     );
 
-    let drivers = args
+    // Shadow mode doesn't apply config changes while running.
+    let config = load_config(&args).await.current();
+    let drivers = config
+        .config
         .drivers
-        .into_iter()
-        .map(|driver| infra::Driver::new(driver.url, driver.name))
+        .iter()
+        .map(|driver| {
+            infra::Driver::new(driver.url.clone(), driver.name.clone())
+                .with_view(driver.view.clone().into())
+        })
         .collect();
 
-    let trusted_tokens = {
-        let web3 = shared::ethrpc::web3(
-            &args.shared.ethrpc,
-            &http_factory,
-            &args.shared.node_url,
-            "base",
-        );
-
-        let chain_id = web3
-            .eth()
-            .chain_id()
-            .await
-            .expect("Could not get chainId")
-            .as_u64();
-        if let Some(expected_chain_id) = args.shared.chain_id {
-            assert_eq!(
-                chain_id, expected_chain_id,
-                "connected to node with incorrect chain ID",
-            );
-        }
-
-        AutoUpdatingTokenList::from_configuration(TokenListConfiguration {
-            url: args.trusted_tokens_url,
-            update_interval: args.trusted_tokens_update_interval,
-            chain_id,
-            client: http_factory.create(),
-            hardcoded: args.trusted_tokens.unwrap_or_default(),
-        })
-        .await
-    };
+    let trusted_tokens = trusted_tokens(&args, &http_factory).await;
 
     let liveness = Arc::new(Liveness::new(args.max_auction_age));
     shared::metrics::serve_metrics(liveness.clone(), args.metrics_address);
@@ -565,9 +601,10 @@ async fn shadow_mode(args: Arguments) -> ! {
         orderbook,
         drivers,
         trusted_tokens,
-        args.solve_deadline,
+        config.config.solve_deadline,
         liveness.clone(),
-        &args
+        &config
+            .config
             .protocol_fee_exempt_addresses
             .iter()
             .cloned()
@@ -577,3 +614,97 @@ async fn shadow_mode(args: Arguments) -> ! {
 
     unreachable!("shadow run loop exited");
 }
+
+async fn replay_mode(args: Arguments) {
+    let http_factory = HttpClientFactory::new(&args.http_client);
+    let auction_id = args.replay.expect("missing replay auction");
+
+    let db = Postgres::new(args.db_url.as_str(), args.insert_batch_size)
+        .await
+        .unwrap();
+    let persistence = infra::persistence::Persistence::new(None, Arc::new(db)).await;
+
+    let config = load_config(&args).await.current();
+    let drivers = config
+        .config
+        .drivers
+        .iter()
+        .map(|driver| {
+            infra::Driver::new(driver.url.clone(), driver.name.clone())
+                .with_view(driver.view.clone().into())
+        })
+        .collect();
+    let trusted_tokens = trusted_tokens(&args, &http_factory).await;
+    let competition = shadow::Competition::new(
+        drivers,
+        trusted_tokens,
+        config.config.solve_deadline,
+        &config
+            .config
+            .protocol_fee_exempt_addresses
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>(),
+    );
+    let protocol_fees = domain::ProtocolFees::new(
+        &config.config.fee_policies,
+        args.fee_policy_max_partner_fee,
+        config.config.protocol_fee_exempt_addresses.as_slice(),
+        args.enable_multiple_fees,
+    );
+    let fork = args.replay_fork_url.as_ref().map(|url| replay::Fork {
+        web3: shared::ethrpc::web3(&args.shared.ethrpc, &http_factory, url, "fork"),
+        upstream: args.shared.node_url.clone(),
+    });
+
+    let report = replay::Replay::new(persistence, competition, protocol_fees, fork)
+        .run(auction_id)
+        .await
+        .expect("failed to replay auction");
+    let report = serde_json::to_string_pretty(&report).unwrap();
+    match &args.replay_report {
+        Some(path) => {
+            tokio::fs::write(path, report)
+                .await
+                .expect("failed to write replay report");
+            tracing::info!(?path, "wrote replay report");
+        }
+        None => tracing::info!(%report, "replay report"),
+    }
+}
+
+/// Sets up the trusted token list for the modes that don't run the full
+/// autopilot.
+async fn trusted_tokens(
+    args: &Arguments,
+    http_factory: &HttpClientFactory,
+) -> AutoUpdatingTokenList {
+    let web3 = shared::ethrpc::web3(
+        &args.shared.ethrpc,
+        http_factory,
+        &args.shared.node_url,
+        "base",
+    );
+
+    let chain_id = web3
+        .eth()
+        .chain_id()
+        .await
+        .expect("Could not get chainId")
+        .as_u64();
+    if let Some(expected_chain_id) = args.shared.chain_id {
+        assert_eq!(
+            chain_id, expected_chain_id,
+            "connected to node with incorrect chain ID",
+        );
+    }
+
+    AutoUpdatingTokenList::from_configuration(TokenListConfiguration {
+        url: args.trusted_tokens_url.clone(),
+        update_interval: args.trusted_tokens_update_interval,
+        chain_id,
+        client: http_factory.create(),
+        hardcoded: args.trusted_tokens.clone().unwrap_or_default(),
+    })
+    .await
+}
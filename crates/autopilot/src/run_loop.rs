@@ -1,6 +1,6 @@
 use {
     crate::{
-        database::competition::Competition,
+        database::competition::{Competition, Winner},
         domain::{
             self,
             auction::order::Class,
@@ -12,7 +12,10 @@ use {
         },
         infra::{
             self,
-            solvers::dto::{reveal, settle, solve},
+            solvers::{
+                dto::{reveal, settle, solve},
+                reputation,
+            },
         },
         run::Liveness,
         solvable_orders::SolvableOrdersCache,
@@ -23,15 +26,16 @@ use {
     model::solver_competition::{
         CompetitionAuction,
         Order,
+        RejectedSolution,
         Score,
         SolverCompetitionDB,
         SolverSettlement,
     },
-    primitive_types::{H160, H256},
+    primitive_types::{H160, H256, U256},
     rand::seq::SliceRandom,
     shared::token_list::AutoUpdatingTokenList,
     std::{
-        collections::{BTreeMap, HashSet},
+        collections::{BTreeMap, HashMap, HashSet},
         sync::Arc,
         time::{Duration, Instant},
     },
@@ -42,6 +46,7 @@ use {
 pub struct RunLoop {
     pub eth: infra::Ethereum,
     pub persistence: infra::Persistence,
+    pub config: infra::config::Watcher,
     pub drivers: Vec<infra::Driver>,
 
     pub solvable_orders_cache: Arc<SolvableOrdersCache>,
@@ -50,16 +55,23 @@ pub struct RunLoop {
     pub additional_deadline_for_rewards: u64,
     pub max_settlement_transaction_wait: Duration,
     pub solve_deadline: Duration,
-    pub in_flight_orders: Arc<Mutex<Option<InFlightOrders>>>,
+    pub max_winners_per_auction: usize,
+    pub fairness_tolerance: Option<f64>,
+    pub in_flight_orders: Arc<Mutex<Vec<InFlightOrders>>>,
     pub liveness: Arc<Liveness>,
+    pub reputation: infra::solvers::Reputation,
     pub surplus_capturing_jit_order_owners: HashSet<H160>,
 }
 
 impl RunLoop {
-    pub async fn run_forever(self) -> ! {
+    pub async fn run_forever(mut self) -> ! {
         let mut last_auction = None;
         let mut last_block = None;
+        Metrics::config_version(self.config.current().version);
         loop {
+            if let Some(config) = self.config.changed() {
+                self.apply_config(&config);
+            }
             if let Some(domain::AuctionWithId { id, auction }) = self.next_auction().await {
                 let current_block = self.eth.current_block().borrow().hash;
                 // Only run the solvers if the auction or block has changed.
@@ -79,6 +91,36 @@ impl RunLoop {
         }
     }
 
+    /// Switches to a new configuration. This only happens between auctions so
+    /// that every auction gets solved with a consistent configuration. Drivers
+    /// and the solve deadline apply right away while new fee policies only
+    /// apply once the solvable orders cache rebuilt the auction, which happens
+    /// on the next block. Until then no auction gets run.
+    fn apply_config(&mut self, config: &infra::config::Versioned) {
+        self.drivers = config
+            .config
+            .drivers
+            .iter()
+            .map(|driver| {
+                infra::Driver::new(driver.url.clone(), driver.name.clone())
+                    .with_view(driver.view.clone().into())
+            })
+            .collect();
+        self.solve_deadline = config.config.solve_deadline;
+        self.surplus_capturing_jit_order_owners = config
+            .config
+            .protocol_fee_exempt_addresses
+            .iter()
+            .cloned()
+            .collect();
+        self.solvable_orders_cache.update_protocol_fees(
+            &config.config.fee_policies,
+            &config.config.protocol_fee_exempt_addresses,
+        );
+        Metrics::config_version(config.version);
+        tracing::info!(version = config.version, "applied config");
+    }
+
     async fn next_auction(&self) -> Option<domain::AuctionWithId> {
         let auction = match self.solvable_orders_cache.current_auction() {
             Some(auction) => auction,
@@ -130,80 +172,27 @@ impl RunLoop {
             solutions.sort_unstable_by_key(|participant| participant.solution.score().get().0);
             solutions
         };
+        // The fairness check never rejects all solutions, so the rejections
+        // always end up in the stored competition.
+        let (solutions, rejected_solutions) = match self.fairness_tolerance {
+            Some(tolerance) => reject_unfair(solutions, tolerance),
+            None => (solutions, Vec::new()),
+        };
         let competition_simulation_block = self.eth.current_block().borrow().number;
 
-        // TODO: Keep going with other solutions until some deadline.
-        if let Some(Participant { driver, solution }) = solutions.last() {
-            tracing::info!(driver = %driver.name, solution = %solution.id(), "winner");
-
-            let revealed = match self.reveal(driver, auction_id, solution.id()).await {
-                Ok(result) => {
-                    Metrics::reveal_ok(driver);
-                    result
-                }
-                Err(err) => {
-                    Metrics::reveal_err(driver, &err);
-                    tracing::warn!(driver = %driver.name, ?err, "failed to reveal winning solution");
-                    return;
-                }
-            };
-
-            let order_uids = solution.order_ids().copied().collect();
-            self.persistence
-                .store_order_events(order_uids, OrderEventLabel::Considered);
-
-            let winner = solution.solver().into();
-            let winning_score = solution.score().get().0;
-            let reference_score = solutions
-                .iter()
-                .nth_back(1)
-                .map(|participant| participant.solution.score().get().0)
-                .unwrap_or_default();
-            let participants = solutions
+        let competition = Competition {
+            auction_id,
+            winners: Vec::new(),
+            participants: solutions
                 .iter()
                 .map(|participant| participant.solution.solver().into())
-                .collect::<HashSet<_>>();
-
-            let mut prices = BTreeMap::new();
-            let mut fee_policies = Vec::new();
-            let block_deadline = competition_simulation_block
+                .collect(),
+            prices: BTreeMap::new(),
+            block_deadline: competition_simulation_block
                 + self.submission_deadline
-                + self.additional_deadline_for_rewards;
-            let call_data = revealed.calldata.internalized.clone();
-            let uninternalized_call_data = revealed.calldata.uninternalized.clone();
-
-            for order_id in solution.order_ids() {
-                let auction_order = auction
-                    .orders
-                    .iter()
-                    .find(|auction_order| &auction_order.uid == order_id);
-                match auction_order {
-                    Some(auction_order) => {
-                        fee_policies.push((auction_order.uid, auction_order.protocol_fees.clone()));
-                        if let Some(price) = auction.prices.get(&auction_order.sell_token) {
-                            prices.insert(auction_order.sell_token, *price);
-                        } else {
-                            tracing::error!(
-                                sell_token = ?auction_order.sell_token,
-                                "sell token price is missing in auction"
-                            );
-                        }
-                        if let Some(price) = auction.prices.get(&auction_order.buy_token) {
-                            prices.insert(auction_order.buy_token, *price);
-                        } else {
-                            tracing::error!(
-                                buy_token = ?auction_order.buy_token,
-                                "buy token price is missing in auction"
-                            );
-                        }
-                    }
-                    None => {
-                        tracing::debug!(?order_id, "order not found in auction");
-                    }
-                }
-            }
-
-            let competition_table = SolverCompetitionDB {
+                + self.additional_deadline_for_rewards,
+            competition_simulation_block,
+            competition_table: SolverCompetitionDB {
                 auction_start_block: auction.block,
                 competition_simulation_block,
                 auction: CompetitionAuction {
@@ -217,88 +206,362 @@ impl RunLoop {
                 solutions: solutions
                     .iter()
                     .enumerate()
-                    .map(|(index, participant)| {
-                        let is_winner = solutions.len() - index == 1;
-                        let mut settlement = SolverSettlement {
-                            solver: participant.driver.name.clone(),
-                            solver_address: participant.solution.solver().0,
-                            score: Some(Score::Solver(participant.solution.score().get().0)),
-                            ranking: solutions.len() - index,
-                            orders: participant
-                                .solution
-                                .orders()
-                                .iter()
-                                .map(|(id, order)| Order::Colocated {
-                                    id: (*id).into(),
-                                    sell_amount: order.sell.into(),
-                                    buy_amount: order.buy.into(),
-                                })
-                                .collect(),
-                            clearing_prices: participant
-                                .solution
-                                .prices()
-                                .iter()
-                                .map(|(token, price)| (token.0, price.get().into()))
-                                .collect(),
-                            call_data: None,
-                            uninternalized_call_data: None,
-                        };
-                        if is_winner {
-                            settlement.call_data = Some(revealed.calldata.internalized.clone());
-                            settlement.uninternalized_call_data =
-                                Some(revealed.calldata.uninternalized.clone());
-                        }
-                        settlement
+                    .map(|(index, participant)| SolverSettlement {
+                        solver: participant.driver.name.clone(),
+                        solver_address: participant.solution.solver().0,
+                        score: Some(Score::Solver(participant.solution.score().get().0)),
+                        ranking: solutions.len() - index,
+                        orders: participant
+                            .solution
+                            .orders()
+                            .iter()
+                            .map(|(id, order)| Order::Colocated {
+                                id: (*id).into(),
+                                sell_amount: order.sell.into(),
+                                buy_amount: order.buy.into(),
+                            })
+                            .collect(),
+                        clearing_prices: participant
+                            .solution
+                            .prices()
+                            .iter()
+                            .map(|(token, price)| (token.0, price.get().into()))
+                            .collect(),
+                        call_data: None,
+                        uninternalized_call_data: None,
                     })
                     .collect(),
+                settled_rankings: Vec::new(),
+                rejected_solutions,
+            },
+        };
+
+        let settled = if self.max_winners_per_auction > 1 {
+            self.settle_winners(&auction, &solutions, competition).await
+        } else {
+            self.settle_with_fallback(&auction, &solutions, competition)
+                .await
+        };
+        if !settled.is_empty() {
+            *self.in_flight_orders.lock().await = settled;
+        }
+    }
+
+    /// Asks the best solution to settle the auction and walks down the ranking
+    /// until one of the solutions gets settled or the submission deadline is
+    /// reached.
+    async fn settle_with_fallback(
+        &self,
+        auction: &domain::Auction,
+        solutions: &[Participant<'_>],
+        mut competition: Competition,
+    ) -> Vec<InFlightOrders> {
+        let auction_id = competition.auction_id;
+        let submission_deadline =
+            competition.competition_simulation_block + self.submission_deadline;
+        let mut saved = false;
+        let mut stored_fee_policies = HashSet::new();
+        let mut attempted = Vec::new();
+        let mut settled = Vec::new();
+
+        let ranked = solutions.iter().rev().collect::<Vec<_>>();
+        let ranked_solutions = ranked
+            .iter()
+            .map(|participant| &participant.solution)
+            .collect::<Vec<_>>();
+        let mut next = 0;
+        loop {
+            let block = self.eth.current_block().borrow().number;
+            let still_solvable = |solution: &competition::Solution| {
+                let solvable = self.still_solvable(auction, solution);
+                if !solvable {
+                    tracing::debug!(
+                        solution = %solution.id(),
+                        "fallback solution contains orders that are no longer solvable"
+                    );
+                }
+                solvable
             };
-            let competition = Competition {
-                auction_id,
-                winner,
-                winning_score,
+            let Some(competition::Fallback {
+                index: ranked_index,
                 reference_score,
-                participants,
-                prices,
-                block_deadline,
-                competition_simulation_block,
-                call_data,
-                uninternalized_call_data,
-                competition_table,
+            }) = competition::next_fallback(
+                &ranked_solutions,
+                next,
+                block,
+                submission_deadline,
+                still_solvable,
+            )
+            else {
+                if next > 0 && next < ranked.len() && block >= submission_deadline {
+                    tracing::info!("submission deadline reached, no more fallback solutions");
+                }
+                break;
             };
-
-            tracing::info!(?competition, "saving competition");
-            if let Err(err) = self.persistence.save_competition(&competition).await {
-                tracing::error!(?err, "failed to save competition");
-                return;
+            next = ranked_index + 1;
+            let Participant { driver, solution } = ranked[ranked_index];
+            let ranking = ranked_index + 1;
+            let index = solutions.len() - ranking;
+            tracing::info!(driver = %driver.name, solution = %solution.id(), ranking, "winner");
+
+            let Some(winner) = self
+                .reveal_winner(driver, auction_id, solution, reference_score.0)
+                .await
+            else {
+                continue;
+            };
+            // Previous winners that failed to settle stay in the competition
+            // so that they get penalized.
+            let fee_policies = add_winner(&mut competition, auction, index, solution, winner)
+                .into_iter()
+                .filter(|(uid, _)| stored_fee_policies.insert(*uid))
+                .collect();
+            if !self
+                .store_competition(&competition, saved, fee_policies)
+                .await
+            {
+                break;
             }
+            saved = true;
 
-            tracing::info!("saving fee policies");
-            if let Err(err) = self
-                .persistence
-                .store_fee_policies(auction_id, fee_policies)
+            attempted.push(solution);
+            if let Ok(in_flight) = self
+                .settle_winner(driver, solution, auction_id, submission_deadline)
                 .await
             {
-                Metrics::fee_policies_store_error();
-                tracing::warn!(?err, "failed to save fee policies");
+                settled.push(in_flight);
+                Metrics::settled_ranking(ranking);
+                competition.competition_table.settled_rankings.push(ranking);
+                self.store_settled_rankings(&competition).await;
+                break;
             }
+        }
 
-            tracing::info!(driver = %driver.name, "settling");
-            let submission_start = Instant::now();
-            match self.settle(driver, solution, auction_id).await {
-                Ok(()) => Metrics::settle_ok(driver, submission_start.elapsed()),
-                Err(err) => {
-                    Metrics::settle_err(driver, &err, submission_start.elapsed());
-                    tracing::warn!(?err, driver = %driver.name, "settlement failed");
-                }
+        if let Some(Participant { driver, .. }) = solutions.last() {
+            Metrics::matched_unsettled(driver, solutions, &attempted);
+        }
+        settled
+    }
+
+    /// Selects the best set of non-conflicting solutions and lets all of
+    /// their drivers settle them in parallel.
+    async fn settle_winners(
+        &self,
+        auction: &domain::Auction,
+        solutions: &[Participant<'_>],
+        mut competition: Competition,
+    ) -> Vec<InFlightOrders> {
+        let auction_id = competition.auction_id;
+        let submission_deadline =
+            competition.competition_simulation_block + self.submission_deadline;
+
+        let ranked = solutions
+            .iter()
+            .rev()
+            .map(|participant| &participant.solution)
+            .collect::<Vec<_>>();
+        let winners = domain::competition::select_winners(
+            ranked.iter().copied(),
+            self.max_winners_per_auction,
+        );
+        let reference_scores =
+            domain::competition::reference_scores(&ranked, &winners, self.max_winners_per_auction);
+
+        let revealed = futures::future::join_all(winners.iter().zip(reference_scores).map(
+            |(ranked_index, reference_score)| async move {
+                let index = solutions.len() - 1 - ranked_index;
+                let Participant { driver, solution } = &solutions[index];
+                tracing::info!(
+                    driver = %driver.name,
+                    solution = %solution.id(),
+                    ranking = ranked_index + 1,
+                    "winner"
+                );
+                self.reveal_winner(driver, auction_id, solution, reference_score.0)
+                    .await
+                    .map(|winner| (index, winner))
+            },
+        ))
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        if revealed.is_empty() {
+            return Vec::new();
+        }
+
+        let mut fee_policies = Vec::new();
+        let mut indices = Vec::new();
+        for (index, winner) in revealed {
+            let solution = &solutions[index].solution;
+            fee_policies.extend(add_winner(
+                &mut competition,
+                auction,
+                index,
+                solution,
+                winner,
+            ));
+            indices.push(index);
+        }
+        if !self
+            .store_competition(&competition, false, fee_policies)
+            .await
+        {
+            return Vec::new();
+        }
+
+        let results = futures::future::join_all(indices.iter().map(|index| async move {
+            let Participant { driver, solution } = &solutions[*index];
+            let result = self
+                .settle_winner(driver, solution, auction_id, submission_deadline)
+                .await;
+            (solutions.len() - index, result)
+        }))
+        .await;
+        let mut settled = Vec::new();
+        for (ranking, result) in results {
+            if let Ok(in_flight) = result {
+                settled.push(in_flight);
+                Metrics::settled_ranking(ranking);
+                competition.competition_table.settled_rankings.push(ranking);
             }
-            let solution_uids = solution.order_ids().copied().collect::<HashSet<_>>();
-            let unsettled_orders: HashSet<_> = solutions
-                .iter()
-                .flat_map(|p| p.solution.order_ids())
-                .filter(|uid| !solution_uids.contains(uid))
-                .collect();
-            Metrics::matched_unsettled(driver, unsettled_orders);
         }
+        if !settled.is_empty() {
+            self.store_settled_rankings(&competition).await;
+        }
+
+        let attempted = indices
+            .iter()
+            .map(|index| &solutions[*index].solution)
+            .collect::<Vec<_>>();
+        if let Some(Participant { driver, .. }) = solutions.last() {
+            Metrics::matched_unsettled(driver, solutions, &attempted);
+        }
+        settled
+    }
+
+    /// Asks the driver to reveal a winning solution and prepares the data that
+    /// gets stored about the winner.
+    async fn reveal_winner(
+        &self,
+        driver: &infra::Driver,
+        auction_id: domain::auction::Id,
+        solution: &competition::Solution,
+        reference_score: U256,
+    ) -> Option<Winner> {
+        let revealed = match self.reveal(driver, auction_id, solution.id()).await {
+            Ok(result) => {
+                Metrics::reveal_ok(driver);
+                self.reputation
+                    .record(&driver.name, reputation::Request::Reveal, true);
+                result
+            }
+            Err(err) => {
+                Metrics::reveal_err(driver, &err);
+                self.reputation
+                    .record(&driver.name, reputation::Request::Reveal, false);
+                tracing::warn!(driver = %driver.name, ?err, "failed to reveal winning solution");
+                return None;
+            }
+        };
+
+        let order_uids = solution.order_ids().copied().collect();
+        self.persistence
+            .store_order_events(order_uids, OrderEventLabel::Considered);
+
+        Some(Winner {
+            solver: solution.solver().into(),
+            score: solution.score().get().0,
+            reference_score,
+            call_data: revealed.calldata.internalized,
+            uninternalized_call_data: revealed.calldata.uninternalized,
+        })
+    }
+
+    /// Saves (or updates) the competition and the fee policies of the newly
+    /// added winners. Returns whether the competition got stored.
+    async fn store_competition(
+        &self,
+        competition: &Competition,
+        update: bool,
+        fee_policies: Vec<(domain::OrderUid, Vec<domain::fee::ScopedPolicy>)>,
+    ) -> bool {
+        tracing::info!(?competition, "saving competition");
+        let result = if update {
+            self.persistence.update_competition(competition).await
+        } else {
+            self.persistence.save_competition(competition).await
+        };
+        if let Err(err) = result {
+            tracing::error!(?err, "failed to save competition");
+            return false;
+        }
+
+        tracing::info!("saving fee policies");
+        if let Err(err) = self
+            .persistence
+            .store_fee_policies(competition.auction_id, fee_policies)
+            .await
+        {
+            Metrics::fee_policies_store_error();
+            tracing::warn!(?err, "failed to save fee policies");
+        }
+        true
+    }
+
+    async fn store_settled_rankings(&self, competition: &Competition) {
+        if let Err(err) = self.persistence.update_competition(competition).await {
+            tracing::error!(?err, "failed to save settled rankings");
+        }
+    }
+
+    /// Asks the driver to settle a winning solution before the deadline.
+    async fn settle_winner(
+        &self,
+        driver: &infra::Driver,
+        solution: &competition::Solution,
+        auction_id: domain::auction::Id,
+        deadline: u64,
+    ) -> Result<InFlightOrders, SettleError> {
+        tracing::info!(driver = %driver.name, "settling");
+        let submission_start = Instant::now();
+        match self.settle(driver, solution, auction_id, deadline).await {
+            Ok(tx_hash) => {
+                Metrics::settle_ok(driver, submission_start.elapsed());
+                self.reputation
+                    .record(&driver.name, reputation::Request::Settle, true);
+                Ok(InFlightOrders {
+                    tx_hash,
+                    orders: solution.order_ids().copied().collect(),
+                })
+            }
+            Err(err) => {
+                Metrics::settle_err(driver, &err, submission_start.elapsed());
+                self.reputation
+                    .record(&driver.name, reputation::Request::Settle, false);
+                tracing::warn!(?err, driver = %driver.name, "settlement failed");
+                Err(err)
+            }
+        }
+    }
+
+    /// Checks whether all auction orders of a fallback solution are still part
+    /// of the most recent auction, i.e. they didn't get filled, cancelled or
+    /// expired since the competition took place.
+    fn still_solvable(&self, auction: &domain::Auction, solution: &competition::Solution) -> bool {
+        let Some(current) = self.solvable_orders_cache.current_auction() else {
+            return false;
+        };
+        let solvable = current
+            .orders
+            .iter()
+            .map(|order| order.uid)
+            .collect::<HashSet<_>>();
+        solution
+            .order_ids()
+            // JIT orders are not part of the auction.
+            .filter(|uid| auction.orders.iter().any(|order| &order.uid == *uid))
+            .all(|uid| solvable.contains(uid))
     }
 
     /// Runs the solver competition, making all configured drivers participate.
@@ -320,16 +583,38 @@ impl RunLoop {
         self.persistence
             .store_order_events(order_uids, OrderEventLabel::Ready);
 
+        let drivers = self
+            .reputation
+            .allowed(&self.drivers, |driver| &driver.name);
         let start = Instant::now();
-        futures::future::join_all(self.drivers.iter().map(|driver| async move {
+        futures::future::join_all(drivers.into_iter().map(|driver| async move {
+            // Drivers with a restricted view get sent their own trimmed auction.
+            let restricted = (!driver.view.is_unrestricted()).then(|| {
+                solve::Request::new(
+                    id,
+                    &driver.view.apply(auction),
+                    &self.market_makable_token_list.all(),
+                    self.solve_deadline,
+                    &self.surplus_capturing_jit_order_owners,
+                )
+            });
+            let request = restricted.as_ref().unwrap_or(request);
             let result = self.solve(driver, request).await;
             let solutions = match result {
                 Ok(solutions) => {
                     Metrics::solve_ok(driver, start.elapsed());
+                    self.reputation
+                        .record(&driver.name, reputation::Request::Solve, true);
                     solutions
                 }
                 Err(err) => {
                     Metrics::solve_err(driver, start.elapsed(), &err);
+                    // Not finding a solution is no sign of a misbehaving driver.
+                    self.reputation.record(
+                        &driver.name,
+                        reputation::Request::Solve,
+                        matches!(err, SolveError::NoSolutions),
+                    );
                     if matches!(err, SolveError::NoSolutions) {
                         tracing::debug!(driver = %driver.name, "solver found no solution");
                     } else {
@@ -396,14 +681,15 @@ impl RunLoop {
         Ok(response)
     }
 
-    /// Execute the solver's solution. Returns Ok when the corresponding
-    /// transaction has been mined.
+    /// Execute the solver's solution. Returns the hash of the corresponding
+    /// transaction once it has been mined.
     async fn settle(
         &self,
         driver: &infra::Driver,
         solved: &competition::Solution,
         auction_id: i64,
-    ) -> Result<(), SettleError> {
+        deadline: u64,
+    ) -> Result<H256, SettleError> {
         let order_ids = solved.order_ids().copied().collect();
         self.persistence
             .store_order_events(order_ids, OrderEventLabel::Executing);
@@ -412,15 +698,11 @@ impl RunLoop {
             solution_id: solved.id(),
         };
         let tx_hash = self
-            .wait_for_settlement(driver, auction_id, request)
+            .wait_for_settlement(driver, auction_id, solved.solver().0, request, deadline)
             .await?;
-        *self.in_flight_orders.lock().await = Some(InFlightOrders {
-            tx_hash,
-            orders: solved.order_ids().copied().collect(),
-        });
         tracing::debug!(?tx_hash, "solution settled");
 
-        Ok(())
+        Ok(tx_hash)
     }
 
     /// Wait for either the settlement transaction to be mined or the driver
@@ -429,10 +711,12 @@ impl RunLoop {
         &self,
         driver: &infra::Driver,
         auction_id: i64,
+        solver: H160,
         request: settle::Request,
+        deadline: u64,
     ) -> Result<H256, SettleError> {
         match futures::future::select(
-            Box::pin(self.wait_for_settlement_transaction(auction_id, self.submission_deadline)),
+            Box::pin(self.wait_for_settlement_transaction(auction_id, solver, deadline)),
             Box::pin(driver.settle(&request, self.max_settlement_transaction_wait)),
         )
         .await
@@ -448,17 +732,18 @@ impl RunLoop {
         }
     }
 
-    /// Tries to find a `settle` contract call with calldata ending in `tag`.
+    /// Tries to find a `settle` contract call of `solver` with calldata ending
+    /// in `tag`.
     ///
-    /// Returns None if no transaction was found within the deadline or the task
-    /// is cancelled.
+    /// Returns None if no transaction was found before the `deadline` block or
+    /// the task is cancelled.
     async fn wait_for_settlement_transaction(
         &self,
         auction_id: i64,
-        max_blocks_wait: u64,
+        solver: H160,
+        deadline: u64,
     ) -> Result<H256, SettleError> {
         let current = self.eth.current_block().borrow().number;
-        let deadline = current.saturating_add(max_blocks_wait);
         tracing::debug!(%current, %deadline, %auction_id, "waiting for tag");
         loop {
             if self.eth.current_block().borrow().number > deadline {
@@ -467,7 +752,7 @@ impl RunLoop {
 
             match self
                 .persistence
-                .find_tx_hash_by_auction_id(auction_id)
+                .find_tx_hash_by_auction_id(auction_id, solver)
                 .await
             {
                 Ok(Some(hash)) => return Ok(hash),
@@ -486,31 +771,123 @@ impl RunLoop {
     /// Removes orders that are currently being settled to avoid solvers trying
     /// to fill an order a second time.
     async fn remove_in_flight_orders(&self, mut auction: domain::Auction) -> domain::Auction {
-        let Some(in_flight) = &*self.in_flight_orders.lock().await else {
-            return auction;
-        };
-
-        let transaction = self.eth.transaction(in_flight.tx_hash.into()).await;
-
-        let prev_settlement_block = match transaction {
-            Ok(transaction) => transaction.block,
-            // Could not find the block of the previous settlement, let's be
-            // conservative and assume all orders are still in-flight.
-            _ => u64::MAX.into(),
-        };
+        for in_flight in &*self.in_flight_orders.lock().await {
+            let transaction = self.eth.transaction(in_flight.tx_hash.into()).await;
+
+            let prev_settlement_block = match transaction {
+                Ok(transaction) => transaction.block,
+                // Could not find the block of the previous settlement, let's be
+                // conservative and assume all orders are still in-flight.
+                _ => u64::MAX.into(),
+            };
 
-        if auction.latest_settlement_block < prev_settlement_block.0 {
-            // Auction was built before the in-flight orders were processed.
-            auction
-                .orders
-                .retain(|o| !in_flight.orders.contains(&o.uid));
-            tracing::debug!(orders = ?in_flight.orders, "filtered out in-flight orders");
+            if auction.latest_settlement_block < prev_settlement_block.0 {
+                // Auction was built before the in-flight orders were processed.
+                auction
+                    .orders
+                    .retain(|o| !in_flight.orders.contains(&o.uid));
+                tracing::debug!(orders = ?in_flight.orders, "filtered out in-flight orders");
+            }
         }
 
         auction
     }
 }
 
+/// Records a revealed winner in the competition. Returns the fee policies of
+/// the winner's orders.
+fn add_winner(
+    competition: &mut Competition,
+    auction: &domain::Auction,
+    index: usize,
+    solution: &competition::Solution,
+    winner: Winner,
+) -> Vec<(domain::OrderUid, Vec<domain::fee::ScopedPolicy>)> {
+    let mut fee_policies = Vec::new();
+    for order_id in solution.order_ids() {
+        let auction_order = auction
+            .orders
+            .iter()
+            .find(|auction_order| &auction_order.uid == order_id);
+        match auction_order {
+            Some(auction_order) => {
+                fee_policies.push((auction_order.uid, auction_order.protocol_fees.clone()));
+                if let Some(price) = auction.prices.get(&auction_order.sell_token) {
+                    competition.prices.insert(auction_order.sell_token, *price);
+                } else {
+                    tracing::error!(
+                        sell_token = ?auction_order.sell_token,
+                        "sell token price is missing in auction"
+                    );
+                }
+                if let Some(price) = auction.prices.get(&auction_order.buy_token) {
+                    competition.prices.insert(auction_order.buy_token, *price);
+                } else {
+                    tracing::error!(
+                        buy_token = ?auction_order.buy_token,
+                        "buy token price is missing in auction"
+                    );
+                }
+            }
+            None => {
+                tracing::debug!(?order_id, "order not found in auction");
+            }
+        }
+    }
+
+    let settlement = &mut competition.competition_table.solutions[index];
+    settlement.call_data = Some(winner.call_data.clone());
+    settlement.uninternalized_call_data = Some(winner.uninternalized_call_data.clone());
+    competition.winners.push(winner);
+    fee_policies
+}
+
+/// Removes the solutions that execute some orders materially worse for the
+/// user than another remaining solution does, see
+/// [`domain::competition::reject_unfair`]. Returns the remaining solutions and
+/// the record of the removed ones.
+fn reject_unfair(
+    solutions: Vec<Participant<'_>>,
+    tolerance: f64,
+) -> (Vec<Participant<'_>>, Vec<RejectedSolution>) {
+    // Solutions are sorted by score with the best one last.
+    let ranked = solutions
+        .iter()
+        .rev()
+        .map(|participant| &participant.solution)
+        .collect::<Vec<_>>();
+    let mut unfair = domain::competition::reject_unfair(&ranked, tolerance)
+        .into_iter()
+        .map(|(ranked_index, orders)| (solutions.len() - 1 - ranked_index, orders))
+        .collect::<HashMap<_, _>>();
+
+    let mut rejected = Vec::new();
+    let fair = solutions
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, participant)| {
+            let Some(unfair_orders) = unfair.remove(&index) else {
+                return Some(participant);
+            };
+            tracing::info!(
+                driver = %participant.driver.name,
+                solution = %participant.solution.id(),
+                orders = ?unfair_orders,
+                "rejecting unfair solution"
+            );
+            Metrics::unfair_solution(participant.driver);
+            rejected.push(RejectedSolution {
+                solver: participant.driver.name.clone(),
+                solver_address: participant.solution.solver().0,
+                score: participant.solution.score().get().0,
+                unfair_orders: unfair_orders.into_iter().map(Into::into).collect(),
+            });
+            None
+        })
+        .collect();
+    (fair, rejected)
+}
+
 /// Orders settled in the previous auction that might still be in-flight.
 #[derive(Default)]
 pub struct InFlightOrders {
@@ -554,6 +931,9 @@ struct Metrics {
     /// Tracks the last executed auction.
     auction: prometheus::IntGauge,
 
+    /// Tracks the version of the active config.
+    config_version: prometheus::IntGauge,
+
     /// Tracks the duration of successful driver `/solve` requests.
     #[metric(
         labels("driver", "result"),
@@ -575,11 +955,19 @@ struct Metrics {
     #[metric(labels("driver", "result"))]
     settle_time: prometheus::IntCounterVec,
 
+    /// Tracks the ranking of the solutions that got settled.
+    #[metric(labels("ranking"))]
+    settled_ranking: prometheus::IntCounterVec,
+
     /// Tracks the number of orders that were part of some but not the winning
     /// solution together with the winning driver that did't include it.
     #[metric(labels("ignored_by"))]
     matched_unsettled: prometheus::IntCounterVec,
 
+    /// Tracks the solutions rejected by the fairness check.
+    #[metric(labels("driver"))]
+    unfair_solutions: prometheus::IntCounterVec,
+
     /// Tracks the number of database errors.
     #[metric(labels("error_type"))]
     db_metric_error: prometheus::IntCounterVec,
@@ -594,6 +982,12 @@ impl Metrics {
         Self::get().auction.set(auction_id)
     }
 
+    fn config_version(version: u64) {
+        Self::get()
+            .config_version
+            .set(version.try_into().unwrap_or(i64::MAX))
+    }
+
     fn solve_ok(driver: &infra::Driver, elapsed: Duration) {
         Self::get()
             .solve
@@ -666,7 +1060,27 @@ impl Metrics {
             .inc_by(time.as_millis().try_into().unwrap_or(u64::MAX));
     }
 
-    fn matched_unsettled(winning: &infra::Driver, unsettled: HashSet<&domain::OrderUid>) {
+    fn settled_ranking(ranking: usize) {
+        Self::get()
+            .settled_ranking
+            .with_label_values(&[&ranking.to_string()])
+            .inc();
+    }
+
+    fn matched_unsettled(
+        winning: &infra::Driver,
+        solutions: &[Participant],
+        winners: &[&competition::Solution],
+    ) {
+        let winner_uids = winners
+            .iter()
+            .flat_map(|solution| solution.order_ids())
+            .collect::<HashSet<_>>();
+        let unsettled: HashSet<_> = solutions
+            .iter()
+            .flat_map(|p| p.solution.order_ids())
+            .filter(|uid| !winner_uids.contains(uid))
+            .collect();
         if !unsettled.is_empty() {
             tracing::debug!(?unsettled, "some orders were matched but not settled");
         }
@@ -676,6 +1090,13 @@ impl Metrics {
             .inc_by(unsettled.len() as u64);
     }
 
+    fn unfair_solution(driver: &infra::Driver) {
+        Self::get()
+            .unfair_solutions
+            .with_label_values(&[&driver.name])
+            .inc();
+    }
+
     fn fee_policies_store_error() {
         Self::get()
             .db_metric_error
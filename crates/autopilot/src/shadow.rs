@@ -9,6 +9,7 @@
 
 use {
     crate::{
+        boundary,
         domain::{self, auction::order::Class},
         infra::{
             self,
@@ -28,13 +29,10 @@ use {
 
 pub struct RunLoop {
     orderbook: infra::shadow::Orderbook,
-    drivers: Vec<infra::Driver>,
-    trusted_tokens: AutoUpdatingTokenList,
+    competition: Competition,
     auction: domain::auction::Id,
     block: u64,
-    solve_deadline: Duration,
     liveness: Arc<Liveness>,
-    surplus_capturing_jit_order_owners: HashSet<H160>,
 }
 
 impl RunLoop {
@@ -48,13 +46,15 @@ impl RunLoop {
     ) -> Self {
         Self {
             orderbook,
-            drivers,
-            trusted_tokens,
+            competition: Competition::new(
+                drivers,
+                trusted_tokens,
+                solve_deadline,
+                surplus_capturing_jit_order_owners,
+            ),
             auction: 0,
             block: 0,
-            solve_deadline,
             liveness,
-            surplus_capturing_jit_order_owners: surplus_capturing_jit_order_owners.clone(),
         }
     }
 
@@ -118,11 +118,7 @@ impl RunLoop {
         Metrics::get().auction.set(id);
         Metrics::get().orders.set(auction.orders.len() as _);
 
-        let mut participants = self.competition(id, auction).await;
-
-        // Shuffle so that sorting randomly splits ties.
-        participants.shuffle(&mut rand::thread_rng());
-        participants.sort_unstable_by_key(|participant| cmp::Reverse(participant.score()));
+        let participants = self.competition.run(id, auction).await;
 
         if let Some(Participant {
             driver,
@@ -183,9 +179,35 @@ impl RunLoop {
             };
         }
     }
+}
+
+/// Runs solver competitions between the configured drivers without settling
+/// the winning solutions.
+pub struct Competition {
+    drivers: Vec<infra::Driver>,
+    trusted_tokens: AutoUpdatingTokenList,
+    solve_deadline: Duration,
+    surplus_capturing_jit_order_owners: HashSet<H160>,
+}
+
+impl Competition {
+    pub fn new(
+        drivers: Vec<infra::Driver>,
+        trusted_tokens: AutoUpdatingTokenList,
+        solve_deadline: Duration,
+        surplus_capturing_jit_order_owners: &HashSet<H160>,
+    ) -> Self {
+        Self {
+            drivers,
+            trusted_tokens,
+            solve_deadline,
+            surplus_capturing_jit_order_owners: surplus_capturing_jit_order_owners.clone(),
+        }
+    }
 
     /// Runs the solver competition, making all configured drivers participate.
-    async fn competition(
+    /// The participants are sorted by their score, best first.
+    pub async fn run(
         &self,
         id: domain::auction::Id,
         auction: &domain::Auction,
@@ -199,11 +221,27 @@ impl RunLoop {
         );
         let request = &request;
 
-        futures::future::join_all(self.drivers.iter().map(|driver| async move {
-            let solution = self.participate(driver, request).await;
-            Participant { driver, solution }
-        }))
-        .await
+        let mut participants =
+            futures::future::join_all(self.drivers.iter().map(|driver| async move {
+                let restricted = (!driver.view.is_unrestricted()).then(|| {
+                    solve::Request::new(
+                        id,
+                        &driver.view.apply(auction),
+                        &self.trusted_tokens.all(),
+                        self.solve_deadline,
+                        &self.surplus_capturing_jit_order_owners,
+                    )
+                });
+                let request = restricted.as_ref().unwrap_or(request);
+                let solution = self.participate(driver, request).await;
+                Participant { driver, solution }
+            }))
+            .await;
+
+        // Shuffle so that sorting randomly splits ties.
+        participants.shuffle(&mut rand::thread_rng());
+        participants.sort_unstable_by_key(|participant| cmp::Reverse(participant.score()));
+        participants
     }
 
     /// Computes a driver's solutions in the shadow competition.
@@ -216,20 +254,14 @@ impl RunLoop {
             .await
             .map_err(|_| Error::Timeout)?
             .map_err(Error::Solve)?;
-        let (score, solution_id, submission_address) = proposed
+        let proposed = proposed
             .solutions
             .into_iter()
             .max_by_key(|solution| solution.score)
-            .map(|solution| {
-                (
-                    solution.score,
-                    solution.solution_id,
-                    solution.submission_address,
-                )
-            })
             .ok_or(Error::NoSolutions)?;
 
-        let score = NonZeroU256::new(score).ok_or(Error::ZeroScore)?;
+        let score = NonZeroU256::new(proposed.score).ok_or(Error::ZeroScore)?;
+        let solution_id = proposed.solution_id;
 
         let revealed = driver
             .reveal(&reveal::Request { solution_id })
@@ -245,25 +277,28 @@ impl RunLoop {
 
         Ok(Solution {
             score,
-            account: submission_address,
+            account: proposed.submission_address,
+            orders: proposed.orders.into_keys().collect(),
             calldata: revealed.calldata,
         })
     }
 }
 
-struct Participant<'a> {
-    driver: &'a infra::Driver,
-    solution: Result<Solution, Error>,
+pub struct Participant<'a> {
+    pub driver: &'a infra::Driver,
+    pub solution: Result<Solution, Error>,
 }
 
-struct Solution {
-    score: NonZeroU256,
-    account: H160,
-    calldata: reveal::Calldata,
+pub struct Solution {
+    pub score: NonZeroU256,
+    pub account: H160,
+    /// The orders the solution executes.
+    pub orders: Vec<boundary::OrderUid>,
+    pub calldata: reveal::Calldata,
 }
 
 impl Participant<'_> {
-    fn score(&self) -> U256 {
+    pub fn score(&self) -> U256 {
         self.solution
             .as_ref()
             .map(|solution| solution.score.get())
@@ -272,7 +307,7 @@ impl Participant<'_> {
 }
 
 #[derive(Debug, thiserror::Error)]
-enum Error {
+pub enum Error {
     #[error("the solver timed out")]
     Timeout,
     #[error("driver did not propose any solutions")]
@@ -288,7 +323,7 @@ enum Error {
 }
 
 impl Error {
-    fn label(&self) -> &str {
+    pub fn label(&self) -> &str {
         match self {
             Error::Timeout => "timeout",
             Error::NoSolutions => "no_solutions",
@@ -1,5 +1,6 @@
 use {
     crate::{
+        arguments,
         domain::{self},
         infra::{self, banned},
     },
@@ -20,6 +21,7 @@ use {
     shared::{
         account_balances::{BalanceFetching, Query},
         bad_token::BadTokenDetecting,
+        order_validation::{is_order_outside_market_price, Amounts},
         price_estimation::{
             native::NativePriceEstimating,
             native_price_cache::CachingNativePriceEstimator,
@@ -28,7 +30,9 @@ use {
         signature_validator::{SignatureCheck, SignatureValidating},
     },
     std::{
+        cmp,
         collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
+        hash::Hash,
         sync::{Arc, Mutex, Weak},
         time::Duration,
     },
@@ -80,16 +84,29 @@ pub struct SolvableOrdersCache {
     metrics: &'static Metrics,
     weth: H160,
     limit_order_price_factor: BigDecimal,
-    protocol_fees: domain::ProtocolFees,
+    max_limit_orders_per_auction: Option<usize>,
+    max_limit_orders_per_owner: Option<usize>,
+    protocol_fees: Mutex<Arc<domain::ProtocolFees>>,
 }
 
 type Balances = HashMap<Query, U256>;
 
 struct Inner {
     auction: Option<domain::Auction>,
+    /// The protocol fees that got applied to the orders of the auction.
+    protocol_fees: Arc<domain::ProtocolFees>,
     update_time: Instant,
 }
 
+impl Inner {
+    /// The cached auction unless it got built with outdated protocol fees.
+    fn auction(&self, protocol_fees: &Arc<domain::ProtocolFees>) -> Option<domain::Auction> {
+        self.auction
+            .clone()
+            .filter(|_| Arc::ptr_eq(&self.protocol_fees, protocol_fees))
+    }
+}
+
 impl SolvableOrdersCache {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -104,8 +121,11 @@ impl SolvableOrdersCache {
         update_interval: Duration,
         weth: H160,
         limit_order_price_factor: BigDecimal,
+        max_limit_orders_per_auction: Option<usize>,
+        max_limit_orders_per_owner: Option<usize>,
         protocol_fees: domain::ProtocolFees,
     ) -> Arc<Self> {
+        let protocol_fees = Arc::new(protocol_fees);
         let self_ = Arc::new(Self {
             min_order_validity_period,
             persistence,
@@ -114,6 +134,7 @@ impl SolvableOrdersCache {
             bad_token_detector,
             cache: Mutex::new(Inner {
                 auction: None,
+                protocol_fees: protocol_fees.clone(),
                 update_time: Instant::now(),
             }),
             native_price_estimator,
@@ -121,7 +142,9 @@ impl SolvableOrdersCache {
             metrics: Metrics::instance(observe::metrics::get_storage_registry()).unwrap(),
             weth,
             limit_order_price_factor,
-            protocol_fees,
+            max_limit_orders_per_auction,
+            max_limit_orders_per_owner,
+            protocol_fees: Mutex::new(protocol_fees),
         });
         tokio::task::spawn(
             update_task(Arc::downgrade(&self_), update_interval, current_block)
@@ -130,8 +153,25 @@ impl SolvableOrdersCache {
         self_
     }
 
+    /// The most recently built auction. `None` while the protocol fees
+    /// changed since and the next update hasn't applied them yet.
     pub fn current_auction(&self) -> Option<domain::Auction> {
-        self.cache.lock().unwrap().auction.clone()
+        let protocol_fees = self.protocol_fees.lock().unwrap().clone();
+        self.cache.lock().unwrap().auction(&protocol_fees)
+    }
+
+    /// Changes the protocol fees that get applied to the orders of future
+    /// auctions. The current auction is held back until the next update
+    /// rebuilt it with the new fees, so that no auction mixes the fees of one
+    /// configuration with the drivers of another.
+    pub fn update_protocol_fees(
+        &self,
+        fee_policies: &[arguments::FeePolicy],
+        protocol_fee_exempt_addresses: &[H160],
+    ) {
+        let mut protocol_fees = self.protocol_fees.lock().unwrap();
+        *protocol_fees =
+            Arc::new(protocol_fees.with_policies(fee_policies, protocol_fee_exempt_addresses));
     }
 
     /// Manually update solvable orders. Usually called by the background
@@ -145,21 +185,21 @@ impl SolvableOrdersCache {
         let db_solvable_orders = self.persistence.solvable_orders(min_valid_to).await?;
 
         let mut counter = OrderFilterCounter::new(self.metrics, &db_solvable_orders.orders);
-        let mut invalid_order_uids = Vec::new();
-        let mut filtered_order_events = Vec::new();
+        let mut invalid_orders = Vec::new();
+        let mut filtered_orders = Vec::new();
 
         let orders = filter_banned_user_orders(db_solvable_orders.orders, &self.banned_users).await;
         let removed = counter.checkpoint("banned_user", &orders);
-        invalid_order_uids.extend(removed);
+        invalid_orders.push(removed);
 
         let orders =
             filter_invalid_signature_orders(orders, self.signature_validator.as_ref()).await;
         let removed = counter.checkpoint("invalid_signature", &orders);
-        invalid_order_uids.extend(removed);
+        invalid_orders.push(removed);
 
         let orders = filter_unsupported_tokens(orders, self.bad_token_detector.as_ref()).await?;
         let removed = counter.checkpoint("unsupported_token", &orders);
-        invalid_order_uids.extend(removed);
+        invalid_orders.push(removed);
 
         let missing_queries: Vec<_> = orders.iter().map(Query::from_order).collect();
         let fetched_balances = self.balance_fetcher.get_balances(&missing_queries).await;
@@ -183,11 +223,11 @@ impl SolvableOrdersCache {
 
         let orders = orders_with_balance(orders, &balances);
         let removed = counter.checkpoint("insufficient_balance", &orders);
-        invalid_order_uids.extend(removed);
+        invalid_orders.push(removed);
 
         let orders = filter_dust_orders(orders, &balances);
         let removed = counter.checkpoint("dust_order", &orders);
-        filtered_order_events.extend(removed);
+        filtered_orders.push(removed);
 
         // create auction
         let (orders, mut prices) = get_orders_with_native_prices(
@@ -209,32 +249,40 @@ impl SolvableOrdersCache {
         }
 
         let removed = counter.checkpoint("missing_price", &orders);
-        filtered_order_events.extend(removed);
+        filtered_orders.push(removed);
 
-        let orders = filter_mispriced_limit_orders(orders, &prices, &self.limit_order_price_factor);
+        let mut orders =
+            filter_mispriced_limit_orders(orders, &prices, &self.limit_order_price_factor);
         let removed = counter.checkpoint("out_of_market", &orders);
-        filtered_order_events.extend(removed);
+        filtered_orders.push(removed);
+
+        if let Some(limit) = self.max_limit_orders_per_owner {
+            orders = truncate_limit_orders(
+                orders,
+                &db_solvable_orders.quotes,
+                &prices,
+                limit,
+                |order| order.metadata.owner,
+            );
+            let removed = counter.checkpoint("owner_order_limit", &orders);
+            filtered_orders.push(removed);
+        }
+        if let Some(limit) = self.max_limit_orders_per_auction {
+            orders =
+                truncate_limit_orders(orders, &db_solvable_orders.quotes, &prices, limit, |_| ());
+            let removed = counter.checkpoint("auction_size_limit", &orders);
+            filtered_orders.push(removed);
+        }
 
         let removed = counter.record(&orders);
-        filtered_order_events.extend(removed);
+        filtered_orders.push(removed);
 
-        // spawning a background task since `order_events` table insert operation takes
+        // spawning background tasks since `order_events` table insert operation takes
         // a while and the result is ignored.
-        self.persistence.store_order_events(
-            invalid_order_uids
-                .iter()
-                .map(|id| domain::OrderUid(id.0))
-                .collect(),
-            OrderEventLabel::Invalid,
-        );
-        self.persistence.store_order_events(
-            filtered_order_events
-                .iter()
-                .map(|id| domain::OrderUid(id.0))
-                .collect(),
-            OrderEventLabel::Filtered,
-        );
+        self.store_removed_order_events(invalid_orders, OrderEventLabel::Invalid);
+        self.store_removed_order_events(filtered_orders, OrderEventLabel::Filtered);
 
+        let protocol_fees = self.protocol_fees.lock().unwrap().clone();
         let auction = domain::Auction {
             block,
             latest_settlement_block: db_solvable_orders.latest_settlement_block,
@@ -242,7 +290,7 @@ impl SolvableOrdersCache {
                 .into_iter()
                 .filter_map(|order| {
                     if let Some(quote) = db_solvable_orders.quotes.get(&order.metadata.uid.into()) {
-                        Some(self.protocol_fees.apply(order, quote))
+                        Some(protocol_fees.apply(order, quote, &prices))
                     } else {
                         tracing::warn!(order_uid = %order.metadata.uid, "order is skipped, quote is missing");
                         None
@@ -253,6 +301,7 @@ impl SolvableOrdersCache {
         };
         *self.cache.lock().unwrap() = Inner {
             auction: Some(auction),
+            protocol_fees,
             update_time: Instant::now(),
         };
 
@@ -260,6 +309,21 @@ impl SolvableOrdersCache {
         Ok(())
     }
 
+    /// Stores an order event with the given label for every removed order
+    /// together with the reason why it got removed.
+    fn store_removed_order_events(&self, removed: Vec<Removed>, label: OrderEventLabel) {
+        for (reason, order_uids) in removed {
+            if order_uids.is_empty() {
+                continue;
+            }
+            self.persistence.store_order_events_with_reason(
+                order_uids.iter().map(|id| domain::OrderUid(id.0)).collect(),
+                label,
+                reason,
+            );
+        }
+    }
+
     pub fn last_update_time(&self) -> Instant {
         self.cache.lock().unwrap().update_time
     }
@@ -612,6 +676,61 @@ fn filter_mispriced_limit_orders(
     orders
 }
 
+/// Removes the limit orders that are least likely to get executed until at
+/// most `limit` limit orders are left in every group of orders with the same
+/// key. Other orders are always kept.
+fn truncate_limit_orders<K: Eq + Hash>(
+    orders: Vec<Order>,
+    quotes: &HashMap<domain::OrderUid, domain::Quote>,
+    prices: &BTreeMap<H160, U256>,
+    limit: usize,
+    key: impl Fn(&Order) -> K,
+) -> Vec<Order> {
+    let (mut limit_orders, mut orders): (Vec<_>, Vec<_>) =
+        orders.into_iter().partition(Order::is_limit_order);
+    limit_orders
+        .sort_by_cached_key(|order| cmp::Reverse(execution_likelihood(order, quotes, prices)));
+
+    let mut counts = HashMap::new();
+    orders.extend(limit_orders.into_iter().filter(|order| {
+        let count = counts.entry(key(order)).or_insert(0);
+        *count += 1;
+        *count <= limit
+    }));
+    orders
+}
+
+/// Orders limit orders by how likely they are to get executed. Orders with a
+/// limit price within the market price are the most likely ones, followed by
+/// orders with a higher value in the native token. Orders that have been open
+/// for longer are less likely to get executed than recent ones.
+fn execution_likelihood(
+    order: &Order,
+    quotes: &HashMap<domain::OrderUid, domain::Quote>,
+    prices: &BTreeMap<H160, U256>,
+) -> impl Ord {
+    let in_market = quotes.get(&order.metadata.uid.into()).is_some_and(|quote| {
+        !is_order_outside_market_price(
+            &Amounts {
+                sell: order.data.sell_amount,
+                buy: order.data.buy_amount,
+                fee: order.data.fee_amount,
+            },
+            &Amounts {
+                sell: quote.sell_amount,
+                buy: quote.buy_amount,
+                fee: quote.fee,
+            },
+            order.data.kind,
+        )
+    });
+    let native_value = prices
+        .get(&order.data.sell_token)
+        .map(|price| order.data.sell_amount.full_mul(*price))
+        .unwrap_or_default();
+    (in_market, native_value, order.metadata.creation_date)
+}
+
 /// Order filtering state for recording filtered orders over the course of
 /// building an auction.
 struct OrderFilterCounter {
@@ -625,6 +744,9 @@ struct OrderFilterCounter {
 
 type Reason = &'static str;
 
+/// Orders that got removed from the auction for a given reason.
+type Removed = (Reason, Vec<OrderUid>);
+
 impl OrderFilterCounter {
     fn new(metrics: &'static Metrics, orders: &[Order]) -> Self {
         // Eagerly store the candidate orders. This ensures that that gauge is
@@ -652,7 +774,7 @@ impl OrderFilterCounter {
     }
 
     /// Creates a new checkpoint from the current remaining orders.
-    fn checkpoint(&mut self, reason: Reason, orders: &[Order]) -> Vec<OrderUid> {
+    fn checkpoint(&mut self, reason: Reason, orders: &[Order]) -> Removed {
         let filtered_orders = orders
             .iter()
             .fold(self.orders.clone(), |mut order_uids, order| {
@@ -671,14 +793,14 @@ impl OrderFilterCounter {
                 orders = ?filtered_orders, "filtered orders"
             );
         }
-        filtered_orders.into_keys().collect()
+        (reason, filtered_orders.into_keys().collect())
     }
 
     /// Records the filter counter to metrics.
     /// If there are orders that have been filtered out since the last
     /// checkpoint these orders will get recorded with the readon "other".
     /// Returns these catch-all orders.
-    fn record(mut self, orders: &[Order]) -> Vec<OrderUid> {
+    fn record(mut self, orders: &[Order]) -> Removed {
         let removed = self.checkpoint("other", orders);
 
         self.metrics.auction_creations.inc();
@@ -722,6 +844,31 @@ mod tests {
         },
     };
 
+    #[test]
+    fn holds_back_auctions_built_with_outdated_protocol_fees() {
+        let protocol_fees = Arc::new(domain::ProtocolFees::new(
+            &[],
+            domain::fee::FeeFactor::try_from(0.0).unwrap(),
+            &[],
+            false,
+        ));
+        let auction = domain::Auction {
+            block: 1,
+            latest_settlement_block: 0,
+            orders: Vec::new(),
+            prices: Default::default(),
+        };
+        let inner = Inner {
+            auction: Some(auction.clone()),
+            protocol_fees: protocol_fees.clone(),
+            update_time: Instant::now(),
+        };
+        assert_eq!(inner.auction(&protocol_fees), Some(auction));
+
+        let updated = Arc::new(protocol_fees.with_policies(&[], &[]));
+        assert_eq!(inner.auction(&updated), None);
+    }
+
     #[test]
     fn computes_u256_prices_normalized_to_1e18() {
         assert_eq!(
@@ -1178,4 +1325,77 @@ mod tests {
             token(5), // coming from limit order (part of 1 orders)
         ]));
     }
+
+    #[test]
+    fn truncates_limit_orders_least_likely_to_execute() {
+        let now = chrono::Utc::now();
+        let token = |byte| H160([byte; 20]);
+        let order = |uid: u8, owner: u8, class, sell_amount: u64, age| Order {
+            metadata: OrderMetadata {
+                uid: OrderUid([uid; 56]),
+                owner: H160([owner; 20]),
+                class,
+                creation_date: now - chrono::Duration::minutes(age),
+                ..Default::default()
+            },
+            data: OrderData {
+                sell_token: token(1),
+                buy_token: token(2),
+                sell_amount: sell_amount.into(),
+                buy_amount: 100.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let orders = vec![
+            order(1, 1, OrderClass::Market, 1, 0),
+            // Lowest value but within the market price.
+            order(2, 1, OrderClass::Limit, 100, 0),
+            order(3, 1, OrderClass::Limit, 200, 0),
+            order(4, 2, OrderClass::Limit, 200, 10),
+            order(5, 2, OrderClass::Limit, 300, 0),
+        ];
+        let quotes = HashMap::from([(
+            domain::OrderUid([2; 56]),
+            domain::Quote {
+                order_uid: domain::OrderUid([2; 56]),
+                sell_amount: 100.into(),
+                buy_amount: 200.into(),
+                fee: 0.into(),
+            },
+        )]);
+        let prices = btreemap! { token(1) => U256::exp10(18) };
+        let uids = |orders: Vec<Order>| {
+            orders
+                .into_iter()
+                .map(|order| order.metadata.uid.0[0])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            uids(truncate_limit_orders(
+                orders.clone(),
+                &quotes,
+                &prices,
+                1,
+                |order| order.metadata.owner
+            )),
+            [1, 2, 5]
+        );
+        assert_eq!(
+            uids(truncate_limit_orders(
+                orders.clone(),
+                &quotes,
+                &prices,
+                3,
+                |_| ()
+            )),
+            [1, 2, 5, 3]
+        );
+        // Older orders are dropped first if everything else is equal.
+        assert_eq!(
+            uids(truncate_limit_orders(orders, &quotes, &prices, 4, |_| ())),
+            [1, 2, 5, 3, 4]
+        );
+    }
 }
@@ -0,0 +1,254 @@
+//! Computes the rewards solvers earned in every accounting period as described
+//! in [CIP-20](https://snapshot.org/#/cow.eth/proposal/0x2d3f9bd1ea72dca84b03e97dda3efc1f4a42a772c54bd2037e8b62e7d09a491f).
+//!
+//! For every auction it won a solver earns the score observed on chain minus
+//! the reference score, capped from above and below. Winners that fail to
+//! settle are treated as having achieved a score of 0 and get penalized.
+
+use {
+    crate::database::Postgres,
+    anyhow::Result,
+    bigdecimal::BigDecimal,
+    database::{
+        auction::AuctionId,
+        solver_rewards::{AuctionOutcome, Period, Statement},
+    },
+    ethrpc::current_block::CurrentBlockStream,
+    number::conversions::u256_to_big_decimal,
+    primitive_types::U256,
+    std::{collections::BTreeMap, time::Duration},
+    tokio::time,
+};
+
+pub struct Config {
+    /// First block of the first accounting period.
+    pub start_block: u64,
+    /// Length of an accounting period in blocks.
+    pub period: u64,
+    /// Number of blocks to wait after a period ended before computing it so
+    /// that all of its settlements got indexed.
+    pub confirmations: u64,
+    /// Number of blocks to additionally wait for settlements that haven't been
+    /// observed yet before leaving their auctions out of the period.
+    pub max_observation_delay: u64,
+    /// Maximum reward per auction in wei.
+    pub upper_cap: U256,
+    /// Maximum penalty per auction in wei.
+    pub lower_cap: U256,
+}
+
+/// How often to check whether an accounting period ended.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct SolverRewards {
+    config: Config,
+    db: Postgres,
+    current_block: CurrentBlockStream,
+}
+
+impl SolverRewards {
+    pub fn new(config: Config, db: Postgres, current_block: CurrentBlockStream) -> Self {
+        Self {
+            config,
+            db,
+            current_block,
+        }
+    }
+
+    pub async fn run_forever(self) -> ! {
+        let mut interval = time::interval(UPDATE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.update().await {
+                tracing::warn!(?err, "failed to compute solver rewards");
+            }
+        }
+    }
+
+    /// Computes all accounting periods that ended since the last update.
+    async fn update(&self) -> Result<()> {
+        let mut start_block = match self.db.latest_reward_period().await? {
+            Some(period) => period.end_block,
+            None => i64::try_from(self.config.start_block)?,
+        };
+        loop {
+            let period = Period {
+                start_block,
+                end_block: start_block + i64::try_from(self.config.period)?,
+            };
+            let current_block = self.current_block.borrow().number;
+            if u64::try_from(period.end_block)? + self.config.confirmations > current_block {
+                return Ok(());
+            }
+
+            let outcomes = self.db.reward_auction_outcomes(&period).await?;
+            let unobserved = unobserved_settlements(&outcomes);
+            if !unobserved.is_empty() {
+                if u64::try_from(period.end_block)?
+                    + self.config.confirmations
+                    + self.config.max_observation_delay
+                    > current_block
+                {
+                    tracing::debug!(
+                        ?period,
+                        ?unobserved,
+                        "waiting for settlements to be observed"
+                    );
+                    return Ok(());
+                }
+                tracing::error!(
+                    ?period,
+                    ?unobserved,
+                    "skipping auctions whose settlements were never observed"
+                );
+                Metrics::get()
+                    .unobserved_settlements
+                    .inc_by(unobserved.len() as u64);
+            }
+            let statements = statements(
+                &period,
+                &outcomes,
+                &u256_to_big_decimal(&self.config.upper_cap),
+                &u256_to_big_decimal(&self.config.lower_cap),
+            );
+            self.db
+                .insert_reward_statements(&period, &statements)
+                .await?;
+            tracing::info!(
+                ?period,
+                solvers = statements.len(),
+                auctions = outcomes.len(),
+                "computed solver rewards"
+            );
+            Metrics::get().period.set(period.start_block);
+            start_block = period.end_block;
+        }
+    }
+}
+
+/// The auctions that got settled but whose settlement has not been observed
+/// yet.
+fn unobserved_settlements(outcomes: &[AuctionOutcome]) -> Vec<AuctionId> {
+    outcomes
+        .iter()
+        .filter(|outcome| outcome.settled && outcome.observed_score.is_none())
+        .map(|outcome| outcome.auction_id)
+        .collect()
+}
+
+/// Sums up the capped rewards of every winner. Auctions whose settlement has
+/// not been observed are left out, see [`unobserved_settlements`].
+fn statements(
+    period: &Period,
+    outcomes: &[AuctionOutcome],
+    upper_cap: &BigDecimal,
+    lower_cap: &BigDecimal,
+) -> Vec<Statement> {
+    let mut statements = BTreeMap::new();
+    for outcome in outcomes {
+        let observed_score = match (outcome.settled, &outcome.observed_score) {
+            (true, Some(score)) => score.clone(),
+            (true, None) => continue,
+            (false, _) => BigDecimal::from(0),
+        };
+        let reward =
+            (observed_score - &outcome.reference_score).clamp(-lower_cap, upper_cap.clone());
+
+        let statement = statements
+            .entry(outcome.winner.0)
+            .or_insert_with(|| Statement {
+                start_block: period.start_block,
+                solver: outcome.winner,
+                auctions: 0,
+                settled: 0,
+                reward: BigDecimal::from(0),
+            });
+        statement.auctions += 1;
+        statement.settled += i64::from(outcome.settled);
+        statement.reward += reward;
+    }
+    statements.into_values().collect()
+}
+
+#[derive(prometheus_metric_storage::MetricStorage)]
+#[metric(subsystem = "solver_rewards")]
+struct Metrics {
+    /// The first block of the most recently computed accounting period.
+    period: prometheus::IntGauge,
+
+    /// Settled auctions that got left out of their accounting period because
+    /// their settlement was never observed.
+    unobserved_settlements: prometheus::IntCounter,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Metrics::instance(observe::metrics::get_storage_registry()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, database::byte_array::ByteArray};
+
+    fn outcome(winner: u8, reference: i64, observed: Option<i64>, settled: bool) -> AuctionOutcome {
+        AuctionOutcome {
+            auction_id: winner.into(),
+            winner: ByteArray([winner; 20]),
+            reference_score: reference.into(),
+            settled,
+            observed_score: observed.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn sums_up_capped_rewards() {
+        let period = Period {
+            start_block: 100,
+            end_block: 200,
+        };
+        let (upper_cap, lower_cap) = (BigDecimal::from(10), BigDecimal::from(8));
+
+        let outcomes = [
+            outcome(1, 5, Some(8), true),
+            // Capped from above.
+            outcome(1, 5, Some(100), true),
+            // Failed to settle, capped from below.
+            outcome(1, 20, None, false),
+            outcome(2, 5, Some(4), true),
+        ];
+        assert_eq!(
+            statements(&period, &outcomes, &upper_cap, &lower_cap),
+            [
+                Statement {
+                    start_block: 100,
+                    solver: ByteArray([1; 20]),
+                    auctions: 3,
+                    settled: 2,
+                    reward: 5.into(),
+                },
+                Statement {
+                    start_block: 100,
+                    solver: ByteArray([2; 20]),
+                    auctions: 1,
+                    settled: 1,
+                    reward: (-1).into(),
+                },
+            ]
+        );
+
+        // Settlements that were never observed are left out.
+        let outcomes = [outcome(1, 5, Some(8), true), outcome(2, 5, None, true)];
+        assert_eq!(unobserved_settlements(&outcomes), [2]);
+        assert_eq!(
+            statements(&period, &outcomes, &upper_cap, &lower_cap),
+            [Statement {
+                start_block: 100,
+                solver: ByteArray([1; 20]),
+                auctions: 1,
+                settled: 1,
+                reward: 3.into(),
+            }]
+        );
+    }
+}
@@ -29,6 +29,21 @@ pub async fn fetch(
     Ok(row)
 }
 
+/// Returns the id of the most recent auction and whether it contained the
+/// given order.
+pub async fn latest_auction_contains(
+    ex: &mut PgConnection,
+    order: &OrderUid,
+) -> Result<Option<(AuctionId, bool)>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT auction_id, $1 = ANY(order_uids)
+FROM auction_orders
+ORDER BY auction_id DESC
+LIMIT 1
+    ;"#;
+    sqlx::query_as(QUERY).bind(order).fetch_optional(ex).await
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
@@ -51,4 +66,30 @@ mod tests {
         let output = fetch(&mut db, 2).await.unwrap();
         assert!(output.is_none());
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_latest_auction_contains() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = ByteArray([1; 56]);
+        assert_eq!(
+            latest_auction_contains(&mut db, &order).await.unwrap(),
+            None
+        );
+
+        insert(&mut db, 1, &[order]).await.unwrap();
+        assert_eq!(
+            latest_auction_contains(&mut db, &order).await.unwrap(),
+            Some((1, true))
+        );
+
+        insert(&mut db, 2, &[ByteArray([2; 56])]).await.unwrap();
+        assert_eq!(
+            latest_auction_contains(&mut db, &order).await.unwrap(),
+            Some((2, false))
+        );
+    }
 }
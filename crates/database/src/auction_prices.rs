@@ -39,9 +39,84 @@ pub async fn fetch(
     Ok(prices)
 }
 
+/// Native price of a token in an auction together with the block at which the
+/// auction's solutions were simulated.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct TokenPrice {
+    pub auction_id: AuctionId,
+    pub block_number: i64,
+    pub price: BigDecimal,
+}
+
+/// Returns the native prices of a token in auctions simulated in the given
+/// (inclusive) block range sorted by auction.
+///
+/// The range gets split into `max_points` equally sized buckets of which only
+/// the price of the last auction is returned.
+pub async fn fetch_token_history(
+    ex: &mut PgConnection,
+    token: &Address,
+    from_block: Option<i64>,
+    to_block: Option<i64>,
+    max_points: i64,
+) -> Result<Vec<TokenPrice>, sqlx::Error> {
+    const QUERY: &str = r#"
+WITH prices AS (
+    SELECT ap.auction_id, ss.simulation_block AS block_number, ap.price
+    FROM auction_prices ap
+    -- auctions with multiple winners have multiple scores
+    JOIN (
+        SELECT DISTINCT auction_id, simulation_block FROM settlement_scores
+    ) ss ON ss.auction_id = ap.auction_id
+    WHERE
+        ap.token = $1 AND
+        ($2 IS NULL OR ss.simulation_block >= $2) AND
+        ($3 IS NULL OR ss.simulation_block <= $3)
+),
+buckets AS (
+    SELECT
+        *,
+        (block_number - MIN(block_number) OVER ()) * $4 /
+            (MAX(block_number) OVER () - MIN(block_number) OVER () + 1) AS bucket
+    FROM prices
+)
+SELECT DISTINCT ON (bucket) auction_id, block_number, price
+FROM buckets
+ORDER BY bucket ASC, auction_id DESC
+    ;"#;
+    sqlx::query_as(QUERY)
+        .bind(token)
+        .bind(from_block)
+        .bind(to_block)
+        .bind(max_points)
+        .fetch_all(ex)
+        .await
+}
+
+/// Returns the native price of a token in the most recent auction that priced
+/// it.
+pub async fn fetch_latest_token_price(
+    ex: &mut PgConnection,
+    token: &Address,
+) -> Result<Option<TokenPrice>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT ap.auction_id, ss.simulation_block AS block_number, ap.price
+FROM auction_prices ap
+JOIN settlement_scores ss ON ss.auction_id = ap.auction_id
+WHERE ap.token = $1
+ORDER BY ap.auction_id DESC
+LIMIT 1
+    ;"#;
+    sqlx::query_as(QUERY).bind(token).fetch_optional(ex).await
+}
+
 #[cfg(test)]
 mod tests {
-    use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
+    use {
+        super::*,
+        crate::{byte_array::ByteArray, settlement_scores},
+        sqlx::Connection,
+    };
 
     #[tokio::test]
     #[ignore]
@@ -88,4 +163,77 @@ mod tests {
         let output = fetch(&mut db, 4).await.unwrap();
         assert!(output.is_empty());
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_token_history() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let token = ByteArray([1; 20]);
+        assert!(fetch_latest_token_price(&mut db, &token)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Auction `i` gets simulated at block `100 * i` and prices the token at
+        // `i`.
+        for auction_id in 1..=10 {
+            settlement_scores::insert(
+                &mut db,
+                settlement_scores::Score {
+                    auction_id,
+                    winner: ByteArray([3; 20]),
+                    winning_score: 0.into(),
+                    reference_score: 0.into(),
+                    block_deadline: 100 * auction_id + 10,
+                    simulation_block: 100 * auction_id,
+                },
+            )
+            .await
+            .unwrap();
+            insert(
+                &mut db,
+                &[
+                    AuctionPrice {
+                        auction_id,
+                        token,
+                        price: auction_id.into(),
+                    },
+                    AuctionPrice {
+                        auction_id,
+                        token: ByteArray([2; 20]),
+                        price: 0.into(),
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+        }
+        let price = |auction_id: i64| TokenPrice {
+            auction_id,
+            block_number: 100 * auction_id,
+            price: auction_id.into(),
+        };
+
+        let history = fetch_token_history(&mut db, &token, None, None, 100)
+            .await
+            .unwrap();
+        assert_eq!(history, (1..=10).map(price).collect::<Vec<_>>());
+
+        let history = fetch_token_history(&mut db, &token, Some(200), Some(500), 100)
+            .await
+            .unwrap();
+        assert_eq!(history, (2..=5).map(price).collect::<Vec<_>>());
+
+        // Blocks 100..=1000 get split into two buckets at block 551.
+        let history = fetch_token_history(&mut db, &token, None, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(history, vec![price(5), price(10)]);
+
+        let latest = fetch_latest_token_price(&mut db, &token).await.unwrap();
+        assert_eq!(latest, Some(price(10)));
+    }
 }
@@ -13,6 +13,7 @@ pub struct FeePolicy {
     pub volume_factor: Option<f64>,
     pub price_improvement_factor: Option<f64>,
     pub price_improvement_max_volume_factor: Option<f64>,
+    pub scope: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, sqlx::Type)]
@@ -35,7 +36,7 @@ pub async fn insert_batch(
     let mut query_builder = QueryBuilder::new(
         "INSERT INTO fee_policies (auction_id, order_uid, kind, surplus_factor, \
          surplus_max_volume_factor, volume_factor, price_improvement_factor, \
-         price_improvement_max_volume_factor)",
+         price_improvement_max_volume_factor, scope)",
     );
 
     query_builder.push_values(fee_policies, |mut b, fee_policy| {
@@ -46,7 +47,8 @@ pub async fn insert_batch(
             .push_bind(fee_policy.surplus_max_volume_factor)
             .push_bind(fee_policy.volume_factor)
             .push_bind(fee_policy.price_improvement_factor)
-            .push_bind(fee_policy.price_improvement_max_volume_factor);
+            .push_bind(fee_policy.price_improvement_max_volume_factor)
+            .push_bind(fee_policy.scope);
     });
 
     query_builder.build().execute(ex).await.map(|_| ())
@@ -96,6 +98,7 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            scope: None,
         };
         // surplus fee policy with caps
         let fee_policy_2 = FeePolicy {
@@ -107,6 +110,7 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            scope: None,
         };
         // volume based fee policy
         let fee_policy_3 = FeePolicy {
@@ -118,6 +122,7 @@ mod tests {
             volume_factor: Some(0.06),
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            scope: None,
         };
         // price improvement fee policy
         let fee_policy_4 = FeePolicy {
@@ -129,9 +134,33 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: Some(0.1),
             price_improvement_max_volume_factor: Some(0.99999),
+            scope: None,
         };
 
-        let fee_policies = vec![fee_policy_1, fee_policy_2, fee_policy_3, fee_policy_4];
+        // volume based fee policy restricted to a token pair
+        let fee_policy_5 = FeePolicy {
+            auction_id,
+            order_uid,
+            kind: FeePolicyKind::Volume,
+            surplus_factor: None,
+            surplus_max_volume_factor: None,
+            volume_factor: Some(0.0001),
+            price_improvement_factor: None,
+            price_improvement_max_volume_factor: None,
+            scope: Some(
+                "pair=0x0101010101010101010101010101010101010101/\
+                 0x0202020202020202020202020202020202020202"
+                    .to_string(),
+            ),
+        };
+
+        let fee_policies = vec![
+            fee_policy_1,
+            fee_policy_2,
+            fee_policy_3,
+            fee_policy_4,
+            fee_policy_5,
+        ];
 
         insert_batch(&mut db, fee_policies.clone()).await.unwrap();
 
@@ -18,6 +18,7 @@ pub mod settlement_observations;
 pub mod settlement_scores;
 pub mod settlements;
 pub mod solver_competition;
+pub mod solver_rewards;
 pub mod trades;
 
 use {
@@ -66,6 +67,8 @@ pub const TABLES: &[&str] = &[
     "auction_prices",
     "auction_participants",
     "app_data",
+    "reward_periods",
+    "solver_reward_statements",
 ];
 
 /// The names of potentially big volume tables we use in the db.
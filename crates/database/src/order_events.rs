@@ -7,6 +7,10 @@ use {
     sqlx::{types::chrono::DateTime, PgConnection, PgPool},
 };
 
+/// Postgres notification channel on which every inserted order event gets
+/// published as JSON (see `V067__notify_order_events.sql`).
+pub const NOTIFICATION_CHANNEL: &str = "order_events";
+
 /// Describes what kind of event was registered for an order.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type)]
 #[sqlx(type_name = "OrderEventLabel")]
@@ -34,7 +38,7 @@ pub enum OrderEventLabel {
 
 /// Contains a single event of the life cycle of an order and when it was
 /// registered.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type, sqlx::FromRow)]
+#[derive(Clone, Debug, Eq, PartialEq, sqlx::FromRow)]
 pub struct OrderEvent {
     /// Which order this event belongs to
     pub order_uid: OrderUid,
@@ -43,39 +47,77 @@ pub struct OrderEvent {
     pub timestamp: DateTime<Utc>,
     /// What kind of event happened
     pub label: OrderEventLabel,
+    /// Why the event happened (e.g. which filter removed the order from the
+    /// auction)
+    pub reason: Option<String>,
 }
 
 /// Inserts a row into the `order_events` table only if the latest event for the
-/// corresponding order UID has a different label than the provided event..
+/// corresponding order UID has a different label or reason than the provided
+/// event.
 pub async fn insert_order_event(
     ex: &mut PgConnection,
     event: &OrderEvent,
 ) -> Result<(), sqlx::Error> {
     const QUERY: &str = r#"
         WITH cte AS (
-            SELECT label
+            SELECT label, reason
             FROM order_events
             WHERE order_uid = $1
             ORDER BY timestamp DESC
             LIMIT 1
         )
-        INSERT INTO order_events (order_uid, timestamp, label)
-        SELECT $1, $2, $3
+        INSERT INTO order_events (order_uid, timestamp, label, reason)
+        SELECT $1, $2, $3, $4
         WHERE NOT EXISTS (
             SELECT 1
             FROM cte
-            WHERE label = $3
+            WHERE label = $3 AND reason IS NOT DISTINCT FROM $4
         )
     "#;
     sqlx::query(QUERY)
         .bind(event.order_uid)
         .bind(event.timestamp)
         .bind(event.label)
+        .bind(event.reason.as_deref())
         .execute(ex)
         .await
         .map(|_| ())
 }
 
+/// Returns all events of the given order sorted by their timestamp (oldest
+/// first).
+pub async fn order_events(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<Vec<OrderEvent>, sqlx::Error> {
+    const QUERY: &str = r#"
+        SELECT order_uid, timestamp, label, reason
+        FROM order_events
+        WHERE order_uid = $1
+        ORDER BY timestamp ASC
+    "#;
+    sqlx::query_as(QUERY).bind(order_uid).fetch_all(ex).await
+}
+
+/// Returns the most recent event of the given order.
+pub async fn latest_order_event(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<Option<OrderEvent>, sqlx::Error> {
+    const QUERY: &str = r#"
+        SELECT order_uid, timestamp, label, reason
+        FROM order_events
+        WHERE order_uid = $1
+        ORDER BY timestamp DESC
+        LIMIT 1
+    "#;
+    sqlx::query_as(QUERY)
+        .bind(order_uid)
+        .fetch_optional(ex)
+        .await
+}
+
 /// Deletes rows before the provided timestamp from the `order_events` table.
 pub async fn delete_order_events_before(
     pool: &PgPool,
@@ -117,24 +159,28 @@ mod tests {
             order_uid: uid_a,
             timestamp: now - chrono::Duration::milliseconds(300),
             label: OrderEventLabel::Created,
+            reason: None,
         };
         insert_order_event(&mut ex, &event_a).await.unwrap();
         let event_b = OrderEvent {
             order_uid: uid_a,
             timestamp: now - chrono::Duration::milliseconds(200),
             label: OrderEventLabel::Invalid,
+            reason: None,
         };
         insert_order_event(&mut ex, &event_b).await.unwrap();
         let event_c = OrderEvent {
             order_uid: uid_b,
             timestamp: now - chrono::Duration::milliseconds(100),
             label: OrderEventLabel::Invalid,
+            reason: None,
         };
         insert_order_event(&mut ex, &event_c).await.unwrap();
         let event_d = OrderEvent {
             order_uid: uid_a,
             timestamp: now,
             label: OrderEventLabel::Invalid,
+            reason: None,
         };
         insert_order_event(&mut ex, &event_d).await.unwrap();
 
@@ -151,6 +197,66 @@ mod tests {
         assert_eq!(ids[2].label, OrderEventLabel::Invalid);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_events_by_uid_with_reasons() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let now = Utc::now();
+        let uid = ByteArray([1; 56]);
+        let event = |millis: i64, label: OrderEventLabel, reason: Option<&str>| OrderEvent {
+            order_uid: uid,
+            timestamp: now - chrono::Duration::milliseconds(millis),
+            label,
+            reason: reason.map(str::to_owned),
+        };
+        let events = [
+            event(400, OrderEventLabel::Created, None),
+            event(300, OrderEventLabel::Filtered, Some("missing_price")),
+            // same label and reason gets deduplicated
+            event(200, OrderEventLabel::Filtered, Some("missing_price")),
+            // same label but a different reason gets stored
+            event(100, OrderEventLabel::Filtered, Some("out_of_market")),
+        ];
+        for event in &events {
+            insert_order_event(&mut db, event).await.unwrap();
+        }
+        insert_order_event(
+            &mut db,
+            &OrderEvent {
+                order_uid: ByteArray([2; 56]),
+                ..event(0, OrderEventLabel::Created, None)
+            },
+        )
+        .await
+        .unwrap();
+
+        let stored = order_events(&mut db, &uid)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|event| (event.label, event.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stored,
+            vec![
+                (OrderEventLabel::Created, None),
+                (OrderEventLabel::Filtered, Some("missing_price".to_owned())),
+                (OrderEventLabel::Filtered, Some("out_of_market".to_owned())),
+            ]
+        );
+
+        let latest = latest_order_event(&mut db, &uid).await.unwrap().unwrap();
+        assert_eq!(latest.label, OrderEventLabel::Filtered);
+        assert_eq!(latest.reason.as_deref(), Some("out_of_market"));
+        assert!(latest_order_event(&mut db, &ByteArray([3; 56]))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
     async fn all_order_events(ex: &mut PgConnection) -> Vec<OrderEvent> {
         const QUERY: &str = r#"
                 SELECT *
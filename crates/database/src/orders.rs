@@ -9,6 +9,7 @@ use {
     },
     futures::stream::BoxStream,
     sqlx::{
+        postgres::PgQueryResult,
         types::{
             chrono::{DateTime, Utc},
             BigDecimal,
@@ -113,6 +114,7 @@ pub async fn insert_orders_and_ignore_conflicts(
                 label: OrderEventLabel::Created,
                 timestamp: order.creation_timestamp,
                 order_uid: order.uid,
+                reason: None,
             },
         )
         .await?;
@@ -156,14 +158,26 @@ pub async fn insert_order_and_ignore_conflicts(
     // Since each order has a unique UID even after a reorg onchain placed orders
     // have the same data. Hence, we can disregard any conflicts.
     const QUERY: &str = const_format::concatcp!(INSERT_ORDER_QUERY, "ON CONFLICT (uid) DO NOTHING");
-    insert_order_execute_sqlx(QUERY, ex, order).await
+    insert_order_execute_sqlx(QUERY, ex, order).await?;
+    Ok(())
+}
+
+/// Inserts the order unless an order with the same UID already exists.
+/// Returns whether the order got inserted.
+pub async fn insert_order_if_new(
+    ex: &mut PgConnection,
+    order: &Order,
+) -> Result<bool, sqlx::Error> {
+    const QUERY: &str = const_format::concatcp!(INSERT_ORDER_QUERY, "ON CONFLICT (uid) DO NOTHING");
+    let result = insert_order_execute_sqlx(QUERY, ex, order).await?;
+    Ok(result.rows_affected() == 1)
 }
 
 async fn insert_order_execute_sqlx(
     query_str: &str,
     ex: &mut PgConnection,
     order: &Order,
-) -> Result<(), sqlx::Error> {
+) -> Result<PgQueryResult, sqlx::Error> {
     sqlx::query(query_str)
         .bind(order.uid)
         .bind(order.owner)
@@ -187,12 +201,12 @@ async fn insert_order_execute_sqlx(
         .bind(order.cancellation_timestamp)
         .bind(order.class)
         .execute(ex)
-        .await?;
-    Ok(())
+        .await
 }
 
 pub async fn insert_order(ex: &mut PgConnection, order: &Order) -> Result<(), sqlx::Error> {
-    insert_order_execute_sqlx(INSERT_ORDER_QUERY, ex, order).await
+    insert_order_execute_sqlx(INSERT_ORDER_QUERY, ex, order).await?;
+    Ok(())
 }
 
 pub async fn read_order(
@@ -567,6 +581,19 @@ pub async fn single_full_order(
     sqlx::query_as(QUERY).bind(uid).fetch_optional(ex).await
 }
 
+pub fn many_full_orders<'a>(
+    ex: &'a mut PgConnection,
+    uids: &'a [OrderUid],
+) -> BoxStream<'a, Result<FullOrder, sqlx::Error>> {
+    #[rustfmt::skip]
+    const QUERY: &str = const_format::concatcp!(
+"SELECT ", ORDERS_SELECT,
+" FROM ", ORDERS_FROM,
+" WHERE o.uid = ANY($1) ",
+    );
+    sqlx::query_as(QUERY).bind(uids).fetch(ex)
+}
+
 // Partial query for getting the log indices of events of a single settlement.
 //
 // This will fail if we ever have multiple settlements in the same transaction
@@ -611,41 +638,124 @@ WHERE
     sqlx::query_as(QUERY).bind(tx_hash).fetch(ex)
 }
 
+/// The status of an order as derived from its trades, invalidations and
+/// validity. Mirrors the status the orderbook computes for API responses.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderStatus {
+    PresignaturePending,
+    Open,
+    Fulfilled,
+    Cancelled,
+    Expired,
+}
+
+impl OrderStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::PresignaturePending => "presignature_pending",
+            Self::Open => "open",
+            Self::Fulfilled => "fulfilled",
+            Self::Cancelled => "cancelled",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+/// Optional filters for [`user_orders`]. Unset fields don't filter anything.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UserOrdersFilter {
+    /// Only return orders that come after the order with this UID in the
+    /// result ordering (i.e. orders that were created before it). This allows
+    /// keyset pagination which stays consistent while new orders get created.
+    pub cursor: Option<OrderUid>,
+    pub status: Option<OrderStatus>,
+    pub class: Option<OrderClass>,
+    pub sell_token: Option<Address>,
+    pub buy_token: Option<Address>,
+    /// Inclusive lower bound of the creation timestamp.
+    pub created_from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound of the creation timestamp.
+    pub created_until: Option<DateTime<Utc>>,
+}
+
+/// Filters of [`UserOrdersFilter`] that only depend on columns of the `orders`
+/// table. They get applied before computing the full orders so postgres can
+/// still use the index on (owner, creation_timestamp).
+const USER_ORDERS_FILTER: &str = r#"
+    ($4 IS NULL OR (o.creation_timestamp, o.uid) < (SELECT creation_timestamp, uid FROM orders WHERE uid = $4)) AND
+    ($5 IS NULL OR o.class = $5) AND
+    ($6 IS NULL OR o.sell_token = $6) AND
+    ($7 IS NULL OR o.buy_token = $7) AND
+    ($8 IS NULL OR o.creation_timestamp >= $8) AND
+    ($9 IS NULL OR o.creation_timestamp < $9)
+"#;
+
+/// Computes the [`OrderStatus`] of a row selected with `ORDERS_SELECT`.
+const USER_ORDER_STATUS: &str = r#"
+CASE
+    WHEN (kind = 'buy' AND sum_buy <> 0 AND sum_buy = buy_amount) OR
+        (kind = 'sell' AND sum_sell <> 0 AND sum_sell - sum_fee = sell_amount)
+        THEN 'fulfilled'
+    WHEN invalidated THEN 'cancelled'
+    WHEN COALESCE(
+        (SELECT eth_o.valid_to FROM ethflow_orders eth_o WHERE eth_o.uid = user_orders.uid),
+        valid_to
+    ) < EXTRACT(EPOCH FROM now()) THEN 'expired'
+    WHEN presignature_pending THEN 'presignature_pending'
+    ELSE 'open'
+END
+"#;
+
+/// All orders of a user (including on-chain placed orders) ordered by creation
+/// date descending (newest orders first).
 pub fn user_orders<'a>(
     ex: &'a mut PgConnection,
     owner: &'a Address,
     offset: i64,
     limit: Option<i64>,
+    filter: &UserOrdersFilter,
 ) -> BoxStream<'a, Result<FullOrder, sqlx::Error>> {
-    // As a future consideration for this query we could move from offset to an
-    // approach called keyset pagination where the offset is identified by "key"
-    // of the previous query. In our case that would be the lowest
-    // creation_timestamp. This way the database can start immediately at the
-    // offset through the index without enumerating the first N elements
-    // before as is the case with OFFSET.
-    // On the other hand that approach is less flexible so we will consider if we
-    // see that these queries are taking too long in practice.
-    #[rustfmt::skip]
-    const QUERY: &str = const_format::concatcp!(
-"(SELECT ", ORDERS_SELECT,
-" FROM ", ORDERS_FROM,
-" LEFT OUTER JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
-" WHERE o.owner = $1",
-" ORDER BY creation_timestamp DESC LIMIT $2 + $3 ) ",
-" UNION ",
-" (SELECT ", ORDERS_SELECT,
-" FROM ", ORDERS_FROM,
-" LEFT OUTER JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
-" WHERE onchain_o.sender = $1 ",
-" ORDER BY creation_timestamp DESC LIMIT $2 + $3 ) ",
-" ORDER BY creation_timestamp DESC ",
-" LIMIT $2 ",
-" OFFSET $3 ",
+    // The status can only be computed from the full orders, so both sides of
+    // the union filter by it on the full orders before getting limited. That
+    // way each side stays bounded and can stop scanning the index early.
+    const QUERY: &str = const_format::formatcp!(
+        r#"
+SELECT * FROM (
+    (SELECT * FROM (
+        SELECT {ORDERS_SELECT}
+        FROM {ORDERS_FROM}
+        WHERE o.owner = $1 AND {USER_ORDERS_FILTER}
+    ) AS user_orders
+    WHERE $10 IS NULL OR {USER_ORDER_STATUS} = $10
+    ORDER BY creation_timestamp DESC, uid DESC
+    LIMIT $2 + $3)
+    UNION
+    (SELECT * FROM (
+        SELECT {ORDERS_SELECT}
+        FROM {ORDERS_FROM}
+        JOIN onchain_placed_orders onchain_o ON onchain_o.uid = o.uid
+        WHERE onchain_o.sender = $1 AND {USER_ORDERS_FILTER}
+    ) AS user_orders
+    WHERE $10 IS NULL OR {USER_ORDER_STATUS} = $10
+    ORDER BY creation_timestamp DESC, uid DESC
+    LIMIT $2 + $3)
+) AS user_orders
+ORDER BY creation_timestamp DESC, uid DESC
+LIMIT $2
+OFFSET $3
+;"#
     );
     sqlx::query_as(QUERY)
         .bind(owner)
         .bind(limit)
         .bind(offset)
+        .bind(filter.cursor)
+        .bind(filter.class)
+        .bind(filter.sell_token)
+        .bind(filter.buy_token)
+        .bind(filter.created_from)
+        .bind(filter.created_until)
+        .bind(filter.status.map(|status| status.as_str()))
         .fetch(ex)
 }
 
@@ -1146,6 +1256,19 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_insert_order_if_new() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = Order::default();
+        assert!(insert_order_if_new(&mut db, &order).await.unwrap());
+        assert!(!insert_order_if_new(&mut db, &order).await.unwrap());
+        assert_eq!(read_order(&mut db, &order.uid).await.unwrap(), Some(order));
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_quote_roundtrip_updating_on_conflict() {
@@ -1417,6 +1540,31 @@ mod tests {
         assert!(result.invalidated);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_many_full_orders() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let orders = [1, 2, 3].map(|i| Order {
+            uid: ByteArray([i; 56]),
+            ..Default::default()
+        });
+        for order in &orders {
+            insert_order(&mut db, order).await.unwrap();
+        }
+
+        let uids = [orders[0].uid, orders[2].uid, ByteArray([4; 56])];
+        let mut result: Vec<_> = many_full_orders(&mut db, &uids)
+            .map_ok(|order| order.uid)
+            .try_collect()
+            .await
+            .unwrap();
+        result.sort_by_key(|uid| uid.0);
+        assert_eq!(result, vec![orders[0].uid, orders[2].uid]);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_solvable_orders() {
@@ -1542,7 +1690,7 @@ mod tests {
         offset: i64,
         limit: Option<i64>,
     ) -> Vec<Data> {
-        super::user_orders(ex, owner, offset, limit)
+        super::user_orders(ex, owner, offset, limit, &Default::default())
             .map(|o| {
                 let o = o.unwrap();
                 (o.uid.0, o.owner, o.creation_timestamp)
@@ -1686,7 +1834,7 @@ mod tests {
             offset: i64,
             limit: Option<i64>,
         ) -> Vec<Data> {
-            super::user_orders(ex, owner, offset, limit)
+            super::user_orders(ex, owner, offset, limit, &Default::default())
                 .map(|o| {
                     let o = o.unwrap();
                     (o.uid.0, o.owner, o.creation_timestamp)
@@ -1723,6 +1871,156 @@ mod tests {
         assert_eq!(result, vec![orders[0]]);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_user_orders_filtered() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let owner = ByteArray([1; 20]);
+        let datetime = |offset: i64| Utc.timestamp_opt(offset, 0).unwrap();
+        let orders = [
+            // open
+            Order {
+                uid: ByteArray([4; 56]),
+                creation_timestamp: datetime(4),
+                class: OrderClass::Limit,
+                sell_token: ByteArray([1; 20]),
+                valid_to: i64::MAX,
+                ..Default::default()
+            },
+            // cancelled
+            Order {
+                uid: ByteArray([3; 56]),
+                creation_timestamp: datetime(3),
+                sell_token: ByteArray([2; 20]),
+                valid_to: i64::MAX,
+                cancellation_timestamp: Some(datetime(5)),
+                ..Default::default()
+            },
+            // expired
+            Order {
+                uid: ByteArray([2; 56]),
+                creation_timestamp: datetime(2),
+                class: OrderClass::Limit,
+                sell_token: ByteArray([1; 20]),
+                valid_to: 0,
+                ..Default::default()
+            },
+            // open
+            Order {
+                uid: ByteArray([1; 56]),
+                creation_timestamp: datetime(1),
+                buy_token: ByteArray([3; 20]),
+                valid_to: i64::MAX,
+                ..Default::default()
+            },
+        ];
+        for order in &orders {
+            let order = Order {
+                owner,
+                ..order.clone()
+            };
+            insert_order(&mut db, &order).await.unwrap();
+        }
+
+        async fn user_orders(
+            ex: &mut PgConnection,
+            owner: &Address,
+            limit: Option<i64>,
+            filter: UserOrdersFilter,
+        ) -> Vec<OrderUid> {
+            super::user_orders(ex, owner, 0, limit, &filter)
+                .map(|o| o.unwrap().uid)
+                .collect::<Vec<_>>()
+                .await
+        }
+        let uids = |indices: &[usize]| indices.iter().map(|i| orders[*i].uid).collect::<Vec<_>>();
+
+        let cursor = UserOrdersFilter {
+            cursor: Some(orders[0].uid),
+            ..Default::default()
+        };
+        assert_eq!(
+            user_orders(&mut db, &owner, None, cursor).await,
+            uids(&[1, 2, 3])
+        );
+        assert_eq!(
+            user_orders(&mut db, &owner, Some(1), cursor).await,
+            uids(&[1])
+        );
+
+        let filter = UserOrdersFilter {
+            class: Some(OrderClass::Limit),
+            ..Default::default()
+        };
+        assert_eq!(
+            user_orders(&mut db, &owner, None, filter).await,
+            uids(&[0, 2])
+        );
+
+        let filter = UserOrdersFilter {
+            sell_token: Some(ByteArray([1; 20])),
+            ..Default::default()
+        };
+        assert_eq!(
+            user_orders(&mut db, &owner, None, filter).await,
+            uids(&[0, 2])
+        );
+
+        let filter = UserOrdersFilter {
+            buy_token: Some(ByteArray([3; 20])),
+            ..Default::default()
+        };
+        assert_eq!(user_orders(&mut db, &owner, None, filter).await, uids(&[3]));
+
+        let filter = UserOrdersFilter {
+            created_from: Some(datetime(2)),
+            created_until: Some(datetime(4)),
+            ..Default::default()
+        };
+        assert_eq!(
+            user_orders(&mut db, &owner, None, filter).await,
+            uids(&[1, 2])
+        );
+
+        let status = |status| UserOrdersFilter {
+            status: Some(status),
+            ..Default::default()
+        };
+        assert_eq!(
+            user_orders(&mut db, &owner, None, status(OrderStatus::Open)).await,
+            uids(&[0, 3])
+        );
+        assert_eq!(
+            user_orders(&mut db, &owner, None, status(OrderStatus::Cancelled)).await,
+            uids(&[1])
+        );
+        assert_eq!(
+            user_orders(&mut db, &owner, None, status(OrderStatus::Expired)).await,
+            uids(&[2])
+        );
+        assert_eq!(
+            user_orders(&mut db, &owner, Some(1), status(OrderStatus::Open)).await,
+            uids(&[0])
+        );
+        // The limit applies to the orders with the status, not to all orders.
+        assert_eq!(
+            user_orders(&mut db, &owner, Some(1), status(OrderStatus::Expired)).await,
+            uids(&[2])
+        );
+        let filter = UserOrdersFilter {
+            cursor: Some(orders[0].uid),
+            status: Some(OrderStatus::Open),
+            ..Default::default()
+        };
+        assert_eq!(
+            user_orders(&mut db, &owner, Some(1), filter).await,
+            uids(&[3])
+        );
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_orders_in_tx() {
@@ -1,16 +1,22 @@
-use {crate::auction::AuctionId, sqlx::PgConnection};
+use {
+    crate::{auction::AuctionId, Address},
+    sqlx::PgConnection,
+};
 
 #[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
 pub struct SettlementCallData {
     pub auction_id: AuctionId,
+    /// The winner that promised to settle this call data.
+    pub solver: Address,
     pub call_data: Vec<u8>,
     pub uninternalized_call_data: Vec<u8>,
 }
 
 pub async fn insert(ex: &mut PgConnection, row: SettlementCallData) -> Result<(), sqlx::Error> {
-    const QUERY: &str = r#"INSERT INTO settlement_call_data (auction_id, call_data, uninternalized_call_data) VALUES ($1, $2, $3);"#;
+    const QUERY: &str = r#"INSERT INTO settlement_call_data (auction_id, solver, call_data, uninternalized_call_data) VALUES ($1, $2, $3, $4);"#;
     sqlx::query(QUERY)
         .bind(row.auction_id)
+        .bind(row.solver)
         .bind(row.call_data.as_slice())
         .bind(row.uninternalized_call_data.as_slice())
         .execute(ex)
@@ -18,20 +24,19 @@ pub async fn insert(ex: &mut PgConnection, row: SettlementCallData) -> Result<()
     Ok(())
 }
 
+/// Returns the call data of all winners of the auction.
 pub async fn fetch(
     ex: &mut PgConnection,
     auction_id: AuctionId,
-) -> Result<Option<SettlementCallData>, sqlx::Error> {
-    const QUERY: &str = r#"SELECT * FROM settlement_call_data WHERE auction_id = $1"#;
-    sqlx::query_as(QUERY)
-        .bind(auction_id)
-        .fetch_optional(ex)
-        .await
+) -> Result<Vec<SettlementCallData>, sqlx::Error> {
+    const QUERY: &str =
+        r#"SELECT * FROM settlement_call_data WHERE auction_id = $1 ORDER BY solver"#;
+    sqlx::query_as(QUERY).bind(auction_id).fetch_all(ex).await
 }
 
 #[cfg(test)]
 mod tests {
-    use {super::*, sqlx::Connection};
+    use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
 
     #[tokio::test]
     #[ignore]
@@ -42,12 +47,20 @@ mod tests {
 
         let input = SettlementCallData {
             auction_id: 1,
+            solver: ByteArray([1; 20]),
             call_data: vec![2; 20],
             uninternalized_call_data: vec![3; 20],
         };
         insert(&mut db, input.clone()).await.unwrap();
+        let second = SettlementCallData {
+            solver: ByteArray([4; 20]),
+            call_data: vec![5; 20],
+            uninternalized_call_data: vec![6; 20],
+            ..input.clone()
+        };
+        insert(&mut db, second.clone()).await.unwrap();
 
-        let output = fetch(&mut db, 1).await.unwrap().unwrap();
-        assert_eq!(input, output);
+        let output = fetch(&mut db, 1).await.unwrap();
+        assert_eq!(output, vec![input, second]);
     }
 }
@@ -29,15 +29,14 @@ pub async fn insert(ex: &mut PgTransaction<'_>, score: Score) -> Result<(), sqlx
     Ok(())
 }
 
+/// Returns the scores of all winners of the auction.
 pub async fn fetch(
     ex: &mut PgConnection,
     auction_id: AuctionId,
-) -> Result<Option<Score>, sqlx::Error> {
-    const QUERY: &str = r#"SELECT * FROM settlement_scores WHERE auction_id = $1"#;
-    sqlx::query_as(QUERY)
-        .bind(auction_id)
-        .fetch_optional(ex)
-        .await
+) -> Result<Vec<Score>, sqlx::Error> {
+    const QUERY: &str =
+        r#"SELECT * FROM settlement_scores WHERE auction_id = $1 ORDER BY winning_score DESC"#;
+    sqlx::query_as(QUERY).bind(auction_id).fetch_all(ex).await
 }
 
 #[cfg(test)]
@@ -61,7 +60,15 @@ mod tests {
         };
         insert(&mut db, input.clone()).await.unwrap();
 
-        let output = fetch(&mut db, 1).await.unwrap().unwrap();
-        assert_eq!(input, output);
+        let second = Score {
+            winner: ByteArray([3; 20]),
+            winning_score: 8.into(),
+            reference_score: 7.into(),
+            ..input.clone()
+        };
+        insert(&mut db, second.clone()).await.unwrap();
+
+        let output = fetch(&mut db, 1).await.unwrap();
+        assert_eq!(output, vec![input, second]);
     }
 }
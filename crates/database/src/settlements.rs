@@ -1,5 +1,5 @@
 use {
-    crate::{events::EventIndex, PgTransaction, TransactionHash},
+    crate::{events::EventIndex, Address, PgTransaction, TransactionHash},
     sqlx::{Executor, PgConnection},
 };
 
@@ -21,18 +21,23 @@ WHERE
         .await
 }
 
+/// Returns the hash of the transaction in which `solver` settled the auction.
+/// Auctions can have multiple winners which settle in separate transactions.
 pub async fn get_hash_by_auction_id(
     ex: &mut PgConnection,
     auction_id: i64,
+    solver: &Address,
 ) -> Result<Option<TransactionHash>, sqlx::Error> {
     const QUERY: &str = r#"
 SELECT tx_hash
 FROM settlements
 WHERE
-    auction_id = $1
+    auction_id = $1 AND
+    solver = $2
     "#;
     sqlx::query_scalar::<_, TransactionHash>(QUERY)
         .bind(auction_id)
+        .bind(solver)
         .fetch_optional(ex)
         .await
 }
@@ -57,13 +62,18 @@ LIMIT 1
     sqlx::query_as(QUERY).fetch_optional(ex).await
 }
 
+/// Checks whether a settlement of `solver` has already been recorded for the
+/// auction.
 pub async fn already_processed(
     ex: &mut PgConnection,
     auction_id: i64,
+    solver: &Address,
 ) -> Result<bool, sqlx::Error> {
-    const QUERY: &str = r#"SELECT COUNT(*) FROM settlements WHERE auction_id = $1;"#;
+    const QUERY: &str =
+        r#"SELECT COUNT(*) FROM settlements WHERE auction_id = $1 AND solver = $2;"#;
     let count: i64 = sqlx::query_scalar(QUERY)
         .bind(auction_id)
+        .bind(solver)
         .fetch_one(ex)
         .await?;
     Ok(count >= 1)
@@ -208,5 +218,18 @@ mod tests {
         let settlement = get_settlement_without_auction(&mut db).await.unwrap();
 
         assert!(settlement.is_none());
+
+        let solver = ByteArray::default();
+        assert!(already_processed(&mut db, 1, &solver).await.unwrap());
+        assert_eq!(
+            get_hash_by_auction_id(&mut db, 1, &solver).await.unwrap(),
+            Some(ByteArray::default())
+        );
+        let other_solver = ByteArray([1; 20]);
+        assert!(!already_processed(&mut db, 1, &other_solver).await.unwrap());
+        assert!(get_hash_by_auction_id(&mut db, 1, &other_solver)
+            .await
+            .unwrap()
+            .is_none());
     }
 }
@@ -16,6 +16,20 @@ VALUES ($1, $2)
     Ok(())
 }
 
+pub async fn update(
+    ex: &mut PgConnection,
+    id: AuctionId,
+    data: &JsonValue,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+UPDATE solver_competitions
+SET json = $2
+WHERE id = $1
+    ;"#;
+    sqlx::query(QUERY).bind(id).bind(data).execute(ex).await?;
+    Ok(())
+}
+
 #[derive(Clone, Debug, sqlx::FromRow)]
 pub struct LoadCompetition {
     pub json: JsonValue,
@@ -89,6 +103,11 @@ mod tests {
         assert!(value_.tx_hash.is_none());
 
         assert!(load_by_id(&mut db, 1).await.unwrap().is_none());
+
+        let value = JsonValue::Bool(false);
+        update(&mut db, 0, &value).await.unwrap();
+        let value_ = load_by_id(&mut db, 0).await.unwrap().unwrap();
+        assert_eq!(value, value_.json);
     }
 
     #[tokio::test]
@@ -0,0 +1,248 @@
+use {
+    crate::{auction::AuctionId, Address, PgTransaction},
+    bigdecimal::BigDecimal,
+    sqlx::PgConnection,
+    std::ops::DerefMut,
+};
+
+/// An accounting period covering all auctions with a block deadline in
+/// `[start_block, end_block)`.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct Period {
+    pub start_block: i64,
+    pub end_block: i64,
+}
+
+/// How an auction went for one of its winners.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct AuctionOutcome {
+    pub auction_id: AuctionId,
+    pub winner: Address,
+    pub reference_score: BigDecimal,
+    /// Whether the winner settled the auction.
+    pub settled: bool,
+    /// Surplus plus fees of the settlement as observed on chain. `None` if the
+    /// auction was not settled or the settlement has not been observed yet.
+    pub observed_score: Option<BigDecimal>,
+}
+
+/// The reward a solver earned in an accounting period.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct Statement {
+    pub start_block: i64,
+    pub solver: Address,
+    /// Number of auctions the solver won.
+    pub auctions: i64,
+    /// Number of won auctions the solver settled.
+    pub settled: i64,
+    /// Can be negative if the solver failed to settle auctions.
+    pub reward: BigDecimal,
+}
+
+/// Returns the outcome of every auction with a block deadline in the period
+/// for each of its winners.
+pub async fn auction_outcomes(
+    ex: &mut PgConnection,
+    period: &Period,
+) -> Result<Vec<AuctionOutcome>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT
+    ss.auction_id,
+    ss.winner,
+    ss.reference_score,
+    s.block_number IS NOT NULL AS settled,
+    so.surplus + so.fee AS observed_score
+FROM settlement_scores ss
+LEFT JOIN settlements s ON s.auction_id = ss.auction_id AND s.solver = ss.winner
+LEFT JOIN settlement_observations so
+    ON so.block_number = s.block_number AND so.log_index = s.log_index
+WHERE ss.block_deadline >= $1 AND ss.block_deadline < $2
+ORDER BY ss.auction_id
+    ;"#;
+    sqlx::query_as(QUERY)
+        .bind(period.start_block)
+        .bind(period.end_block)
+        .fetch_all(ex)
+        .await
+}
+
+/// Stores the statements of a period and marks the period as computed.
+pub async fn insert(
+    ex: &mut PgTransaction<'_>,
+    period: &Period,
+    statements: &[Statement],
+) -> Result<(), sqlx::Error> {
+    const QUERY_PERIOD: &str = r#"INSERT INTO reward_periods (start_block, end_block, computed_at) VALUES ($1, $2, now());"#;
+    sqlx::query(QUERY_PERIOD)
+        .bind(period.start_block)
+        .bind(period.end_block)
+        .execute(ex.deref_mut())
+        .await?;
+
+    const QUERY_STATEMENT: &str = r#"INSERT INTO solver_reward_statements (start_block, solver, auctions, settled, reward) VALUES ($1, $2, $3, $4, $5);"#;
+    for statement in statements {
+        sqlx::query(QUERY_STATEMENT)
+            .bind(statement.start_block)
+            .bind(statement.solver)
+            .bind(statement.auctions)
+            .bind(statement.settled)
+            .bind(&statement.reward)
+            .execute(ex.deref_mut())
+            .await?;
+    }
+    Ok(())
+}
+
+/// Returns the period starting at the given block if it has been computed.
+pub async fn fetch_period(
+    ex: &mut PgConnection,
+    start_block: i64,
+) -> Result<Option<Period>, sqlx::Error> {
+    const QUERY: &str =
+        r#"SELECT start_block, end_block FROM reward_periods WHERE start_block = $1;"#;
+    sqlx::query_as(QUERY)
+        .bind(start_block)
+        .fetch_optional(ex)
+        .await
+}
+
+/// Returns the most recently computed period.
+pub async fn latest_period(ex: &mut PgConnection) -> Result<Option<Period>, sqlx::Error> {
+    const QUERY: &str =
+        r#"SELECT start_block, end_block FROM reward_periods ORDER BY start_block DESC LIMIT 1;"#;
+    sqlx::query_as(QUERY).fetch_optional(ex).await
+}
+
+/// Returns the statements of the period starting at the given block, highest
+/// reward first.
+pub async fn fetch_statements(
+    ex: &mut PgConnection,
+    start_block: i64,
+) -> Result<Vec<Statement>, sqlx::Error> {
+    const QUERY: &str =
+        r#"SELECT * FROM solver_reward_statements WHERE start_block = $1 ORDER BY reward DESC;"#;
+    sqlx::query_as(QUERY).bind(start_block).fetch_all(ex).await
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            byte_array::ByteArray,
+            events::{insert_settlement, EventIndex, Settlement},
+            settlement_observations::{self, Observation},
+            settlement_scores::{self, Score},
+            settlements,
+        },
+        sqlx::Connection,
+    };
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let period = Period {
+            start_block: 100,
+            end_block: 200,
+        };
+        let score = |auction_id, winner, block_deadline| Score {
+            auction_id,
+            winner: ByteArray([winner; 20]),
+            winning_score: 10.into(),
+            reference_score: 5.into(),
+            block_deadline,
+            simulation_block: 0,
+        };
+        // Settled and observed.
+        settlement_scores::insert(&mut db, score(1, 1, 100))
+            .await
+            .unwrap();
+        // Not settled.
+        settlement_scores::insert(&mut db, score(2, 2, 199))
+            .await
+            .unwrap();
+        // Outside of the period.
+        settlement_scores::insert(&mut db, score(3, 1, 200))
+            .await
+            .unwrap();
+
+        let index = EventIndex {
+            block_number: 150,
+            log_index: 0,
+        };
+        insert_settlement(
+            &mut db,
+            &index,
+            &Settlement {
+                solver: ByteArray([1; 20]),
+                transaction_hash: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+        settlements::update_settlement_auction(&mut db, 150, 0, 1)
+            .await
+            .unwrap();
+        settlement_observations::upsert(
+            &mut db,
+            Observation {
+                surplus: 7.into(),
+                fee: 1.into(),
+                block_number: 150,
+                log_index: 0,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let outcomes = auction_outcomes(&mut db, &period).await.unwrap();
+        assert_eq!(
+            outcomes,
+            vec![
+                AuctionOutcome {
+                    auction_id: 1,
+                    winner: ByteArray([1; 20]),
+                    reference_score: 5.into(),
+                    settled: true,
+                    observed_score: Some(8.into()),
+                },
+                AuctionOutcome {
+                    auction_id: 2,
+                    winner: ByteArray([2; 20]),
+                    reference_score: 5.into(),
+                    settled: false,
+                    observed_score: None,
+                },
+            ]
+        );
+
+        assert_eq!(latest_period(&mut db).await.unwrap(), None);
+        let statements = vec![
+            Statement {
+                start_block: 100,
+                solver: ByteArray([1; 20]),
+                auctions: 1,
+                settled: 1,
+                reward: 3.into(),
+            },
+            Statement {
+                start_block: 100,
+                solver: ByteArray([2; 20]),
+                auctions: 1,
+                settled: 0,
+                reward: (-5).into(),
+            },
+        ];
+        insert(&mut db, &period, &statements).await.unwrap();
+
+        assert_eq!(latest_period(&mut db).await.unwrap(), Some(period.clone()));
+        assert_eq!(fetch_period(&mut db, 100).await.unwrap(), Some(period));
+        assert_eq!(fetch_period(&mut db, 200).await.unwrap(), None);
+        assert_eq!(fetch_statements(&mut db, 100).await.unwrap(), statements);
+    }
+}
@@ -19,10 +19,28 @@ pub struct TradesQueryRow {
     pub tx_hash: Option<TransactionHash>,
 }
 
+/// Optional filters for [`trades`]. `None` means that the field is unfiltered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TradesFilter {
+    pub owner: Option<Address>,
+    pub order_uid: Option<OrderUid>,
+    /// Hash of the settlement transaction that executed the trades.
+    pub tx_hash: Option<TransactionHash>,
+    pub sell_token: Option<Address>,
+    pub buy_token: Option<Address>,
+    /// Inclusive lower bound of the block the trade happened in.
+    pub from_block: Option<i64>,
+    /// Inclusive upper bound of the block the trade happened in.
+    pub to_block: Option<i64>,
+}
+
+/// Trades matching the filter ordered by their on-chain position (oldest
+/// first). `limit: None` returns all remaining trades after `offset`.
 pub fn trades<'a>(
     ex: &'a mut PgConnection,
-    owner_filter: Option<&'a Address>,
-    order_uid_filter: Option<&'a OrderUid>,
+    filter: &TradesFilter,
+    offset: i64,
+    limit: Option<i64>,
 ) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
     const COMMON_QUERY: &str = r#"
 SELECT
@@ -46,24 +64,81 @@ LEFT OUTER JOIN LATERAL (
 ) AS settlement ON true
 JOIN orders o
 ON o.uid = t.order_uid"#;
+    // Restricting the block number first allows the tx hash filter to use the
+    // settlements index instead of computing the settlement of every trade.
+    const COMMON_FILTER: &str = r#"
+AND ($2 IS NULL OR o.uid = $2)
+AND ($3 IS NULL OR (
+    t.block_number IN (SELECT block_number FROM settlements WHERE tx_hash = $3)
+    AND settlement.tx_hash = $3
+))
+AND ($4 IS NULL OR o.sell_token = $4)
+AND ($5 IS NULL OR o.buy_token = $5)
+AND ($6 IS NULL OR t.block_number >= $6)
+AND ($7 IS NULL OR t.block_number <= $7)"#;
     const QUERY: &str = const_format::concatcp!(
+        "SELECT * FROM (",
         COMMON_QUERY,
         " WHERE ($1 IS NULL OR o.owner = $1)",
-        " AND ($2 IS NULL OR o.uid = $2)",
-        "UNION",
+        COMMON_FILTER,
+        " UNION ",
         COMMON_QUERY,
         " LEFT OUTER JOIN onchain_placed_orders onchain_o",
         " ON onchain_o.uid = t.order_uid",
         " WHERE onchain_o.sender = $1",
-        " AND ($2 IS NULL OR o.uid = $2)",
+        COMMON_FILTER,
+        " ) AS trades",
+        " ORDER BY block_number ASC, log_index ASC",
+        " LIMIT $8",
+        " OFFSET $9",
     );
 
     sqlx::query_as(QUERY)
-        .bind(owner_filter)
-        .bind(order_uid_filter)
+        .bind(filter.owner)
+        .bind(filter.order_uid)
+        .bind(filter.tx_hash)
+        .bind(filter.sell_token)
+        .bind(filter.buy_token)
+        .bind(filter.from_block)
+        .bind(filter.to_block)
+        .bind(limit)
+        .bind(offset)
         .fetch(ex)
 }
 
+/// Sums of the amounts an order got executed with by some of its trades.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct ExecutedAmounts {
+    pub order_uid: OrderUid,
+    pub sell_amount: BigDecimal,
+    pub buy_amount: BigDecimal,
+    pub fee_amount: BigDecimal,
+}
+
+/// Sums up the trades of the given orders that happened after `block`. This
+/// allows computing how much of an order was executed at that block.
+pub async fn executed_after(
+    ex: &mut PgConnection,
+    order_uids: &[OrderUid],
+    block: i64,
+) -> Result<Vec<ExecutedAmounts>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT
+    order_uid,
+    SUM(sell_amount) AS sell_amount,
+    SUM(buy_amount) AS buy_amount,
+    SUM(fee_amount) AS fee_amount
+FROM trades
+WHERE order_uid = ANY($1) AND block_number > $2
+GROUP BY order_uid
+    ;"#;
+    sqlx::query_as(QUERY)
+        .bind(order_uids)
+        .bind(block)
+        .fetch_all(ex)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -139,7 +214,12 @@ mod tests {
         order_uid_filter: Option<&OrderUid>,
         expected: &[TradesQueryRow],
     ) {
-        let filtered = trades(db, owner_filter, order_uid_filter)
+        let filter = TradesFilter {
+            owner: owner_filter.copied(),
+            order_uid: order_uid_filter.copied(),
+            ..Default::default()
+        };
+        let filtered = trades(db, &filter, 0, None)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -170,7 +250,7 @@ mod tests {
         };
         let trade_b =
             add_order_and_trade(&mut db, owners[0], order_ids[1], event_index_b, None).await;
-        assert_trades(&mut db, None, None, &[trade_b, trade_a]).await;
+        assert_trades(&mut db, None, None, &[trade_a, trade_b]).await;
     }
 
     #[tokio::test]
@@ -211,7 +291,11 @@ mod tests {
         }
 
         let now = std::time::Instant::now();
-        trades(&mut db, Some(&ByteArray([2u8; 20])), None)
+        let filter = TradesFilter {
+            owner: Some(ByteArray([2u8; 20])),
+            ..Default::default()
+        };
+        trades(&mut db, &filter, 0, None)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -495,4 +579,148 @@ mod tests {
         .await;
         assert_trades(&mut db, None, None, &[trade_a, trade_b]).await;
     }
+
+    async fn query(
+        db: &mut PgConnection,
+        filter: TradesFilter,
+        offset: i64,
+        limit: Option<i64>,
+    ) -> Vec<TradesQueryRow> {
+        trades(db, &filter, offset, limit)
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_trades_with_filters_and_pagination() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let owner = ByteArray([1; 20]);
+        let (sell_token, buy_token) = (ByteArray([2; 20]), ByteArray([3; 20]));
+        let tx_hashes = [ByteArray([4; 32]), ByteArray([5; 32])];
+
+        let mut expected = Vec::new();
+        for (i, block_number) in [0, 0, 1].into_iter().enumerate() {
+            let order = Order {
+                uid: ByteArray([i as u8; 56]),
+                owner,
+                sell_token: if i == 2 { buy_token } else { sell_token },
+                buy_token: if i == 2 { sell_token } else { buy_token },
+                ..Default::default()
+            };
+            crate::orders::insert_order(&mut db, &order).await.unwrap();
+            let tx_hash = tx_hashes[block_number as usize];
+            let mut trade = add_trade(
+                &mut db,
+                owner,
+                order.uid,
+                EventIndex {
+                    block_number,
+                    log_index: i as i64,
+                },
+                Some(tx_hash),
+            )
+            .await;
+            trade.sell_token = order.sell_token;
+            trade.buy_token = order.buy_token;
+            expected.push(trade);
+        }
+        for (block_number, tx_hash) in tx_hashes.into_iter().enumerate() {
+            let event_index = EventIndex {
+                block_number: block_number as i64,
+                log_index: 10,
+            };
+            add_settlement(&mut db, event_index, Default::default(), tx_hash).await;
+        }
+
+        let owner_filter = TradesFilter {
+            owner: Some(owner),
+            ..Default::default()
+        };
+        assert_eq!(query(&mut db, owner_filter, 0, None).await, expected);
+        assert_eq!(
+            query(&mut db, owner_filter, 1, Some(1)).await,
+            &expected[1..2]
+        );
+        assert_eq!(query(&mut db, owner_filter, 3, None).await, []);
+
+        let filter = TradesFilter {
+            tx_hash: Some(tx_hashes[0]),
+            ..Default::default()
+        };
+        assert_eq!(query(&mut db, filter, 0, None).await, &expected[..2]);
+
+        let filter = TradesFilter {
+            from_block: Some(1),
+            ..owner_filter
+        };
+        assert_eq!(query(&mut db, filter, 0, None).await, &expected[2..]);
+        let filter = TradesFilter {
+            to_block: Some(0),
+            ..owner_filter
+        };
+        assert_eq!(query(&mut db, filter, 0, None).await, &expected[..2]);
+
+        let filter = TradesFilter {
+            sell_token: Some(buy_token),
+            ..owner_filter
+        };
+        assert_eq!(query(&mut db, filter, 0, None).await, &expected[2..]);
+        let filter = TradesFilter {
+            buy_token: Some(buy_token),
+            ..owner_filter
+        };
+        assert_eq!(query(&mut db, filter, 0, None).await, &expected[..2]);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_executed_after() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (order, other) = (ByteArray([1; 56]), ByteArray([2; 56]));
+        let trade = |order_uid, amount: u32| {
+            Event::Trade(Trade {
+                order_uid,
+                sell_amount_including_fee: amount.into(),
+                buy_amount: (2 * amount).into(),
+                fee_amount: 1.into(),
+            })
+        };
+        let index = |block_number, log_index| EventIndex {
+            block_number,
+            log_index,
+        };
+        crate::events::append(
+            &mut db,
+            &[
+                (index(1, 0), trade(order, 10)),
+                (index(2, 0), trade(order, 20)),
+                (index(3, 0), trade(order, 30)),
+                (index(3, 1), trade(other, 40)),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let executed = executed_after(&mut db, &[order], 1).await.unwrap();
+        assert_eq!(
+            executed,
+            vec![ExecutedAmounts {
+                order_uid: order,
+                sell_amount: 50.into(),
+                buy_amount: 100.into(),
+                fee_amount: 2.into(),
+            }]
+        );
+
+        let executed = executed_after(&mut db, &[order, other], 3).await.unwrap();
+        assert!(executed.is_empty());
+    }
 }
@@ -29,11 +29,13 @@ use {
 
 pub mod auction;
 pub mod order;
+pub mod pool;
 pub mod solution;
 
 pub use {
     auction::{Auction, AuctionProcessor},
     order::Order,
+    pool::SolutionPool,
     solution::Solution,
 };
 
@@ -49,6 +51,9 @@ pub struct Competition {
     pub liquidity: infra::liquidity::Fetcher,
     pub simulator: Simulator,
     pub mempools: Mempools,
+    /// Solutions of all solvers hosted by the driver which opted into
+    /// cross-solver merging.
+    pub solution_pool: SolutionPool,
     pub settlement: Mutex<Option<Settlement>>,
     pub encoding: encoding::Strategy,
 }
@@ -104,10 +109,23 @@ impl Competition {
             }
         });
 
+        let solutions = solutions.collect_vec();
+
+        // Exchange solutions with the other solvers of this driver. Only real
+        // auctions have an ID (/quote requests don't).
+        let others = match (self.solver.cross_solver_merging(), auction.id()) {
+            (SolutionMerging::Allowed, Some(id)) => {
+                self.solution_pool
+                    .exchange(id, self.solver.name(), &solutions)
+            }
+            _ => Default::default(),
+        };
+
         let all_solutions = match self.solver.solution_merging() {
-            SolutionMerging::Allowed => merge(solutions, auction),
-            SolutionMerging::Forbidden => solutions.collect(),
+            SolutionMerging::Allowed => merge(solutions.into_iter(), auction),
+            SolutionMerging::Forbidden => solutions,
         };
+        let all_solutions = merge_across_solvers(all_solutions, others, auction);
 
         // Encode solutions into settlements (streamed).
         let encoded = all_solutions
@@ -122,6 +140,7 @@ impl Competition {
                         &self.simulator,
                         self.encoding,
                         self.solver.solver_native_token(),
+                        &self.mempools,
                     )
                     .await;
                 (id, settlement)
@@ -203,11 +222,14 @@ impl Competition {
                 (
                     Solved {
                         score,
+                        submission_address: settlement
+                            .transaction(settlement::Internalization::Enable)
+                            .from,
                         trades: settlement.orders(),
                         prices: settlement.prices(),
                         gas: Some(settlement.gas.estimate),
                     },
-                    settlement,
+                    settlement.with_competition_score(score),
                 )
             })
             .unzip();
@@ -289,6 +311,19 @@ impl Competition {
             settlement.solution(),
             &executed,
         );
+        // Other solvers whose solutions were merged into the settlement get
+        // notified about its outcome as well.
+        for contribution in settlement.attribution() {
+            if contribution.solver.name().0 != self.solver.name().0 {
+                notify::contributed(
+                    &contribution.solver,
+                    settlement.auction_id,
+                    &contribution.solution,
+                    self.solver.name(),
+                    &executed,
+                );
+            }
+        }
 
         match executed {
             Err(_) => Err(Error::SubmissionError),
@@ -359,8 +394,71 @@ fn merge(solutions: impl Iterator<Item = Solution>, auction: &Auction) -> Vec<So
         merged.extend(extension);
     }
 
-    // Sort merged solutions descending by score.
-    merged.sort_by_key(|solution| {
+    sort_by_score(&mut merged, auction);
+    merged
+}
+
+/// Merges the solutions other solvers proposed for the same auction into the
+/// solver's own solutions. Other solutions get merged greedily, best scoring
+/// first, since trying all combinations would be too expensive. Merged
+/// solutions are submitted by the solver they were merged for, other solvers'
+/// solutions are never proposed on their own.
+fn merge_across_solvers(
+    solutions: Vec<Solution>,
+    mut others: Vec<Solution>,
+    auction: &Auction,
+) -> Vec<Solution> {
+    if others.is_empty() {
+        return solutions;
+    }
+    sort_by_score(&mut others, auction);
+
+    let merged = merge_greedily(&solutions, &others, |current, other| {
+        match current.merge(other) {
+            Ok(result) => {
+                observe::merged(current, other, &result);
+                Some(result)
+            }
+            Err(err) => {
+                observe::not_merged(current, other, err);
+                None
+            }
+        }
+    });
+
+    let mut all = [merged, solutions].concat();
+    sort_by_score(&mut all, auction);
+    all
+}
+
+/// Merges as many of `others` as possible, in order, into each of the first
+/// [`MAX_SOLUTIONS_TO_MERGE`] solutions. Only solutions which got extended by
+/// at least one other solution are returned.
+fn merge_greedily<T: Clone>(
+    solutions: &[T],
+    others: &[T],
+    merge: impl Fn(&T, &T) -> Option<T>,
+) -> Vec<T> {
+    let mut merged = Vec::new();
+    for solution in solutions.iter().take(MAX_SOLUTIONS_TO_MERGE) {
+        let mut current = solution.clone();
+        let mut extended = false;
+        for other in others {
+            if let Some(result) = merge(&current, other) {
+                current = result;
+                extended = true;
+            }
+        }
+        if extended {
+            merged.push(current);
+        }
+    }
+    merged
+}
+
+/// Sorts solutions descending by score.
+fn sort_by_score(solutions: &mut [Solution], auction: &Auction) {
+    solutions.sort_by_key(|solution| {
         Reverse(
             solution
                 .scoring(
@@ -371,7 +469,6 @@ fn merge(solutions: impl Iterator<Item = Solution>, auction: &Auction) -> Vec<So
                 .unwrap_or_default(),
         )
     });
-    merged
 }
 
 /// Solution information sent to the protocol by the driver before the solution
@@ -379,6 +476,8 @@ fn merge(solutions: impl Iterator<Item = Solution>, auction: &Auction) -> Vec<So
 #[derive(Debug)]
 pub struct Solved {
     pub score: eth::Ether,
+    /// The account the settlement will be submitted from.
+    pub submission_address: eth::Address,
     pub trades: HashMap<order::Uid, Amounts>,
     pub prices: HashMap<eth::TokenAddress, eth::TokenAmount>,
     pub gas: Option<eth::Gas>,
@@ -434,3 +533,38 @@ pub enum Error {
     #[error("failed to submit the solution")]
     SubmissionError,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Solutions are modelled as sets of orders (one bit per order) which can
+    /// be merged as long as they don't settle the same order twice.
+    fn merge_disjoint(a: &u32, b: &u32) -> Option<u32> {
+        (a & b == 0).then_some(a | b)
+    }
+
+    #[test]
+    fn merges_other_solutions_greedily() {
+        let merged = merge_greedily(&[0b0001, 0b0110], &[0b0010, 0b1000], merge_disjoint);
+        // The first solution gets extended by both other solutions. The second
+        // one can't include the first other solution since it settles an order
+        // twice but it can still be extended by the second one.
+        assert_eq!(merged, vec![0b1011, 0b1110]);
+    }
+
+    #[test]
+    fn skips_solutions_which_cannot_be_extended() {
+        let merged = merge_greedily(&[0b0011, 0b0110], &[0b0010], merge_disjoint);
+        assert_eq!(merged, Vec::<u32>::new());
+    }
+
+    #[test]
+    fn limits_solutions_to_merge() {
+        let solutions = (0..MAX_SOLUTIONS_TO_MERGE as u32 + 5)
+            .map(|i| 1 << i)
+            .collect::<Vec<_>>();
+        let merged = merge_greedily(&solutions, &[1 << 31], merge_disjoint);
+        assert_eq!(merged.len(), MAX_SOLUTIONS_TO_MERGE);
+    }
+}
@@ -0,0 +1,78 @@
+use {
+    super::{auction, Solution},
+    crate::infra::solver,
+    std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Solutions are only kept for the most recent auctions. Solvers respond to an
+/// auction within its deadline so older solutions can't be merged anymore.
+const AUCTIONS_TO_KEEP: usize = 2;
+
+/// The solutions that solvers hosted by this driver proposed for the most
+/// recent auctions. Solvers which opted into cross-solver merging publish their
+/// solutions here so that solvers responding later can merge them into their
+/// own solutions.
+#[derive(Debug, Clone)]
+pub struct SolutionPool<T = Solution>(Arc<Mutex<BTreeMap<i64, BTreeMap<String, Vec<T>>>>>);
+
+impl<T> Default for SolutionPool<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T: Clone> SolutionPool<T> {
+    /// Publishes the solutions of a solver for the auction and returns the
+    /// solutions other solvers published for the same auction so far.
+    pub fn exchange(&self, auction: auction::Id, solver: &solver::Name, solutions: &[T]) -> Vec<T> {
+        let mut auctions = self.0.lock().unwrap();
+        let published = auctions.entry(auction.0).or_default();
+        let others = published
+            .iter()
+            .filter(|(name, _)| **name != solver.0)
+            .flat_map(|(_, solutions)| solutions.iter().cloned())
+            .collect();
+        published.insert(solver.0.clone(), solutions.to_vec());
+        while auctions.len() > AUCTIONS_TO_KEEP {
+            auctions.pop_first();
+        }
+        others
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exchanges_solutions_of_other_solvers() {
+        let pool = SolutionPool::default();
+        let (a, b) = (solver::Name("a".into()), solver::Name("b".into()));
+
+        assert_eq!(
+            pool.exchange(auction::Id(1), &a, &[1, 2]),
+            Vec::<i32>::new()
+        );
+        assert_eq!(pool.exchange(auction::Id(1), &b, &[3]), vec![1, 2]);
+        // Solutions of other auctions are kept separately.
+        assert_eq!(pool.exchange(auction::Id(2), &b, &[4]), Vec::<i32>::new());
+        // Publishing again replaces the solver's previous solutions.
+        assert_eq!(pool.exchange(auction::Id(1), &a, &[5]), vec![3]);
+        assert_eq!(pool.exchange(auction::Id(1), &b, &[]), vec![5]);
+    }
+
+    #[test]
+    fn forgets_old_auctions() {
+        let pool = SolutionPool::default();
+        let (a, b) = (solver::Name("a".into()), solver::Name("b".into()));
+
+        for auction in 1..=AUCTIONS_TO_KEEP as i64 + 1 {
+            pool.exchange(auction::Id(auction), &a, &[auction]);
+        }
+        assert_eq!(pool.exchange(auction::Id(1), &b, &[]), Vec::<i64>::new());
+        assert_eq!(pool.exchange(auction::Id(3), &b, &[]), vec![3]);
+    }
+}
@@ -6,12 +6,13 @@ use {
         domain::{
             competition::{self, order},
             eth::{self, TokenAddress},
+            Mempools,
         },
         infra::{
             blockchain::{self, Ethereum},
             config::file::FeeHandler,
             simulator,
-            solver::{ManageNativeToken, Solver},
+            solver::{ManageNativeToken, SolutionMerging, Solver},
             Simulator,
         },
     },
@@ -50,6 +51,25 @@ pub struct Solution {
     solver: Solver,
     weth: eth::WethAddress,
     gas: Option<eth::Gas>,
+    /// The solutions this solution was merged from, including solutions of
+    /// other solvers.
+    attribution: Vec<Contribution>,
+}
+
+/// A solution of a solver that went into a (possibly merged) solution.
+#[derive(Clone)]
+pub struct Contribution {
+    pub solver: Solver,
+    pub solution: Id,
+}
+
+impl std::fmt::Debug for Contribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Contribution")
+            .field("solver", &self.solver.name())
+            .field("solution", &self.solution)
+            .finish()
+    }
 }
 
 impl Solution {
@@ -107,6 +127,10 @@ impl Solution {
             );
         }
 
+        let attribution = vec![Contribution {
+            solver: solver.clone(),
+            solution: id.clone(),
+        }];
         let solution = Self {
             id,
             trades,
@@ -117,6 +141,7 @@ impl Solution {
             solver,
             weth,
             gas,
+            attribution,
         };
 
         // Check that the solution includes clearing prices for all user trades.
@@ -266,10 +291,19 @@ impl Solution {
             .any(|trade| self.trade_count_for_scorable(trade, surplus_capturing_jit_order_owners))
     }
 
+    /// Merges two solutions into one. The merged solution is submitted by the
+    /// solver of `self`.
     pub fn merge(&self, other: &Self) -> Result<Self, error::Merge> {
-        // We can only merge solutions from the same solver
-        if self.solver.account().address() != other.solver.account().address() {
-            return Err(error::Merge::Incompatible("Solvers"));
+        // We can only merge solutions from the same solver unless both solvers
+        // opted into merging their solutions with each other.
+        if self.solver.address() != other.solver.address() {
+            compatible_solvers(
+                (
+                    self.solver.cross_solver_merging(),
+                    other.solver.cross_solver_merging(),
+                ),
+                (self.solver.fee_handler(), other.solver.fee_handler()),
+            )?;
         }
 
         // Solutions should not settle the same order twice
@@ -286,7 +320,10 @@ impl Solution {
 
         // To avoid precision issues, make sure we always scale up settlements
         if factor < BigRational::one() {
-            return other.merge(self);
+            return other.merge(self).map(|merged| Solution {
+                solver: self.solver.clone(),
+                ..merged
+            });
         }
 
         // Scale prices
@@ -328,16 +365,22 @@ impl Solution {
             .concat(),
             solver: self.solver.clone(),
             weth: self.weth,
-            // Same solver are guaranteed to have the same fee handler
+            // Merged solutions are guaranteed to have the same fee handler
             gas: match (self.gas, other.gas) {
                 (Some(gas), Some(other_gas)) => Some(gas + other_gas),
                 (Some(gas), None) => Some(gas),
                 (None, Some(gas)) => Some(gas),
                 (None, None) => None,
             },
+            attribution: [self.attribution.clone(), other.attribution.clone()].concat(),
         })
     }
 
+    /// The solutions this solution was merged from.
+    pub fn attribution(&self) -> &[Contribution] {
+        &self.attribution
+    }
+
     /// Return the trades which fulfill non-liquidity auction orders. These are
     /// the orders placed by end users.
     fn user_trades(&self) -> impl Iterator<Item = &trade::Fulfillment> {
@@ -395,8 +438,18 @@ impl Solution {
         simulator: &Simulator,
         encoding: encoding::Strategy,
         solver_native_token: ManageNativeToken,
+        mempools: &Mempools,
     ) -> Result<Settlement, Error> {
-        Settlement::encode(self, auction, eth, simulator, encoding, solver_native_token).await
+        Settlement::encode(
+            self,
+            auction,
+            eth,
+            simulator,
+            encoding,
+            solver_native_token,
+            mempools,
+        )
+        .await
     }
 
     /// Token prices settled by this solution, expressed using an arbitrary
@@ -477,10 +530,30 @@ impl std::fmt::Debug for Solution {
             .field("interactions", &self.interactions)
             .field("post_interactions", &self.post_interactions)
             .field("solver", &self.solver.name())
+            .field("attribution", &self.attribution)
             .finish()
     }
 }
 
+/// Checks whether solutions of two different solvers can be merged. Both
+/// solvers need to opt into cross-solver merging and handle fees the same way
+/// since the merged solution gets processed like a solution of either solver.
+fn compatible_solvers(
+    merging: (SolutionMerging, SolutionMerging),
+    fee_handlers: (FeeHandler, FeeHandler),
+) -> Result<(), error::Merge> {
+    if !matches!(
+        merging,
+        (SolutionMerging::Allowed, SolutionMerging::Allowed)
+    ) {
+        return Err(error::Merge::Incompatible("Solvers"));
+    }
+    if fee_handlers.0 != fee_handlers.1 {
+        return Err(error::Merge::Incompatible("FeeHandler"));
+    }
+    Ok(())
+}
+
 /// Given two solutions returns the factors with
 /// which prices of the second solution would have to be multiplied so that the
 /// given token would have the same price in both solutions.
@@ -572,8 +645,8 @@ pub mod error {
         FailingInternalization,
         #[error("Gas estimate of {0:?} exceeded the per settlement limit of {1:?}")]
         GasLimitExceeded(eth::Gas, eth::Gas),
-        #[error("insufficient solver account Ether balance, required {0:?}")]
-        SolverAccountInsufficientBalance(eth::Ether),
+        #[error("insufficient Ether balance of solver accounts {0:?}, required {1:?}")]
+        SolverAccountInsufficientBalance(Vec<eth::Address>, eth::Ether),
         #[error("attempted to merge settlements generated by different solvers")]
         DifferentSolvers,
         #[error("encoding error: {0:?}")]
@@ -632,3 +705,31 @@ pub mod error {
         Math(#[from] Math),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_across_solvers_only_if_compatible() {
+        let allowed = SolutionMerging::Allowed;
+        let forbidden = SolutionMerging::Forbidden;
+        let driver = FeeHandler::Driver;
+        let solver = FeeHandler::Solver;
+
+        assert!(compatible_solvers((allowed, allowed), (driver, driver)).is_ok());
+        assert!(compatible_solvers((allowed, allowed), (solver, solver)).is_ok());
+        assert!(matches!(
+            compatible_solvers((allowed, forbidden), (driver, driver)),
+            Err(error::Merge::Incompatible("Solvers"))
+        ));
+        assert!(matches!(
+            compatible_solvers((forbidden, allowed), (driver, driver)),
+            Err(error::Merge::Incompatible("Solvers"))
+        ));
+        assert!(matches!(
+            compatible_solvers((allowed, allowed), (driver, solver)),
+            Err(error::Merge::Incompatible("FeeHandler"))
+        ));
+    }
+}
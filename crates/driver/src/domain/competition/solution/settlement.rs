@@ -5,6 +5,7 @@ use {
         domain::{
             competition::{self, auction, order, solution},
             eth,
+            Mempools,
         },
         infra::{blockchain::Ethereum, observe, solver::ManageNativeToken, Simulator},
     },
@@ -40,6 +41,8 @@ pub struct Settlement {
     /// The gas parameters used by the settlement.
     pub gas: Gas,
     solution: Solution,
+    /// The score the settlement competed with, if it was scored.
+    competition_score: Option<eth::Ether>,
 }
 
 #[derive(Debug, Clone)]
@@ -60,6 +63,20 @@ impl SettlementTx {
             ..self
         }
     }
+
+    fn with_sender(self, from: eth::Address) -> Self {
+        Self {
+            internalized: eth::Tx {
+                from,
+                ..self.internalized
+            },
+            uninternalized: eth::Tx {
+                from,
+                ..self.uninternalized
+            },
+            ..self
+        }
+    }
 }
 
 impl Settlement {
@@ -71,6 +88,7 @@ impl Settlement {
         simulator: &Simulator,
         encoding: encoding::Strategy,
         solver_native_token: ManageNativeToken,
+        mempools: &Mempools,
     ) -> Result<Self, Error> {
         // For a settlement to be valid, the solution has to respect some rules which
         // would otherwise lead to slashing. Check those rules first.
@@ -152,7 +170,15 @@ impl Settlement {
                 may_revert: solution.revertable(),
             },
         };
-        Self::new(auction.id().unwrap(), solution, tx, eth, simulator).await
+        Self::new(
+            auction.id().unwrap(),
+            solution,
+            tx,
+            eth,
+            simulator,
+            mempools,
+        )
+        .await
     }
 
     /// Create a new settlement and ensure that it is valid.
@@ -162,6 +188,7 @@ impl Settlement {
         transaction: SettlementTx,
         eth: &Ethereum,
         simulator: &Simulator,
+        mempools: &Mempools,
     ) -> Result<Self, Error> {
         // The settlement contract will fail if the receiver is a smart contract.
         // Because of this, if the receiver is a smart contract and we try to
@@ -203,12 +230,9 @@ impl Settlement {
         let price = eth.gas_price().await?;
         let gas = Gas::new(gas, eth.block_gas_limit(), price)?;
 
-        // Ensure that the solver has sufficient balance for the settlement to be mined.
-        if eth.balance(solution.solver().address()).await? < gas.required_balance() {
-            return Err(Error::SolverAccountInsufficientBalance(
-                gas.required_balance(),
-            ));
-        }
+        // Ensure that the settlement gets submitted from an account with
+        // sufficient balance for the settlement to be mined.
+        let sender = Self::sender(&solution, eth, mempools, gas.required_balance()).await?;
 
         // Is at least one interaction internalized?
         if solution
@@ -234,11 +258,69 @@ impl Settlement {
         Ok(Self {
             auction_id,
             solution,
-            transaction: transaction.with_access_list(access_list),
+            transaction: transaction
+                .with_access_list(access_list)
+                .with_sender(sender),
             gas,
+            competition_score: None,
         })
     }
 
+    /// Records the score the settlement competed with. The score limits how
+    /// much may be spent on getting the settlement included.
+    pub fn with_competition_score(self, score: eth::Ether) -> Self {
+        Self {
+            competition_score: Some(score),
+            ..self
+        }
+    }
+
+    /// The score the settlement competed with.
+    pub fn competition_score(&self) -> Option<eth::Ether> {
+        self.competition_score
+    }
+
+    /// Picks the account of the solver to submit the settlement from. Idle
+    /// accounts are preferred so the settlement doesn't have to wait for
+    /// pending settlements to get mined. The settlement is simulated from the
+    /// solver's main account, which is equivalent because all submission
+    /// accounts are verified to be allow-listed on startup.
+    ///
+    /// Note that the account is picked when the solution gets encoded while
+    /// accounts only become busy once a settlement gets submitted. Solutions
+    /// for consecutive auctions which are encoded before the earlier one gets
+    /// settled may therefore pick the same account. That is safe since nonces
+    /// are reserved on submission, but the later settlement has to wait for
+    /// the earlier one to get mined.
+    async fn sender(
+        solution: &Solution,
+        eth: &Ethereum,
+        mempools: &Mempools,
+        required: eth::Ether,
+    ) -> Result<eth::Address, Error> {
+        let (idle, busy): (Vec<_>, Vec<_>) = solution
+            .solver()
+            .accounts()
+            .map(|account| eth::Address::from(account.address()))
+            .partition(|account| mempools.is_idle(*account));
+        let mut insufficient = Vec::new();
+        for account in idle.into_iter().chain(busy) {
+            if eth.balance(account).await? >= required {
+                return Ok(account);
+            }
+            observe::solver_account_insufficient_balance(
+                solution.solver().name(),
+                account,
+                required,
+            );
+            insufficient.push(account);
+        }
+        Err(Error::SolverAccountInsufficientBalance(
+            insufficient,
+            required,
+        ))
+    }
+
     /// Simulate executing this settlement on the blockchain. This process
     /// ensures that the settlement does not revert, and calculates the
     /// access list and gas needed to settle the solution.
@@ -297,6 +379,11 @@ impl Settlement {
         self.solution.solver().address()
     }
 
+    /// The solutions this settlement was merged from.
+    pub fn attribution(&self) -> &[solution::Contribution] {
+        self.solution.attribution()
+    }
+
     /// The settled user orders with their in/out amounts.
     pub fn orders(&self) -> HashMap<order::Uid, competition::Amounts> {
         let mut acc: HashMap<order::Uid, competition::Amounts> = HashMap::new();
@@ -9,6 +9,10 @@ use {
     },
     ethrpc::current_block::into_stream,
     futures::{future::select_ok, FutureExt, StreamExt},
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
     thiserror::Error,
     tracing::Instrument,
 };
@@ -25,6 +29,7 @@ const CANCELLATION_GAS_AMOUNT: u64 = 21000;
 pub struct Mempools {
     mempools: Vec<infra::Mempool>,
     ethereum: Ethereum,
+    nonces: Nonces,
 }
 
 impl Mempools {
@@ -32,21 +37,37 @@ impl Mempools {
         if mempools.is_empty() {
             Err(NoMempools)
         } else {
-            Ok(Self { mempools, ethereum })
+            Ok(Self {
+                mempools,
+                ethereum,
+                nonces: Default::default(),
+            })
         }
     }
 
-    /// Publish a settlement to the mempools.
+    /// Publish a settlement to the mempools. The settlement gets submitted
+    /// from the account it was encoded for.
     pub async fn execute(
         &self,
         solver: &Solver,
         settlement: &Settlement,
     ) -> Result<eth::TxId, Error> {
+        let account = settlement
+            .transaction(settlement::Internalization::Enable)
+            .from;
+        let onchain = self
+            .ethereum
+            .nonce(account)
+            .await
+            .map_err(|err| Error::Other(err.into()))?;
+        let reservation = self.nonces.reserve(account, onchain);
+
         let (tx_hash, _remaining_futures) =
             select_ok(self.mempools.iter().cloned().map(|mempool| {
+                let nonce = reservation.value;
                 async move {
                     let result = self
-                        .submit(&mempool, solver, settlement)
+                        .submit(&mempool, solver, settlement, nonce)
                         .instrument(tracing::info_span!("mempool", kind = mempool.to_string()))
                         .await;
                     observe::mempool_executed(&mempool, settlement, &result);
@@ -59,6 +80,15 @@ impl Mempools {
         Ok(tx_hash)
     }
 
+    /// Whether the account has no settlement in flight. Submitting from an
+    /// idle account means the settlement doesn't have to wait for previous
+    /// settlements to get mined. An account only stops being idle once a
+    /// settlement from it gets submitted, not when it gets picked for a
+    /// solution.
+    pub fn is_idle(&self, account: eth::Address) -> bool {
+        self.nonces.is_idle(account)
+    }
+
     /// Defines if the mempools are configured in a way that guarantees that
     /// settled solution will not revert.
     pub fn revert_protection(&self) -> RevertProtection {
@@ -79,6 +109,7 @@ impl Mempools {
         mempool: &infra::mempool::Mempool,
         solver: &Solver,
         settlement: &Settlement,
+        nonce: eth::U256,
     ) -> Result<eth::TxId, Error> {
         // Don't submit risky transactions if revert protection is
         // enabled and the settlement may revert in this mempool.
@@ -98,49 +129,94 @@ impl Mempools {
         let mut block_stream = into_stream(self.ethereum.current_block().clone());
         block_stream.next().await;
 
-        let hash = mempool.submit(tx.clone(), settlement.gas, solver).await?;
+        let mut gas = settlement.gas;
+        let mut hashes = vec![mempool.submit(tx.clone(), gas, solver, nonce).await?];
+        let max_cost = settlement
+            .competition_score()
+            .zip(mempool.config().max_cost_share_of_score)
+            .map(|(score, share)| {
+                eth::Ether(eth::U256::from_f64_lossy(score.0.to_f64_lossy() * share))
+            });
 
         // Wait for the transaction to be mined, expired or failing.
         let result = async {
+            let mut blocks = 0;
             loop {
                 // Wait for the next block to be mined or we time out.
                 if tokio::time::timeout_at(deadline, block_stream.next())
                     .await
                     .is_err()
                 {
-                    tracing::info!(?hash, "tx not confirmed in time, cancelling");
-                    self.cancel(mempool, settlement.gas.price, solver).await?;
+                    tracing::info!(?hashes, "tx not confirmed in time, cancelling");
+                    self.cancel(mempool, gas.price, solver, tx.from, nonce)
+                        .await?;
                     return Err(Error::Expired);
                 }
-                tracing::debug!(?hash, "checking if tx is confirmed");
+                blocks += 1;
+                tracing::debug!(?hashes, "checking if tx is confirmed");
 
-                let receipt = self
-                    .ethereum
-                    .transaction_status(&hash)
-                    .await
-                    .unwrap_or_else(|err| {
-                        tracing::warn!(?hash, ?err, "failed to get transaction status",);
-                        TxStatus::Pending
-                    });
-                match receipt {
-                    TxStatus::Executed => return Ok(hash.clone()),
-                    TxStatus::Reverted => return Err(Error::Revert(hash.clone())),
-                    TxStatus::Pending => {
-                        // Check if transaction still simulates
-                        if let Err(err) = self.ethereum.estimate_gas(tx).await {
-                            if err.is_revert() {
-                                tracing::info!(
-                                    ?hash,
-                                    ?err,
-                                    "tx started failing in mempool, cancelling"
-                                );
-                                self.cancel(mempool, settlement.gas.price, solver).await?;
-                                return Err(Error::SimulationRevert);
-                            } else {
-                                tracing::warn!(?hash, ?err, "couldn't re-simulate tx");
-                            }
+                // Any of the submitted replacements might have been mined.
+                for hash in &hashes {
+                    let status =
+                        self.ethereum
+                            .transaction_status(hash)
+                            .await
+                            .unwrap_or_else(|err| {
+                                tracing::warn!(?hash, ?err, "failed to get transaction status",);
+                                TxStatus::Pending
+                            });
+                    match status {
+                        TxStatus::Executed => return Ok(hash.clone()),
+                        TxStatus::Reverted => return Err(Error::Revert(hash.clone())),
+                        TxStatus::Pending => {}
+                    }
+                }
+
+                // Check if transaction still simulates before paying more for it.
+                let simulates = match self.ethereum.estimate_gas(tx).await {
+                    Ok(_) => true,
+                    Err(err) if err.is_revert() => {
+                        tracing::info!(?hashes, ?err, "tx started failing in mempool, cancelling");
+                        self.cancel(mempool, gas.price, solver, tx.from, nonce)
+                            .await?;
+                        return Err(Error::SimulationRevert);
+                    }
+                    Err(err) => {
+                        tracing::warn!(?hashes, ?err, "couldn't re-simulate tx");
+                        false
+                    }
+                };
+
+                let bumped = if simulates {
+                    bumped_price(
+                        mempool.config(),
+                        settlement.gas.price,
+                        gas.price,
+                        blocks,
+                        gas.estimate,
+                        max_cost,
+                    )
+                } else {
+                    None
+                };
+                // Bundles expire after a single block so they need to be
+                // submitted again for the upcoming one even without a bump.
+                if bumped.is_none() && !mempool.submits_bundles() {
+                    continue;
+                }
+                let replacement = competition::solution::settlement::Gas {
+                    price: bumped.unwrap_or(gas.price),
+                    ..gas
+                };
+                match mempool.submit(tx.clone(), replacement, solver, nonce).await {
+                    Ok(hash) => {
+                        tracing::debug!(?hash, price = ?replacement.price, "resubmitted tx");
+                        gas = replacement;
+                        if !hashes.iter().any(|known| known.0 == hash.0) {
+                            hashes.push(hash);
                         }
                     }
+                    Err(err) => tracing::warn!(?hashes, ?err, "failed to resubmit tx"),
                 }
             }
         }
@@ -149,25 +225,33 @@ impl Mempools {
         if result.is_err() {
             // Do one last attempt to see if the transaction was confirmed (in case of race
             // conditions or misclassified errors like `OrderFilled` simulation failures).
-            if let Ok(TxStatus::Executed) = self.ethereum.transaction_status(&hash).await {
-                tracing::info!(?hash, "Found confirmed transaction, ignoring error");
-                return Ok(hash);
+            for hash in hashes {
+                if let Ok(TxStatus::Executed) = self.ethereum.transaction_status(&hash).await {
+                    tracing::info!(?hash, "Found confirmed transaction, ignoring error");
+                    return Ok(hash);
+                }
             }
         }
         result
     }
 
-    /// Cancel a pending settlement by sending a transaction to self with a
-    /// slightly higher gas price than the existing one.
+    /// Cancel a pending settlement by sending a transaction to self with the
+    /// same nonce and a slightly higher gas price than the existing one.
     async fn cancel(
         &self,
         mempool: &infra::mempool::Mempool,
         pending: eth::GasPrice,
         solver: &Solver,
+        account: eth::Address,
+        nonce: eth::U256,
     ) -> Result<(), Error> {
+        if mempool.submits_bundles() {
+            // Not resubmitting the bundle is enough to cancel it.
+            return Ok(());
+        }
         let cancellation = eth::Tx {
-            from: solver.address(),
-            to: solver.address(),
+            from: account,
+            to: account,
             value: 0.into(),
             input: Default::default(),
             access_list: Default::default(),
@@ -177,11 +261,126 @@ impl Mempools {
             limit: CANCELLATION_GAS_AMOUNT.into(),
             price: pending * GAS_PRICE_BUMP,
         };
-        mempool.submit(cancellation, gas, solver).await?;
+        mempool.submit(cancellation, gas, solver, nonce).await?;
         Ok(())
     }
 }
 
+/// The gas price to replace a pending settlement with after it didn't get
+/// included for `blocks` blocks. Returns `None` if the pending settlement
+/// should not be replaced, either because the bump curve doesn't increase the
+/// price enough for nodes to accept the replacement yet or because the
+/// replacement would cost more than allowed.
+fn bumped_price(
+    config: &infra::mempool::Config,
+    initial: eth::GasPrice,
+    pending: eth::GasPrice,
+    blocks: u32,
+    gas: eth::Gas,
+    max_cost: Option<eth::Ether>,
+) -> Option<eth::GasPrice> {
+    let bumped = initial * config.gas_price_bump.factor(blocks);
+    let required = pending * GAS_PRICE_BUMP;
+    if bumped.max() < required.max() || bumped.tip() < required.tip() {
+        return None;
+    }
+    let max_fee = eth::U256::from(bumped.max());
+    if max_fee > config.gas_price_cap {
+        return None;
+    }
+    if max_cost.is_some_and(|max_cost| gas.0.saturating_mul(max_fee) > max_cost.0) {
+        return None;
+    }
+    Some(bumped)
+}
+
+/// How the gas price of a pending settlement increases with every block it
+/// doesn't get included in. The price is only ever replaced once it increased
+/// by at least [`GAS_PRICE_BUMP`] since the last submission because nodes
+/// reject smaller replacements.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum GasPriceBump {
+    /// Keep the initial gas price.
+    #[default]
+    None,
+    /// Increase the gas price by `step` times the initial gas price per block.
+    Linear { step: f64 },
+    /// Multiply the gas price by `factor` per block.
+    Exponential { factor: f64 },
+}
+
+impl GasPriceBump {
+    /// The factor the initial gas price gets multiplied with after `blocks`
+    /// blocks.
+    fn factor(&self, blocks: u32) -> f64 {
+        match self {
+            Self::None => 1.,
+            Self::Linear { step } => 1. + step * f64::from(blocks),
+            Self::Exponential { factor } => factor.powf(f64::from(blocks)),
+        }
+    }
+}
+
+/// Hands out nonces for the accounts settlements are submitted from. Nodes
+/// don't know about settlements submitted to private mempools so the nonces of
+/// settlements in flight are tracked locally. Once an account has no
+/// settlement in flight anymore its on-chain nonce is authoritative again,
+/// which also recovers from nonces that never got used because their
+/// settlement expired.
+#[derive(Debug, Clone, Default)]
+struct Nonces(Arc<Mutex<HashMap<eth::Address, InFlight>>>);
+
+#[derive(Debug)]
+struct InFlight {
+    next_nonce: eth::U256,
+    settlements: usize,
+}
+
+impl Nonces {
+    fn is_idle(&self, account: eth::Address) -> bool {
+        !self.0.lock().unwrap().contains_key(&account)
+    }
+
+    /// Reserves the next nonce of the account. `onchain` is the nonce of the
+    /// next transaction according to the node.
+    fn reserve(&self, account: eth::Address, onchain: eth::U256) -> Reservation {
+        let mut accounts = self.0.lock().unwrap();
+        let in_flight = accounts.entry(account).or_insert(InFlight {
+            next_nonce: onchain,
+            settlements: 0,
+        });
+        let value = in_flight.next_nonce.max(onchain);
+        in_flight.next_nonce = value + 1;
+        in_flight.settlements += 1;
+        Reservation {
+            nonces: self.clone(),
+            account,
+            value,
+        }
+    }
+}
+
+/// A nonce in use by a settlement in flight. The account becomes idle again
+/// once all of its reservations are dropped.
+#[derive(Debug)]
+struct Reservation {
+    nonces: Nonces,
+    account: eth::Address,
+    value: eth::U256,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let mut accounts = self.nonces.0.lock().unwrap();
+        if let Some(in_flight) = accounts.get_mut(&self.account) {
+            in_flight.settlements -= 1;
+            if in_flight.settlements == 0 {
+                accounts.remove(&self.account);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("no mempools configured, cannot execute settlements")]
 pub struct NoMempools;
@@ -207,3 +406,72 @@ pub enum Error {
     #[error("Failed to submit: {0:?}")]
     Other(#[from] anyhow::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hands_out_consecutive_nonces_while_in_flight() {
+        let nonces = Nonces::default();
+        let (a, b) = (
+            eth::Address(eth::H160([1; 20])),
+            eth::Address(eth::H160([2; 20])),
+        );
+
+        let first = nonces.reserve(a, 5.into());
+        assert_eq!(first.value, 5.into());
+        assert!(!nonces.is_idle(a));
+        assert!(nonces.is_idle(b));
+
+        // The node doesn't know about the settlement in flight yet.
+        let second = nonces.reserve(a, 5.into());
+        assert_eq!(second.value, 6.into());
+        // The node knows about more transactions than the driver submitted.
+        let third = nonces.reserve(a, 10.into());
+        assert_eq!(third.value, 10.into());
+
+        drop((first, second, third));
+        assert!(nonces.is_idle(a));
+        // Unused nonces get reused once no settlement is in flight anymore.
+        assert_eq!(nonces.reserve(a, 5.into()).value, 5.into());
+    }
+
+    #[test]
+    fn bumps_gas_price_along_curve() {
+        let gwei = |amount: u64| eth::U256::from(amount) * eth::U256::exp10(9);
+        let config = |gas_price_bump| infra::mempool::Config {
+            min_priority_fee: Default::default(),
+            gas_price_cap: gwei(100),
+            target_confirm_time: Default::default(),
+            max_confirm_time: Default::default(),
+            retry_interval: Default::default(),
+            gas_price_bump,
+            max_cost_share_of_score: None,
+            kind: infra::mempool::Kind::Public(infra::mempool::RevertProtection::Disabled),
+        };
+        let initial = eth::GasPrice::new(gwei(50).into(), gwei(2).into(), gwei(20).into());
+        let max_fee = |price: eth::GasPrice| eth::U256::from(price.max());
+        let gas = eth::Gas(100_000.into());
+
+        let linear = config(GasPriceBump::Linear { step: 0.0625 });
+        // A 6.25% increase is not enough to replace the pending settlement.
+        assert!(bumped_price(&linear, initial, initial, 1, gas, None).is_none());
+        let bumped = bumped_price(&linear, initial, initial, 2, gas, None).unwrap();
+        assert_eq!(max_fee(bumped), gwei(56) + gwei(1) / 4);
+        assert!(bumped_price(&linear, initial, bumped, 4, gas, None).is_none());
+        assert!(bumped_price(&linear, initial, bumped, 5, gas, None).is_some());
+
+        let exponential = config(GasPriceBump::Exponential { factor: 1.25 });
+        let bumped = bumped_price(&exponential, initial, initial, 1, gas, None).unwrap();
+        assert_eq!(max_fee(bumped), gwei(62) + gwei(1) / 2);
+        // Capped by the maximum gas price.
+        assert!(bumped_price(&exponential, initial, initial, 4, gas, None).is_none());
+        // Capped by the share of the score.
+        let max_cost = eth::Ether(gas.0 * gwei(60));
+        assert!(bumped_price(&exponential, initial, initial, 1, gas, Some(max_cost)).is_none());
+
+        let none = config(GasPriceBump::None);
+        assert!(bumped_price(&none, initial, initial, 10, gas, None).is_none());
+    }
+}
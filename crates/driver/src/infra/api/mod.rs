@@ -50,6 +50,7 @@ impl Api {
 
         let tokens = tokens::Fetcher::new(&self.eth);
         let pre_processor = domain::competition::AuctionProcessor::new(&self.eth);
+        let solution_pool = domain::competition::SolutionPool::default();
 
         // Add the metrics and healthz endpoints.
         app = routes::metrics(app);
@@ -76,6 +77,7 @@ impl Api {
                     liquidity: self.liquidity.clone(),
                     simulator: self.simulator.clone(),
                     mempools: self.mempools.clone(),
+                    solution_pool: solution_pool.clone(),
                     settlement: Default::default(),
                     encoding: self.encoding.to_domain(),
                 },
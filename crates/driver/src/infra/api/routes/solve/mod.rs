@@ -35,7 +35,7 @@ async fn route(
         let competition = state.competition();
         let result = competition.solve(&auction).await;
         observe::solved(state.solver().name(), &result);
-        Ok(axum::Json(dto::Solved::new(result?, &competition.solver)))
+        Ok(axum::Json(dto::Solved::new(result?)))
     };
 
     handle_request
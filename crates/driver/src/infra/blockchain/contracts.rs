@@ -10,6 +10,7 @@ pub struct Contracts {
     vault_relayer: eth::ContractAddress,
     vault: contracts::BalancerV2Vault,
     weth: contracts::WETH9,
+    authenticator: contracts::GPv2AllowListAuthentication,
 
     /// The domain separator for settlement contract used for signing orders.
     settlement_domain_separator: eth::DomainSeparator,
@@ -51,6 +52,11 @@ impl Contracts {
             address_for(contracts::WETH9::raw_contract(), addresses.weth),
         );
 
+        let authenticator = contracts::GPv2AllowListAuthentication::at(
+            web3,
+            settlement.methods().authenticator().call().await?,
+        );
+
         let settlement_domain_separator = eth::DomainSeparator(
             settlement
                 .domain_separator()
@@ -65,6 +71,7 @@ impl Contracts {
             vault_relayer,
             vault,
             weth,
+            authenticator,
             settlement_domain_separator,
         })
     }
@@ -85,6 +92,12 @@ impl Contracts {
         &self.weth
     }
 
+    /// The contract which allow-lists the accounts that can submit
+    /// settlements.
+    pub fn authenticator(&self) -> &contracts::GPv2AllowListAuthentication {
+        &self.authenticator
+    }
+
     pub fn weth_address(&self) -> eth::WethAddress {
         self.weth.address().into()
     }
@@ -45,7 +45,7 @@ impl GasPriceEstimator {
                     additional_tip_percentage,
                     ..
                 } => Some((max_additional_tip, additional_tip_percentage)),
-                mempool::Kind::Public(_) => None,
+                mempool::Kind::Public(_) | mempool::Kind::BuilderRelay { .. } => None,
             })
             .next();
         // Use the lowest max_fee_per_gas of all mempools as the max_fee_per_gas
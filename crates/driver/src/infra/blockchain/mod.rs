@@ -206,6 +206,16 @@ impl Ethereum {
             .map_err(Into::into)
     }
 
+    /// Returns the nonce of the next transaction of the specified account,
+    /// including transactions pending in the node's mempool.
+    pub async fn nonce(&self, address: eth::Address) -> Result<eth::U256, Error> {
+        self.web3
+            .eth()
+            .transaction_count(address.into(), Some(web3::types::BlockNumber::Pending))
+            .await
+            .map_err(Into::into)
+    }
+
     /// Returns a [`token::Erc20`] for the specified address.
     pub fn erc20(&self, address: eth::TokenAddress) -> token::Erc20 {
         token::Erc20::new(self, address)
@@ -233,6 +243,11 @@ impl Ethereum {
             .map_err(Into::into)
     }
 
+    /// The node connection used to fetch state for simulations.
+    pub(super) fn web3(&self) -> &DynWeb3 {
+        &self.web3
+    }
+
     pub(super) async fn simulation_gas_price(&self) -> Option<eth::U256> {
         // Some nodes don't pick a reasonable default value when you don't specify a gas
         // price and default to 0. Additionally some sneaky tokens have special code
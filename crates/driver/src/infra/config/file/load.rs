@@ -1,6 +1,6 @@
 use {
     crate::{
-        domain::eth,
+        domain::{eth, mempools},
         infra::{
             self,
             blockchain,
@@ -46,21 +46,6 @@ pub async fn load(chain: eth::ChainId, path: &Path) -> infra::Config {
     );
     infra::Config {
         solvers: join_all(config.solvers.into_iter().map(|config| async move {
-            let account = match config.account {
-                file::Account::PrivateKey(private_key) => ethcontract::Account::Offline(
-                    ethcontract::PrivateKey::from_raw(private_key.0).unwrap(),
-                    None,
-                ),
-                file::Account::Kms(key_id) => {
-                    let config = ethcontract::aws_config::load_from_env().await;
-                    let account =
-                        ethcontract::transaction::kms::Account::new((&config).into(), &key_id.0)
-                            .await
-                            .unwrap_or_else(|_| panic!("Unable to load KMS account {:?}", key_id));
-                    ethcontract::Account::Kms(account, None)
-                }
-                file::Account::Address(address) => ethcontract::Account::Local(address, None),
-            };
             solver::Config {
                 endpoint: config.endpoint,
                 name: config.name.into(),
@@ -73,7 +58,9 @@ pub async fn load(chain: eth::ChainId, path: &Path) -> infra::Config {
                 } else {
                     solver::Liquidity::Fetch
                 },
-                account,
+                account: account(config.account).await,
+                submission_accounts: join_all(config.submission_accounts.into_iter().map(account))
+                    .await,
                 timeouts: solver::Timeouts {
                     http_delay: chrono::Duration::from_std(config.timeouts.http_time_buffer)
                         .unwrap(),
@@ -90,6 +77,10 @@ pub async fn load(chain: eth::ChainId, path: &Path) -> infra::Config {
                     true => SolutionMerging::Allowed,
                     false => SolutionMerging::Forbidden,
                 },
+                merge_solutions_across_solvers: match config.merge_solutions_across_solvers {
+                    true => SolutionMerging::Allowed,
+                    false => SolutionMerging::Forbidden,
+                },
                 s3: config.s3.map(Into::into),
                 solver_native_token: config.manage_native_token.to_domain(),
                 quote_tx_origin: config.quote_tx_origin.map(eth::Address),
@@ -264,18 +255,27 @@ pub async fn load(chain: eth::ChainId, path: &Path) -> infra::Config {
                 target_confirm_time: config.submission.target_confirm_time,
                 max_confirm_time: config.submission.max_confirm_time,
                 retry_interval: config.submission.retry_interval,
+                gas_price_bump: match config.submission.gas_price_bump {
+                    file::GasPriceBump::None => mempools::GasPriceBump::None,
+                    file::GasPriceBump::Linear { step } => mempools::GasPriceBump::Linear { step },
+                    file::GasPriceBump::Exponential { factor } => {
+                        mempools::GasPriceBump::Exponential { factor }
+                    }
+                },
+                max_cost_share_of_score: config.submission.max_cost_share_of_score,
                 kind: match mempool {
                     file::Mempool::Public => {
                         // If there is no private mempool, revert protection is
                         // disabled, otherwise driver would not even try to settle revertable
                         // settlements
                         mempool::Kind::Public(
-                            if config
-                                .submission
-                                .mempools
-                                .iter()
-                                .any(|pool| matches!(pool, file::Mempool::MevBlocker { .. }))
-                            {
+                            if config.submission.mempools.iter().any(|pool| {
+                                matches!(
+                                    pool,
+                                    file::Mempool::MevBlocker { .. }
+                                        | file::Mempool::BuilderRelay { .. }
+                                )
+                            }) {
                                 mempool::RevertProtection::Enabled
                             } else {
                                 mempool::RevertProtection::Disabled
@@ -293,11 +293,16 @@ pub async fn load(chain: eth::ChainId, path: &Path) -> infra::Config {
                         additional_tip_percentage: *additional_tip_percentage,
                         use_soft_cancellations: *use_soft_cancellations,
                     },
+                    file::Mempool::BuilderRelay { urls, signer } => mempool::Kind::BuilderRelay {
+                        urls: urls.to_owned(),
+                        signer: secp256k1::SecretKey::from_slice(&signer.0)
+                            .expect("invalid builder relay signer key"),
+                    },
                 },
             })
             .collect(),
-        simulator: match (config.tenderly, config.enso) {
-            (Some(config), None) => {
+        simulator: match (config.tenderly, config.enso, config.evm) {
+            (Some(config), None, None) => {
                 Some(simulator::Config::Tenderly(simulator::tenderly::Config {
                     url: config.url,
                     api_key: config.api_key,
@@ -307,12 +312,13 @@ pub async fn load(chain: eth::ChainId, path: &Path) -> infra::Config {
                     save_if_fails: config.save_if_fails,
                 }))
             }
-            (None, Some(config)) => Some(simulator::Config::Enso(simulator::enso::Config {
+            (None, Some(config), None) => Some(simulator::Config::Enso(simulator::enso::Config {
                 url: config.url,
                 network_block_interval: config.network_block_interval,
             })),
-            (None, None) => None,
-            (Some(_), Some(_)) => panic!("Cannot configure both Tenderly and Enso"),
+            (None, None, Some(_)) => Some(simulator::Config::Evm),
+            (None, None, None) => None,
+            _ => panic!("Cannot configure more than one of Tenderly, Enso and EVM simulation"),
         },
         contracts: blockchain::contracts::Addresses {
             settlement: config.contracts.gp_v2_settlement.map(Into::into),
@@ -324,3 +330,20 @@ pub async fn load(chain: eth::ChainId, path: &Path) -> infra::Config {
         gas_estimator: config.gas_estimator,
     }
 }
+
+async fn account(account: file::Account) -> ethcontract::Account {
+    match account {
+        file::Account::PrivateKey(private_key) => ethcontract::Account::Offline(
+            ethcontract::PrivateKey::from_raw(private_key.0).unwrap(),
+            None,
+        ),
+        file::Account::Kms(key_id) => {
+            let config = ethcontract::aws_config::load_from_env().await;
+            let account = ethcontract::transaction::kms::Account::new((&config).into(), &key_id.0)
+                .await
+                .unwrap_or_else(|_| panic!("Unable to load KMS account {:?}", key_id));
+            ethcontract::Account::Kms(account, None)
+        }
+        file::Account::Address(address) => ethcontract::Account::Local(address, None),
+    }
+}
@@ -49,6 +49,9 @@ struct Config {
     /// Use Enso for transaction simulation.
     enso: Option<EnsoConfig>,
 
+    /// Use an EVM embedded in the driver for transaction simulation.
+    evm: Option<EvmConfig>,
+
     #[serde(rename = "solver")]
     solvers: Vec<SolverConfig>,
 
@@ -90,12 +93,35 @@ struct SubmissionConfig {
     #[serde(with = "humantime_serde", default = "default_max_confirm_time")]
     max_confirm_time: Duration,
 
+    /// How the gas price of a pending settlement increases with every block
+    /// it doesn't get included in. By default the gas price is never
+    /// increased.
+    #[serde(default)]
+    gas_price_bump: GasPriceBump,
+
+    /// The share of a settlement's score that may at most be spent on gas
+    /// when increasing its gas price. This stops overpaying for settlements
+    /// which are no longer profitable. Unlimited if not set.
+    max_cost_share_of_score: Option<f64>,
+
     /// The mempools to submit settlement transactions to. Can be the public
-    /// mempool of a node or the private MEVBlocker mempool.
+    /// mempool of a node, the private MEVBlocker mempool or block builder
+    /// relays.
     #[serde(rename = "mempool", default)]
     mempools: Vec<Mempool>,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(tag = "curve", rename_all = "kebab-case", deny_unknown_fields)]
+enum GasPriceBump {
+    #[default]
+    None,
+    /// Increase the gas price by `step` times the initial gas price per block.
+    Linear { step: f64 },
+    /// Multiply the gas price by `factor` per block.
+    Exponential { factor: f64 },
+}
+
 #[serde_as]
 #[derive(Debug, Deserialize)]
 #[serde(tag = "mempool")]
@@ -124,6 +150,14 @@ enum Mempool {
         #[serde(default = "default_soft_cancellations_flag")]
         use_soft_cancellations: bool,
     },
+    #[serde(rename_all = "kebab-case")]
+    BuilderRelay {
+        /// The relays to send bundles to via `eth_sendBundle`.
+        urls: Vec<Url>,
+        /// Private key used to sign the `X-Flashbots-Signature` header which
+        /// authenticates bundles with the relays.
+        signer: eth::H256,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -237,6 +271,14 @@ struct SolverConfig {
     /// The account which should be used to sign settlements for this solver.
     account: Account,
 
+    /// Further accounts which can sign settlements for this solver. Each
+    /// settlement is submitted from an idle account with sufficient Ether so
+    /// a pending settlement doesn't hold up settling the next auction. All
+    /// accounts need to be allow-listed as solvers, which is verified on
+    /// startup.
+    #[serde(default)]
+    submission_accounts: Vec<Account>,
+
     /// Timeout configuration for the solver.
     #[serde(default, flatten)]
     timeouts: Timeouts,
@@ -257,6 +299,12 @@ struct SolverConfig {
     #[serde(default)]
     merge_solutions: bool,
 
+    /// If enabled driver tries to merge solutions of this solver with the
+    /// solutions proposed by other solvers that opted in for the same auction.
+    /// The merged solution is submitted by the solver it was merged for.
+    #[serde(default)]
+    merge_solutions_across_solvers: bool,
+
     /// S3 configuration for storing the auctions in the form they are sent to
     /// the solver engine
     #[serde(default)]
@@ -381,6 +429,12 @@ struct EnsoConfig {
     network_block_interval: Option<Duration>,
 }
 
+/// The embedded EVM fetches all state from the node so it needs no further
+/// configuration.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct EvmConfig {}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct LiquidityConfig {
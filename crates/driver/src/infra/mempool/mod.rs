@@ -4,9 +4,13 @@ use {
         domain::{competition, eth, mempools},
         infra,
     },
-    ethcontract::dyns::DynWeb3,
+    anyhow::anyhow,
+    ethcontract::{dyns::DynWeb3, transaction::Transaction},
+    futures::future::join_all,
 };
 
+mod relay;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub min_priority_fee: eth::U256,
@@ -14,6 +18,11 @@ pub struct Config {
     pub target_confirm_time: std::time::Duration,
     pub max_confirm_time: std::time::Duration,
     pub retry_interval: std::time::Duration,
+    /// How the gas price of pending settlements increases per block.
+    pub gas_price_bump: mempools::GasPriceBump,
+    /// The share of a settlement's score that may at most be spent on gas
+    /// when increasing its gas price.
+    pub max_cost_share_of_score: Option<f64>,
     pub kind: Kind,
 }
 
@@ -34,6 +43,15 @@ pub enum Kind {
         additional_tip_percentage: f64,
         use_soft_cancellations: bool,
     },
+    /// Block builder relays which accept private bundles via
+    /// `eth_sendBundle`. Bundles are only valid for a single block so they get
+    /// resubmitted for every block until the settlement gets included.
+    BuilderRelay {
+        urls: Vec<reqwest::Url>,
+        /// Key used to authenticate bundles with the relays. This is not the
+        /// key signing the settlement transaction.
+        signer: secp256k1::SecretKey,
+    },
 }
 
 impl Kind {
@@ -42,6 +60,7 @@ impl Kind {
         match self {
             Kind::Public(_) => "PublicMempool",
             Kind::MEVBlocker { .. } => "MEVBlocker",
+            Kind::BuilderRelay { .. } => "BuilderRelay",
         }
     }
 }
@@ -60,6 +79,7 @@ pub enum RevertProtection {
 #[derive(Debug, Clone)]
 pub struct Mempool {
     transport: DynWeb3,
+    client: reqwest::Client,
     config: Config,
 }
 
@@ -72,23 +92,124 @@ impl std::fmt::Display for Mempool {
 impl Mempool {
     pub fn new(config: Config, transport: DynWeb3) -> Self {
         let transport = match &config.kind {
-            Kind::Public(_) => transport,
+            Kind::Public(_) | Kind::BuilderRelay { .. } => transport,
             // Flashbots Protect RPC fallback doesn't support buffered transport
             Kind::MEVBlocker { url, .. } => unbuffered_web3_client(url),
         };
-        Self { config, transport }
+        Self {
+            config,
+            transport,
+            client: reqwest::Client::new(),
+        }
     }
 
-    /// Submits a transaction to the mempool. Returns optimistically as soon as
-    /// the transaction is pending.
+    /// Submits a transaction signed by the solver's account matching `tx.from`
+    /// to the mempool. Returns optimistically as soon as the transaction is
+    /// pending.
+    ///
+    /// Bundles are only valid for the next block so they need to be submitted
+    /// again for every block, see [`Self::submits_bundles`].
     pub async fn submit(
         &self,
         tx: eth::Tx,
         gas: competition::solution::settlement::Gas,
         solver: &infra::Solver,
+        nonce: eth::U256,
+    ) -> Result<eth::TxId, mempools::Error> {
+        let account = solver.account(tx.from).ok_or_else(|| {
+            mempools::Error::Other(anyhow!("{:?} is not an account of the solver", tx.from))
+        })?;
+        match &self.config.kind {
+            Kind::BuilderRelay { urls, signer } => {
+                self.submit_bundle(tx, gas, account, nonce, urls, signer)
+                    .await
+            }
+            Kind::Public(_) | Kind::MEVBlocker { .. } => {
+                self.submit_tx(tx, gas, account, nonce).await
+            }
+        }
+    }
+
+    async fn submit_tx(
+        &self,
+        tx: eth::Tx,
+        gas: competition::solution::settlement::Gas,
+        account: &ethcontract::Account,
+        nonce: eth::U256,
     ) -> Result<eth::TxId, mempools::Error> {
+        self.transaction(tx, gas, account, nonce)
+            .resolve(ethcontract::transaction::ResolveCondition::Pending)
+            .send()
+            .await
+            .map(|result| eth::TxId(result.hash()))
+            .map_err(|err| mempools::Error::Other(anyhow::Error::from(err)))
+    }
+
+    /// Signs the transaction and sends it as a bundle targeting the next block
+    /// to all relays. Signing is deterministic and the nonce is fixed so
+    /// resubmissions result in the same transaction.
+    async fn submit_bundle(
+        &self,
+        tx: eth::Tx,
+        gas: competition::solution::settlement::Gas,
+        account: &ethcontract::Account,
+        nonce: eth::U256,
+        urls: &[reqwest::Url],
+        signer: &secp256k1::SecretKey,
+    ) -> Result<eth::TxId, mempools::Error> {
+        let (bytes, hash) = match self.transaction(tx, gas, account, nonce).build().await {
+            Ok(Transaction::Raw { bytes, hash }) => (bytes, hash),
+            Ok(Transaction::Request(_)) => {
+                return Err(mempools::Error::Other(anyhow!(
+                    "builder relays require a solver account that signs locally"
+                )))
+            }
+            Err(err) => return Err(mempools::Error::Other(err.into())),
+        };
+        let block = self
+            .transport
+            .eth()
+            .block_number()
+            .await
+            .map_err(|err| mempools::Error::Other(err.into()))?
+            .as_u64()
+            + 1;
+
+        let results = join_all(urls.iter().map(|url| {
+            relay::send_bundle(
+                &self.client,
+                url,
+                signer,
+                std::slice::from_ref(&bytes),
+                block,
+            )
+        }))
+        .await;
+        for (url, result) in urls.iter().zip(&results) {
+            if let Err(err) = result {
+                tracing::debug!(%url, ?err, "relay did not accept bundle");
+            }
+        }
+        // The bundle only needs to reach one of the builders to get included.
+        if results.iter().any(Result::is_ok) {
+            Ok(eth::TxId(hash))
+        } else {
+            Err(mempools::Error::Other(anyhow!(
+                "no relay accepted the bundle for block {block}"
+            )))
+        }
+    }
+
+    fn transaction(
+        &self,
+        tx: eth::Tx,
+        gas: competition::solution::settlement::Gas,
+        account: &ethcontract::Account,
+        nonce: eth::U256,
+    ) -> ethcontract::transaction::TransactionBuilder<ethcontract::dyns::DynTransport> {
         ethcontract::transaction::TransactionBuilder::new(self.transport.clone())
-            .from(solver.account().clone())
+            .from(account.clone())
+            .nonce(nonce)
             .to(tx.to.into())
             .gas_price(ethcontract::GasPrice::Eip1559 {
                 max_fee_per_gas: gas.price.max().into(),
@@ -98,11 +219,6 @@ impl Mempool {
             .value(tx.value.0)
             .gas(gas.limit.0)
             .access_list(web3::types::AccessList::from(tx.access_list))
-            .resolve(ethcontract::transaction::ResolveCondition::Pending)
-            .send()
-            .await
-            .map(|result| eth::TxId(result.hash()))
-            .map_err(|err| mempools::Error::Other(anyhow::Error::from(err)))
     }
 
     pub fn config(&self) -> &Config {
@@ -112,7 +228,14 @@ impl Mempool {
     pub fn may_revert(&self) -> bool {
         match &self.config.kind {
             Kind::Public(_) => true,
-            Kind::MEVBlocker { .. } => false,
+            Kind::MEVBlocker { .. } | Kind::BuilderRelay { .. } => false,
         }
     }
+
+    /// Whether transactions get submitted as bundles which are only valid for
+    /// the next block. Bundles that don't get included simply expire so they
+    /// never need to be cancelled.
+    pub fn submits_bundles(&self) -> bool {
+        matches!(self.config.kind, Kind::BuilderRelay { .. })
+    }
 }
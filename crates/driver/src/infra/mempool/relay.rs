@@ -0,0 +1,145 @@
+//! Submission of transactions as bundles to block builder relays which follow
+//! the Flashbots `eth_sendBundle` API.
+
+use {
+    anyhow::{anyhow, Context, Result},
+    serde_json::json,
+    web3::signing::{self, Key, SecretKeyRef},
+};
+
+/// Sends a bundle consisting of the given raw transactions that is only valid
+/// for inclusion in `block`.
+pub async fn send_bundle(
+    client: &reqwest::Client,
+    url: &reqwest::Url,
+    signer: &secp256k1::SecretKey,
+    txs: &[web3::types::Bytes],
+    block: u64,
+) -> Result<()> {
+    let body = serde_json::to_vec(&json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_sendBundle",
+        "params": [{
+            "txs": txs,
+            "blockNumber": format!("{block:#x}"),
+        }],
+    }))?;
+    let response: serde_json::Value = client
+        .post(url.clone())
+        .header("Content-Type", "application/json")
+        .header("X-Flashbots-Signature", signature(signer, &body))
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .context("invalid relay response")?;
+    match response.get("error") {
+        Some(err) => Err(anyhow!("relay rejected bundle: {err}")),
+        None => Ok(()),
+    }
+}
+
+/// Relays authenticate bundles with the `<address>:<signature>` header where
+/// the signature is an `eth_sign` signature of the hex encoded keccak hash of
+/// the request body.
+fn signature(signer: &secp256k1::SecretKey, body: &[u8]) -> String {
+    let key = SecretKeyRef::new(signer);
+    let message = format!("0x{}", hex::encode(signing::keccak256(body)));
+    let hash = signing::hash_message(message.as_bytes());
+    // Unwrap because the only error is for invalid messages which we don't create.
+    let signature = key.sign(hash.as_bytes(), None).unwrap();
+    let mut bytes = [0; 65];
+    bytes[..32].copy_from_slice(signature.r.as_bytes());
+    bytes[32..64].copy_from_slice(signature.s.as_bytes());
+    bytes[64] = signature.v as u8;
+    format!("{:?}:0x{}", key.address(), hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::sync::{Arc, Mutex},
+    };
+
+    /// Starts a relay that records all requests and responds with the given
+    /// JSON-RPC response.
+    fn stub_relay(
+        response: serde_json::Value,
+    ) -> (reqwest::Url, Arc<Mutex<Vec<(String, serde_json::Value)>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post({
+                let requests = requests.clone();
+                move |headers: axum::http::HeaderMap,
+                      axum::extract::Json(request): axum::extract::Json<serde_json::Value>| {
+                    let signature = headers["X-Flashbots-Signature"].to_str().unwrap();
+                    requests
+                        .lock()
+                        .unwrap()
+                        .push((signature.to_owned(), request));
+                    let response = response.clone();
+                    async move { axum::response::Json(response) }
+                }
+            }),
+        );
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let url = format!("http://{}", server.local_addr()).parse().unwrap();
+        tokio::spawn(async move { server.await.unwrap() });
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn sends_signed_bundles() {
+        let signer = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+        let txs = [web3::types::Bytes(vec![0xde, 0xad])];
+
+        let (url, requests) =
+            stub_relay(json!({"jsonrpc": "2.0", "id": 1, "result": {"bundleHash": "0x01"}}));
+        send_bundle(&reqwest::Client::new(), &url, &signer, &txs, 16)
+            .await
+            .unwrap();
+
+        let (signature, request) = requests.lock().unwrap().pop().unwrap();
+        assert_eq!(
+            request,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "eth_sendBundle",
+                "params": [{"txs": ["0xdead"], "blockNumber": "0x10"}],
+            })
+        );
+        let (address, signature) = signature.split_once(':').unwrap();
+        assert_eq!(
+            address,
+            format!("{:?}", SecretKeyRef::new(&signer).address())
+        );
+        let body = serde_json::to_vec(&request).unwrap();
+        let message = format!("0x{}", hex::encode(signing::keccak256(&body)));
+        let signature = hex::decode(&signature[2..]).unwrap();
+        let recovered = signing::recover(
+            signing::hash_message(message.as_bytes()).as_bytes(),
+            &signature[..64],
+            i32::from(signature[64]) - 27,
+        )
+        .unwrap();
+        assert_eq!(recovered, SecretKeyRef::new(&signer).address());
+
+        let (url, _) = stub_relay(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": {"code": -32000, "message": "bundle rejected"},
+        }));
+        assert!(
+            send_bundle(&reqwest::Client::new(), &url, &signer, &txs, 16)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -58,8 +58,15 @@ pub fn encoding_failed(
         solution::Error::NonBufferableTokensUsed(tokens) => {
             notification::Kind::NonBufferableTokensUsed(tokens.clone())
         }
-        solution::Error::SolverAccountInsufficientBalance(required) => {
-            notification::Kind::SolverAccountInsufficientBalance(*required)
+        solution::Error::SolverAccountInsufficientBalance(accounts, required) => {
+            for account in accounts {
+                solver.notify(
+                    auction_id,
+                    Some(solution_id.clone()),
+                    notification::Kind::SolverAccountInsufficientBalance(*account, *required),
+                );
+            }
+            return;
         }
         solution::Error::Blockchain(_) => return,
         solution::Error::Boundary(_) => return,
@@ -103,20 +110,38 @@ pub fn executed(
     solution_id: &solution::Id,
     res: &Result<eth::TxId, Error>,
 ) {
-    let kind = match res {
-        Ok(hash) => notification::Settlement::Success(hash.clone()),
-        Err(Error::Revert(hash)) => notification::Settlement::Revert(hash.clone()),
-        Err(Error::SimulationRevert) => notification::Settlement::SimulationRevert,
-        Err(Error::Other(_) | Error::Expired | Error::Disabled) => notification::Settlement::Fail,
-    };
+    solver.notify(
+        Some(auction_id),
+        Some(solution_id.clone()),
+        notification::Kind::Settled(settlement(res)),
+    );
+}
 
+/// Notifies a solver whose solution got merged into the settlement of another
+/// solver about the outcome of that settlement.
+pub fn contributed(
+    solver: &Solver,
+    auction_id: auction::Id,
+    solution_id: &solution::Id,
+    settled_by: &super::solver::Name,
+    res: &Result<eth::TxId, Error>,
+) {
     solver.notify(
         Some(auction_id),
         Some(solution_id.clone()),
-        notification::Kind::Settled(kind),
+        notification::Kind::Contributed(settled_by.clone(), settlement(res)),
     );
 }
 
+fn settlement(res: &Result<eth::TxId, Error>) -> notification::Settlement {
+    match res {
+        Ok(hash) => notification::Settlement::Success(hash.clone()),
+        Err(Error::Revert(hash)) => notification::Settlement::Revert(hash.clone()),
+        Err(Error::SimulationRevert) => notification::Settlement::SimulationRevert,
+        Err(Error::Other(_) | Error::Expired | Error::Disabled) => notification::Settlement::Fail,
+    }
+}
+
 pub fn duplicated_solution_id(
     solver: &Solver,
     auction_id: Option<auction::Id>,
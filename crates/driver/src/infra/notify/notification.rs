@@ -1,7 +1,10 @@
 use {
-    crate::domain::{
-        competition::{auction, solution},
-        eth::{self, Ether, TokenAddress},
+    crate::{
+        domain::{
+            competition::{auction, solution},
+            eth::{self, Ether, TokenAddress},
+        },
+        infra::solver,
     },
     std::collections::BTreeSet,
 };
@@ -36,10 +39,14 @@ pub enum Kind {
     /// Solution aimed to internalize tokens that are not considered safe to
     /// keep in the settlement contract.
     NonBufferableTokensUsed(TokensUsed),
-    /// Solver don't have enough balance to submit the solution onchain.
-    SolverAccountInsufficientBalance(RequiredEther),
+    /// A solver account doesn't have enough balance to submit the solution
+    /// onchain.
+    SolverAccountInsufficientBalance(eth::Address, RequiredEther),
     /// Result of winning solver trying to settle the transaction onchain.
     Settled(Settlement),
+    /// The solution was merged into the winning solution of another solver
+    /// which tried to settle it onchain.
+    Contributed(solver::Name, Settlement),
     /// Some aspect of the driver logic failed preventing the solution from
     /// participating in the auction.
     DriverError(String),
@@ -125,6 +125,15 @@ pub fn encoding_failed(solver: &solver::Name, id: &solution::Id, err: &solution:
         .inc();
 }
 
+/// Observe that a solver account can't pay for submitting a settlement.
+pub fn solver_account_insufficient_balance(
+    solver: &solver::Name,
+    account: eth::Address,
+    required: eth::Ether,
+) {
+    tracing::warn!(%solver, ?account, ?required, "insufficient solver account balance");
+}
+
 /// Observe that two solutions were merged.
 pub fn merged(first: &Solution, other: &Solution, result: &Solution) {
     tracing::debug!(?first, ?other, ?result, "merged solutions");
@@ -0,0 +1,279 @@
+use {
+    crate::{domain::eth, infra::Ethereum},
+    ethrpc::current_block::BlockInfo,
+    revm::primitives::{
+        Address,
+        Bytes,
+        EVMError,
+        ExecutionResult,
+        ResultAndState,
+        TransactTo,
+        U256,
+    },
+    thiserror::Error,
+};
+
+mod state;
+
+/// Simulates transactions in an EVM embedded in the driver. The chain state is
+/// fetched from the node on demand and cached for the current block which
+/// avoids round trips to external simulation services.
+#[derive(Debug, Clone)]
+pub(super) struct Evm {
+    eth: Ethereum,
+    cache: state::Cache,
+}
+
+/// The result of a successful simulation.
+pub(super) struct Simulation {
+    pub gas: eth::Gas,
+    /// The accounts and storage slots the transaction accessed.
+    pub access_list: eth::AccessList,
+}
+
+impl Evm {
+    pub(super) fn new(eth: Ethereum) -> Self {
+        Self {
+            eth,
+            cache: Default::default(),
+        }
+    }
+
+    /// Executes the transaction on top of the current block.
+    pub(super) async fn simulate(&self, tx: &eth::Tx) -> Result<Simulation, Error> {
+        let block = *self.eth.current_block().borrow();
+        let gas_price = self
+            .eth
+            .simulation_gas_price()
+            .await
+            .unwrap_or(block.gas_price)
+            .max(block.gas_price);
+        let state = self.cache.at(self.eth.web3().clone(), block.number);
+        let chain_id = self.eth.network().0;
+        let (caller, to) = (address(tx.from), address(tx.to));
+        let tx = tx.clone();
+
+        let result =
+            tokio::task::spawn_blocking(move || transact(state, chain_id, &block, gas_price, tx))
+                .await?
+                .map_err(|err| match err {
+                    EVMError::Database(err) => Error::Node(err),
+                    err => Error::Invalid(format!("{err:?}")),
+                })?;
+        simulation(result, caller, to)
+    }
+}
+
+/// Executes the transaction on top of the given block.
+fn transact<DB: revm::Database>(
+    db: DB,
+    chain_id: u64,
+    block: &BlockInfo,
+    gas_price: eth::U256,
+    tx: eth::Tx,
+) -> Result<ResultAndState, EVMError<DB::Error>> {
+    let (caller, to) = (address(tx.from), address(tx.to));
+    let mut evm = revm::Evm::builder()
+        .with_db(db)
+        .modify_cfg_env(|cfg| {
+            cfg.chain_id = chain_id;
+            // Like `eth_estimateGas` simulations shouldn't fail just because
+            // the sender can't pay for the whole gas limit.
+            cfg.disable_balance_check = true;
+        })
+        .modify_block_env(|env| {
+            env.number = U256::from(block.number);
+            env.timestamp = U256::from(block.timestamp);
+            env.gas_limit = u256(block.gas_limit);
+            env.basefee = u256(block.gas_price);
+        })
+        .modify_tx_env(|env| {
+            env.caller = caller;
+            env.transact_to = TransactTo::Call(to);
+            env.value = u256(tx.value.0);
+            env.data = Bytes::from(tx.input.0);
+            env.gas_limit = block.gas_limit.low_u64();
+            env.gas_price = u256(gas_price);
+            env.access_list = web3::types::AccessList::from(tx.access_list)
+                .into_iter()
+                .map(|item| {
+                    (
+                        address(item.address.into()),
+                        item.storage_keys
+                            .into_iter()
+                            .map(|key| U256::from_be_bytes(key.0))
+                            .collect(),
+                    )
+                })
+                .collect();
+        })
+        .build();
+    evm.transact()
+}
+
+/// Extracts the used gas and the access list from an executed transaction.
+fn simulation(result: ResultAndState, caller: Address, to: Address) -> Result<Simulation, Error> {
+    let ResultAndState { result, state } = result;
+    let gas = match result {
+        ExecutionResult::Success { gas_used, .. } => gas_used,
+        ExecutionResult::Revert { output, .. } => return Err(Error::Revert(reason(&output))),
+        ExecutionResult::Halt { reason, .. } => return Err(Error::Revert(format!("{reason:?}"))),
+    };
+
+    // Like `eth_createAccessList` the sender, the recipient, the coinbase (left
+    // at the zero address) and the precompiles are left out since they are
+    // always warm.
+    let access_list: web3::types::AccessList = state
+        .into_iter()
+        .filter(|(account, _)| {
+            ![caller, to, Address::ZERO].contains(account) && !is_precompile(account)
+        })
+        .map(|(account, state)| web3::types::AccessListItem {
+            address: primitive_types::H160::from_slice(account.as_slice()),
+            storage_keys: state
+                .storage
+                .into_keys()
+                .map(|key| primitive_types::H256(key.to_be_bytes()))
+                .collect(),
+        })
+        .collect();
+
+    Ok(Simulation {
+        gas: gas.into(),
+        access_list: access_list.into(),
+    })
+}
+
+fn address(address: eth::Address) -> Address {
+    Address::from(address.0 .0)
+}
+
+/// The precompiled contracts live at the addresses 0x01 to 0x0a.
+fn is_precompile(address: &Address) -> bool {
+    let (prefix, last) = address.as_slice().split_at(19);
+    prefix.iter().all(|byte| *byte == 0) && (1..=10).contains(&last[0])
+}
+
+fn u256(value: eth::U256) -> U256 {
+    U256::from_limbs(value.0)
+}
+
+/// Decodes the revert reason of `Error(string)` reverts and hex encodes all
+/// other revert data.
+fn reason(output: &[u8]) -> String {
+    const ERROR_SELECTOR: [u8; 4] = hex_literal::hex!("08c379a0");
+    output
+        .strip_prefix(&ERROR_SELECTOR)
+        .and_then(|data| ethabi::decode(&[ethabi::ParamType::String], data).ok())
+        .and_then(|tokens| tokens.into_iter().next()?.into_string())
+        .unwrap_or_else(|| format!("0x{}", hex::encode(output)))
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Fetching state from the node failed.
+    #[error("node error: {0}")]
+    Node(#[from] web3::Error),
+    /// The transaction is invalid, e.g. because the sender can't pay for the
+    /// transferred value.
+    #[error("invalid transaction: {0}")]
+    Invalid(String),
+    #[error("revert: {0}")]
+    Revert(String),
+    /// The blocking task running the simulation panicked or got cancelled.
+    #[error("simulation task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        revm::{
+            db::{CacheDB, EmptyDB},
+            primitives::{AccountInfo, Bytecode},
+        },
+    };
+
+    const CALLER: Address = Address::repeat_byte(1);
+    const SETTLEMENT: Address = Address::repeat_byte(2);
+    const POOL: Address = Address::repeat_byte(3);
+
+    fn deploy(db: &mut CacheDB<EmptyDB>, account: Address, code: Vec<u8>) {
+        let code = Bytecode::new_raw(Bytes::from(code));
+        db.insert_account_info(
+            account,
+            AccountInfo::new(U256::ZERO, 1, code.hash_slow(), code),
+        );
+    }
+
+    fn execute(db: CacheDB<EmptyDB>) -> Result<Simulation, Error> {
+        let tx = eth::Tx {
+            from: primitive_types::H160::from_slice(CALLER.as_slice()).into(),
+            to: primitive_types::H160::from_slice(SETTLEMENT.as_slice()).into(),
+            value: eth::U256::zero().into(),
+            input: Default::default(),
+            access_list: Default::default(),
+        };
+        let block = BlockInfo {
+            number: 1,
+            gas_limit: 30_000_000.into(),
+            ..Default::default()
+        };
+        let result = transact(db, 1, &block, eth::U256::zero(), tx).unwrap();
+        simulation(result, CALLER, SETTLEMENT)
+    }
+
+    #[test]
+    fn reports_gas_and_access_list() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        // STATICCALL(gas, POOL, 0, 0, 0, 0)
+        let settlement = [
+            hex_literal::hex!("6000600060006000").as_slice(),
+            &[0x73],
+            POOL.as_slice(),
+            &hex_literal::hex!("5afa5000"),
+        ]
+        .concat();
+        deploy(&mut db, SETTLEMENT, settlement);
+        // SLOAD(7)
+        deploy(&mut db, POOL, hex_literal::hex!("6007545000").to_vec());
+
+        let simulation = execute(db).unwrap();
+        // Intrinsic gas, 4 PUSH1 and PUSH20, GAS, cold STATICCALL, the call's
+        // PUSH1 and cold SLOAD, and 2 POPs.
+        assert_eq!(
+            simulation.gas,
+            eth::Gas::from(21_000_u64 + 5 * 3 + 2 + 2_600 + 3 + 2_100 + 2 * 2)
+        );
+        assert_eq!(
+            web3::types::AccessList::from(simulation.access_list),
+            vec![web3::types::AccessListItem {
+                address: primitive_types::H160::from_slice(POOL.as_slice()),
+                storage_keys: vec![primitive_types::H256::from_low_u64_be(7)],
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_reverts() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        // REVERT(0, 0)
+        deploy(
+            &mut db,
+            SETTLEMENT,
+            hex_literal::hex!("60006000fd").to_vec(),
+        );
+        assert!(matches!(execute(db), Err(Error::Revert(reason)) if reason == "0x"));
+    }
+
+    #[test]
+    fn decodes_revert_reasons() {
+        let output = ethabi::encode(&[ethabi::Token::String(
+            "GPv2: limit price not respected".into(),
+        )]);
+        let output = [hex_literal::hex!("08c379a0").as_slice(), &output].concat();
+        assert_eq!(reason(&output), "GPv2: limit price not respected");
+        assert_eq!(reason(&[0xde, 0xad]), "0xdead");
+    }
+}
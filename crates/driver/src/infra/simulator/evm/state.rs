@@ -0,0 +1,241 @@
+//! Chain state for the embedded EVM. State is lazily fetched from the node and
+//! cached until the next block.
+
+use {
+    ethcontract::dyns::DynWeb3,
+    primitive_types::{H160, H256},
+    revm::primitives::{AccountInfo, Address, Bytecode, Bytes, B256, KECCAK_EMPTY, U256},
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
+    web3::types::{BlockId, BlockNumber},
+};
+
+/// Node state fetched for a single block. Clones share the same cache.
+#[derive(Debug, Clone, Default)]
+pub struct Cache(Arc<Mutex<Inner>>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    block: u64,
+    accounts: HashMap<Address, AccountInfo>,
+    code: HashMap<B256, Bytecode>,
+    storage: HashMap<(Address, U256), U256>,
+    block_hashes: HashMap<u64, B256>,
+}
+
+impl Cache {
+    /// Returns the state of the given block. Previously cached state is
+    /// discarded once a newer block gets requested.
+    pub fn at(&self, web3: DynWeb3, block: u64) -> State {
+        let mut inner = self.0.lock().unwrap();
+        if inner.block < block {
+            *inner = Inner {
+                block,
+                ..Default::default()
+            };
+        }
+        State {
+            cache: self.clone(),
+            web3,
+            block,
+            runtime: tokio::runtime::Handle::current(),
+        }
+    }
+
+    /// Looks up a value in the cache if it belongs to the given block.
+    fn get<T>(&self, block: u64, lookup: impl FnOnce(&Inner) -> Option<T>) -> Option<T> {
+        let inner = self.0.lock().unwrap();
+        (inner.block == block).then(|| lookup(&inner)).flatten()
+    }
+
+    /// Stores a value in the cache unless a newer block is cached by now.
+    fn insert(&self, block: u64, insert: impl FnOnce(&mut Inner)) {
+        let mut inner = self.0.lock().unwrap();
+        if inner.block == block {
+            insert(&mut inner);
+        }
+    }
+}
+
+/// The state of a block, fetched from the node on demand.
+///
+/// The EVM accesses state synchronously so node requests block the current
+/// thread. Simulations must therefore run on a blocking thread.
+pub struct State {
+    cache: Cache,
+    web3: DynWeb3,
+    block: u64,
+    runtime: tokio::runtime::Handle,
+}
+
+impl State {
+    fn block_number(&self) -> Option<BlockNumber> {
+        Some(BlockNumber::Number(self.block.into()))
+    }
+
+    fn fetch_account(&self, address: Address) -> Result<AccountInfo, web3::Error> {
+        let h160 = H160::from_slice(address.as_slice());
+        let eth = self.web3.eth();
+        let (balance, nonce, code) = self.runtime.block_on(async {
+            futures::try_join!(
+                eth.balance(h160, self.block_number()),
+                eth.transaction_count(h160, self.block_number()),
+                eth.code(h160, self.block_number()),
+            )
+        })?;
+        let code_hash = if code.0.is_empty() {
+            KECCAK_EMPTY
+        } else {
+            revm::primitives::keccak256(&code.0)
+        };
+        let code = Bytecode::new_raw(Bytes::from(code.0));
+        self.cache.insert(self.block, |inner| {
+            inner.code.insert(code_hash, code.clone());
+        });
+        Ok(AccountInfo::new(
+            U256::from_limbs(balance.0),
+            nonce.as_u64(),
+            code_hash,
+            code,
+        ))
+    }
+}
+
+impl revm::Database for State {
+    type Error = web3::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(account) = self
+            .cache
+            .get(self.block, |inner| inner.accounts.get(&address).cloned())
+        {
+            return Ok(Some(account));
+        }
+        let account = self.fetch_account(address)?;
+        self.cache.insert(self.block, |inner| {
+            inner.accounts.insert(address, account.clone());
+        });
+        Ok(Some(account))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // Code is always fetched together with its account so it is known by
+        // the time the EVM looks it up by its hash.
+        Ok(self
+            .cache
+            .get(self.block, |inner| inner.code.get(&code_hash).cloned())
+            .unwrap_or_default())
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if let Some(value) = self.cache.get(self.block, |inner| {
+            inner.storage.get(&(address, index)).copied()
+        }) {
+            return Ok(value);
+        }
+        let value = self.runtime.block_on(self.web3.eth().storage(
+            H160::from_slice(address.as_slice()),
+            primitive_types::U256(*index.as_limbs()),
+            self.block_number(),
+        ))?;
+        let value = U256::from_be_bytes(value.0);
+        self.cache.insert(self.block, |inner| {
+            inner.storage.insert((address, index), value);
+        });
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        let number = number.saturating_to::<u64>();
+        if let Some(hash) = self
+            .cache
+            .get(self.block, |inner| inner.block_hashes.get(&number).copied())
+        {
+            return Ok(hash);
+        }
+        let block = self.runtime.block_on(
+            self.web3
+                .eth()
+                .block(BlockId::Number(BlockNumber::Number(number.into()))),
+        )?;
+        let hash = B256::from(
+            block
+                .and_then(|block| block.hash)
+                .unwrap_or_else(H256::zero)
+                .0,
+        );
+        self.cache.insert(self.block, |inner| {
+            inner.block_hashes.insert(number, hash);
+        });
+        Ok(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ethcontract::transport::DynTransport,
+        ethrpc::mock::MockTransport,
+        revm::Database,
+        serde_json::{json, Value},
+    };
+
+    const ACCOUNT: Address = Address::repeat_byte(1);
+
+    /// Expects exactly one request of the method for the given block.
+    fn expect(transport: &MockTransport, method: &'static str, block: u64, response: Value) {
+        transport
+            .mock()
+            .expect_execute()
+            .withf(move |m, params| {
+                m == method && params.last() == Some(&json!(format!("{block:#x}")))
+            })
+            .times(1)
+            .returning(move |_, _| Ok(response.clone()));
+    }
+
+    #[tokio::test]
+    async fn caches_state_per_block() {
+        let transport = MockTransport::new();
+        let web3 = DynWeb3::new(DynTransport::new(transport.clone()));
+        let slot = format!("{:#066x}", 5);
+        for block in [1, 2] {
+            expect(&transport, "eth_getBalance", block, json!("0x64"));
+            expect(&transport, "eth_getTransactionCount", block, json!("0x2"));
+            expect(&transport, "eth_getCode", block, json!("0x6000"));
+            expect(&transport, "eth_getStorageAt", block, json!(slot));
+        }
+
+        let cache = Cache::default();
+        tokio::task::spawn_blocking(move || {
+            let mut state = cache.at(web3.clone(), 1);
+            for _ in 0..2 {
+                let account = state.basic(ACCOUNT).unwrap().unwrap();
+                assert_eq!(account.balance, U256::from(100));
+                assert_eq!(account.nonce, 2);
+                assert_eq!(
+                    state
+                        .code_by_hash(account.code_hash)
+                        .unwrap()
+                        .original_bytes(),
+                    Bytes::from(vec![0x60, 0x00])
+                );
+                assert_eq!(
+                    state.storage(ACCOUNT, U256::from(1)).unwrap(),
+                    U256::from(5)
+                );
+            }
+
+            // State of the previous block gets discarded once a newer block
+            // is requested.
+            let mut state = cache.at(web3, 2);
+            state.basic(ACCOUNT).unwrap();
+            state.storage(ACCOUNT, U256::from(1)).unwrap();
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -7,6 +7,7 @@ use {
 };
 
 pub mod enso;
+pub mod evm;
 pub mod tenderly;
 
 /// Ethereum transaction simulator.
@@ -25,6 +26,7 @@ pub struct Simulator {
 pub enum Config {
     Tenderly(tenderly::Config),
     Enso(enso::Config),
+    Evm,
 }
 
 impl Simulator {
@@ -66,6 +68,18 @@ impl Simulator {
         }
     }
 
+    /// Simulate transactions in an EVM embedded in the driver. The chain state
+    /// is fetched from the node on demand and cached per block.
+    pub fn evm(eth: Ethereum) -> Self {
+        let eth = eth.with_metric_label("evmSimulator".into());
+        Self {
+            inner: Inner::Evm(evm::Evm::new(eth.clone())),
+            eth,
+            disable_access_lists: false,
+            disable_gas: None,
+        }
+    }
+
     /// Disable access list simulation. Some environments, such as less popular
     /// blockchains, don't support access list simulation.
     pub fn disable_access_lists(&mut self) {
@@ -104,6 +118,13 @@ impl Simulator {
                 .create_access_list(tx.clone())
                 .await
                 .map_err(with(tx.clone(), block))?,
+            Inner::Evm(evm) => {
+                evm.simulate(tx)
+                    .measure("evm_simulate_access_list")
+                    .await
+                    .map_err(with(tx.clone(), block))?
+                    .access_list
+            }
         };
         Ok(tx.access_list.clone().merge(access_list))
     }
@@ -133,6 +154,13 @@ impl Simulator {
                 .measure("enso_simulate_gas")
                 .await
                 .map_err(with(tx.clone(), block))?,
+            Inner::Evm(evm) => {
+                evm.simulate(tx)
+                    .measure("evm_simulate_gas")
+                    .await
+                    .map_err(with(tx.clone(), block))?
+                    .gas
+            }
         })
     }
 }
@@ -142,6 +170,7 @@ enum Inner {
     Tenderly(tenderly::Tenderly),
     Ethereum,
     Enso(enso::Enso),
+    Evm(evm::Evm),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -152,6 +181,8 @@ pub enum SimulatorError {
     Blockchain(#[from] blockchain::Error),
     #[error("enso error: {0:?}")]
     Enso(#[from] enso::Error),
+    #[error("evm error: {0:?}")]
+    Evm(#[from] evm::Error),
     #[error("the simulated gas {0} exceeded the gas limit {1} provided in the solution")]
     GasExceeded(eth::Gas, eth::Gas),
 }
@@ -194,6 +225,8 @@ where
             }
             SimulatorError::Enso(enso::Error::Http(_)) => None,
             SimulatorError::Enso(enso::Error::Revert(_)) => Some(tx),
+            SimulatorError::Evm(evm::Error::Node(_) | evm::Error::Task(_)) => None,
+            SimulatorError::Evm(evm::Error::Invalid(_) | evm::Error::Revert(_)) => Some(tx),
             SimulatorError::GasExceeded(..) => Some(tx),
         };
         match tx {
@@ -42,8 +42,9 @@ impl Notification {
                 notify::Kind::NonBufferableTokensUsed(tokens) => Kind::NonBufferableTokensUsed {
                     tokens: tokens.into_iter().map(|token| token.0 .0).collect(),
                 },
-                notify::Kind::SolverAccountInsufficientBalance(required) => {
+                notify::Kind::SolverAccountInsufficientBalance(account, required) => {
                     Kind::SolverAccountInsufficientBalance {
+                        account: account.0,
                         required: required.0,
                     }
                 }
@@ -59,12 +60,31 @@ impl Notification {
                     notify::Settlement::SimulationRevert => Kind::Cancelled,
                     notify::Settlement::Fail => Kind::Fail,
                 },
+                notify::Kind::Contributed(solver, settlement) => Kind::Contributed {
+                    solver: solver.0,
+                    settlement: settlement.into(),
+                },
                 notify::Kind::PostprocessingTimedOut => Kind::PostprocessingTimedOut,
             },
         }
     }
 }
 
+impl From<notify::Settlement> for Settlement {
+    fn from(value: notify::Settlement) -> Self {
+        match value {
+            notify::Settlement::Success(hash) => Settlement::Success {
+                transaction: hash.0,
+            },
+            notify::Settlement::Revert(hash) => Settlement::Revert {
+                transaction: hash.0,
+            },
+            notify::Settlement::SimulationRevert => Settlement::Cancelled,
+            notify::Settlement::Fail => Settlement::Fail,
+        }
+    }
+}
+
 impl From<notify::ScoreKind> for Kind {
     fn from(value: notify::ScoreKind) -> Self {
         match value {
@@ -127,6 +147,7 @@ pub enum Kind {
         tokens: BTreeSet<eth::H160>,
     },
     SolverAccountInsufficientBalance {
+        account: eth::H160,
         #[serde_as(as = "serialize::U256")]
         required: eth::U256,
     },
@@ -142,6 +163,20 @@ pub enum Kind {
     Cancelled,
     Fail,
     PostprocessingTimedOut,
+    Contributed {
+        solver: String,
+        settlement: Settlement,
+    },
+}
+
+/// The outcome of a settlement a solution was merged into.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Settlement {
+    Success { transaction: eth::H256 },
+    Revert { transaction: eth::H256 },
+    Cancelled,
+    Fail,
 }
 
 type BlockNo = u64;
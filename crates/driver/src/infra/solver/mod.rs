@@ -107,6 +107,10 @@ pub struct Config {
     pub liquidity: Liquidity,
     /// The private key of this solver, used for settlement submission.
     pub account: ethcontract::Account,
+    /// Further accounts settlements can be submitted from. This allows
+    /// settling consecutive auctions concurrently while a settlement from
+    /// another account is still pending.
+    pub submission_accounts: Vec<ethcontract::Account>,
     /// How much time to spend for each step of the solving and competition.
     pub timeouts: Timeouts,
     /// HTTP headers that should be added to every request.
@@ -117,6 +121,9 @@ pub struct Config {
     /// TODO: Remove once all solvers are moved to use limit orders for quoting
     pub quote_using_limit_orders: bool,
     pub merge_solutions: SolutionMerging,
+    /// Whether solutions of this solver may be merged with solutions of other
+    /// solvers that opted in as well.
+    pub merge_solutions_across_solvers: SolutionMerging,
     /// S3 configuration for storing the auctions in the form they are sent to
     /// the solver engine
     pub s3: Option<S3>,
@@ -140,6 +147,24 @@ impl Solver {
             headers.insert(header_name, val.parse()?);
         }
 
+        // Settlements get simulated from the solver's main account and only
+        // switch to a submission account afterwards, which is only sound if
+        // those accounts are allow-listed as well.
+        for account in &config.submission_accounts {
+            let allow_listed = eth
+                .contracts()
+                .authenticator()
+                .is_solver(account.address())
+                .call()
+                .await?;
+            anyhow::ensure!(
+                allow_listed,
+                "submission account {:?} of solver {} is not allow-listed",
+                account.address(),
+                config.name,
+            );
+        }
+
         let persistence = Persistence::build(&config).await;
 
         Ok(Self {
@@ -175,9 +200,17 @@ impl Solver {
         self.config.account.address().into()
     }
 
-    /// The account which should be used to sign settlements for this solver.
-    pub fn account(&self) -> ethcontract::Account {
-        self.config.account.clone()
+    /// All accounts settlements of this solver can be submitted from, starting
+    /// with the solver's main account.
+    pub fn accounts(&self) -> impl Iterator<Item = &ethcontract::Account> {
+        std::iter::once(&self.config.account).chain(&self.config.submission_accounts)
+    }
+
+    /// The account with the given address which should be used to sign
+    /// settlements for this solver.
+    pub fn account(&self, address: eth::Address) -> Option<&ethcontract::Account> {
+        self.accounts()
+            .find(|account| eth::Address::from(account.address()) == address)
     }
 
     /// Timeout configuration for this solver.
@@ -194,6 +227,14 @@ impl Solver {
         self.config.merge_solutions
     }
 
+    pub fn cross_solver_merging(&self) -> SolutionMerging {
+        self.config.merge_solutions_across_solvers
+    }
+
+    pub fn fee_handler(&self) -> FeeHandler {
+        self.config.fee_handler
+    }
+
     pub fn solver_native_token(&self) -> ManageNativeToken {
         self.config.solver_native_token
     }
@@ -270,7 +311,7 @@ impl Solver {
 }
 
 /// Controls whether or not the driver is allowed to merge multiple solutions
-/// of the same solver to produce an overall better solution.
+/// to produce an overall better solution.
 #[derive(Debug, Clone, Copy)]
 pub enum SolutionMerging {
     Allowed,
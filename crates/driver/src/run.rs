@@ -110,6 +110,7 @@ fn simulator(config: &infra::Config, eth: &Ethereum) -> Simulator {
             },
             eth.to_owned(),
         ),
+        Some(infra::simulator::Config::Evm) => Simulator::evm(eth.to_owned()),
         None => Simulator::ethereum(eth.to_owned()),
     };
     if config.disable_access_list_simulation {
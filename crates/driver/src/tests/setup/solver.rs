@@ -293,6 +293,8 @@ impl Solver {
                     target_confirm_time: Default::default(),
                     max_confirm_time: Default::default(),
                     retry_interval: Default::default(),
+                    gas_price_bump: Default::default(),
+                    max_cost_share_of_score: None,
                     kind: infra::mempool::Kind::Public(infra::mempool::RevertProtection::Disabled),
                 }],
             )
@@ -71,10 +71,14 @@ SELECT * FROM settlements WHERE auction_id = $1";
         .unwrap();
     let score = database::settlement_scores::fetch(&mut db, auction_id)
         .await
-        .unwrap()?;
+        .unwrap()
+        .into_iter()
+        .find(|score| score.winner == tx.solver)?;
     let call_data = database::settlement_call_data::fetch(&mut db, auction_id)
         .await
-        .unwrap()?;
+        .unwrap()
+        .into_iter()
+        .find(|call_data| call_data.solver == tx.solver)?;
     let competition = database::solver_competition::load_by_id(&mut db, auction_id)
         .await
         .unwrap()?
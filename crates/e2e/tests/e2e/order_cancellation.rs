@@ -128,7 +128,6 @@ async fn order_cancellation(web3: Web3) {
                 .delete(&format!("{API_HOST}{ORDERS_ENDPOINT}/{order_uid}"))
                 .json(&CancellationPayload {
                     signature: cancellation.signature,
-                    signing_scheme: cancellation.signing_scheme,
                 })
                 .send()
                 .await
@@ -151,8 +150,7 @@ async fn order_cancellation(web3: Web3) {
 
         let signed_cancellations = SignedOrderCancellations {
             data: cancellations,
-            signature,
-            signing_scheme,
+            signature: signature.to_signature(signing_scheme),
         };
 
         async move {
@@ -272,6 +272,54 @@ pub async fn timestamp_of_current_block_in_seconds(web3: &Web3) -> Result<u32> {
     timestamp_of_block_in_seconds(web3, BlockNumber::Latest).await
 }
 
+/// Returns the number of the first block whose timestamp is at or after the
+/// given one or `None` if no such block has been mined yet.
+///
+/// Block times are roughly constant, so the block is found by interpolating
+/// between the timestamps of known blocks, starting with the genesis and the
+/// latest block. This usually takes a handful of RPC requests. A step which
+/// doesn't at least halve the search range is followed by a bisection step so
+/// irregular block times still only take a logarithmic number of requests.
+pub async fn first_block_at_or_after(web3: &Web3, timestamp: u32) -> Result<Option<u64>> {
+    let target = u64::from(timestamp);
+    let latest = get_block_info_at_id(web3, BlockNumber::Latest.into()).await?;
+    if latest.timestamp < target {
+        return Ok(None);
+    }
+    let block_timestamp = |number: u64| async move {
+        timestamp_of_block_in_seconds(web3, BlockNumber::Number(number.into()))
+            .await
+            .map(u64::from)
+    };
+    let genesis = block_timestamp(0).await?;
+    if genesis >= target {
+        return Ok(Some(0));
+    }
+
+    // The `low` block was mined before the timestamp, the `high` block at or
+    // after it.
+    let (mut low, mut high) = ((0, genesis), (latest.number, latest.timestamp));
+    let mut bisect = false;
+    while high.0 - low.0 > 1 {
+        let range = high.0 - low.0;
+        let number = if bisect {
+            low.0 + range / 2
+        } else {
+            let offset =
+                u128::from(target - low.1) * u128::from(range) / u128::from(high.1 - low.1);
+            (low.0 + offset as u64).clamp(low.0 + 1, high.0 - 1)
+        };
+        let timestamp = block_timestamp(number).await?;
+        if timestamp < target {
+            low = (number, timestamp);
+        } else {
+            high = (number, timestamp);
+        }
+        bisect = !bisect && high.0 - low.0 > range / 2;
+    }
+    Ok(Some(high.0))
+}
+
 pub async fn block_number_to_block_number_hash(
     web3: &Web3,
     block_number: BlockNumber,
@@ -315,8 +363,12 @@ fn update_block_metrics(current_block: u64, new_block: u64) {
 mod tests {
     use {
         super::*,
-        crate::create_env_test_transport,
+        crate::{create_env_test_transport, mock::MockTransport},
         futures::StreamExt,
+        std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
         tokio::time::{timeout, Duration},
     };
 
@@ -368,6 +420,59 @@ mod tests {
         assert_eq!(blocks.first().unwrap().0, 0);
     }
 
+    #[tokio::test]
+    async fn finds_first_block_at_or_after_timestamp() {
+        const LATEST: u64 = 20_000_000;
+        /// Blocks took 14 seconds until the merge and 12 seconds afterwards.
+        fn timestamp(block: u64) -> u64 {
+            const MERGE: u64 = 15_000_000;
+            1_438_000_000 + block.min(MERGE) * 14 + block.saturating_sub(MERGE) * 12
+        }
+
+        let transport = MockTransport::new();
+        let requests = Arc::new(AtomicUsize::new(0));
+        transport.mock().expect_execute().returning({
+            let requests = requests.clone();
+            move |method, params| {
+                assert_eq!(method, "eth_getBlockByNumber");
+                requests.fetch_add(1, Ordering::SeqCst);
+                let number = match params[0].as_str().unwrap() {
+                    "latest" => LATEST,
+                    number => u64::from_str_radix(number.trim_start_matches("0x"), 16).unwrap(),
+                };
+                Ok(serde_json::to_value(Block::<H256> {
+                    number: Some(number.into()),
+                    hash: Some(H256::from_low_u64_be(number)),
+                    timestamp: timestamp(number).into(),
+                    base_fee_per_gas: Some(1.into()),
+                    ..Default::default()
+                })
+                .unwrap())
+            }
+        });
+        let web3 = Web3::new(Web3Transport::new(transport));
+
+        for (target, expected, max_requests) in [
+            (timestamp(0), Some(0), 2),
+            (timestamp(1_234_567) - 1, Some(1_234_567), 6),
+            (timestamp(1_234_567), Some(1_234_567), 6),
+            (timestamp(1_234_567) + 1, Some(1_234_568), 6),
+            // Interpolating converges slowly around a change of the block
+            // time, the bisection steps still bound the number of requests.
+            (timestamp(15_000_000), Some(15_000_000), 20),
+            (timestamp(19_999_990) - 5, Some(19_999_990), 6),
+            (timestamp(LATEST), Some(LATEST), 3),
+            (timestamp(LATEST) + 1, None, 1),
+        ] {
+            requests.store(0, Ordering::SeqCst);
+            let result = first_block_at_or_after(&web3, target.try_into().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(result, expected, "timestamp {target}");
+            assert!(requests.load(Ordering::SeqCst) <= max_requests);
+        }
+    }
+
     // Tests that a throttled block stream indeed skips the configured
     // number of updates.
     // Always awaits the next block on a timer to not get the test stuck
@@ -4,10 +4,12 @@ pub mod auction;
 mod format;
 pub mod interaction;
 pub mod order;
+pub mod order_event;
 pub mod quote;
 pub mod ratio_as_decimal;
 pub mod signature;
 pub mod solver_competition;
+pub mod solver_rewards;
 pub mod time;
 pub mod trade;
 
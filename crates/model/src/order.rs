@@ -3,9 +3,11 @@
 
 use {
     crate::{
+        auction::AuctionId,
         interaction::InteractionData,
+        order_event::OrderEvent,
         quote::QuoteId,
-        signature::{self, EcdsaSignature, EcdsaSigningScheme, Signature},
+        signature::{self, EcdsaSignature, EcdsaSigningScheme, Signature, SigningScheme},
         DomainSeparator,
         TokenPair,
     },
@@ -66,6 +68,25 @@ pub enum OrderStatus {
     Expired,
 }
 
+/// Where an order currently stands in the settlement process as returned by
+/// the `/api/v1/orders/{uid}/status` endpoint.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderStatusDetails {
+    pub status: OrderStatus,
+    /// The most recent life cycle event of the order.
+    pub latest_event: Option<OrderEvent>,
+    /// The id of the most recent auction.
+    pub latest_auction_id: Option<AuctionId>,
+    /// Whether the order was part of the most recent auction.
+    pub in_latest_auction: bool,
+    /// Names of the solvers that proposed a solution containing the order in
+    /// the solver competition of the most recent auction.
+    pub proposed_by: Vec<String>,
+    /// Executed fraction of the order between 0 and 1.
+    pub fill_ratio: f64,
+}
+
 impl Order {
     pub fn contains_token_from(&self, token_list: &HashSet<H160>) -> bool {
         token_list.contains(&self.data.buy_token) || token_list.contains(&self.data.sell_token)
@@ -514,34 +535,34 @@ impl OrderCancellations {
 }
 
 /// Signed order cancellations.
+///
+/// The signature is either an ECDSA signature of the owner or an EIP-1271
+/// signature that gets verified by the owner contract.
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignedOrderCancellations {
     #[serde(flatten)]
     pub data: OrderCancellations,
-    pub signature: EcdsaSignature,
-    pub signing_scheme: EcdsaSigningScheme,
+    #[serde(flatten)]
+    pub signature: Signature,
 }
 
 impl SignedOrderCancellations {
-    pub fn validate(&self, domain_separator: &DomainSeparator) -> Result<H160> {
+    /// Recovers the signer of ECDSA signed cancellations. Returns `None` for
+    /// signing schemes that don't support signer recovery.
+    pub fn recover_signer(&self, domain_separator: &DomainSeparator) -> Result<Option<H160>> {
         Ok(self
             .signature
-            .recover(
-                self.signing_scheme,
-                domain_separator,
-                &self.data.hash_struct(),
-            )?
-            .signer)
+            .recover(domain_separator, &self.data.hash_struct())?
+            .map(|recovered| recovered.signer))
     }
 }
 
 /// An order cancellation as provided to the orderbook by the frontend.
-#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[derive(Eq, PartialEq, Clone, Debug)]
 pub struct OrderCancellation {
     pub order_uid: OrderUid,
-    pub signature: EcdsaSignature,
-    pub signing_scheme: EcdsaSigningScheme,
+    pub signature: Signature,
 }
 
 impl Default for OrderCancellation {
@@ -572,15 +593,15 @@ impl OrderCancellation {
     ) -> Self {
         let mut result = Self {
             order_uid,
-            signature: Default::default(),
-            signing_scheme: EcdsaSigningScheme::Eip712,
+            signature: Signature::default_with(SigningScheme::Eip712),
         };
         result.signature = EcdsaSignature::sign(
-            result.signing_scheme,
+            EcdsaSigningScheme::Eip712,
             domain_separator,
             &result.hash_struct(),
             key,
-        );
+        )
+        .to_signature(EcdsaSigningScheme::Eip712);
         result
     }
 
@@ -591,11 +612,13 @@ impl OrderCancellation {
         signing::keccak256(&hash_data)
     }
 
-    pub fn validate(&self, domain_separator: &DomainSeparator) -> Result<H160> {
+    /// Recovers the signer of an ECDSA signed cancellation. Returns `None` for
+    /// signing schemes that don't support signer recovery.
+    pub fn recover_signer(&self, domain_separator: &DomainSeparator) -> Result<Option<H160>> {
         Ok(self
             .signature
-            .recover(self.signing_scheme, domain_separator, &self.hash_struct())?
-            .signer)
+            .recover(domain_separator, &self.hash_struct())?
+            .map(|recovered| recovered.signer))
     }
 }
 
@@ -603,8 +626,8 @@ impl OrderCancellation {
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CancellationPayload {
-    pub signature: EcdsaSignature,
-    pub signing_scheme: EcdsaSigningScheme,
+    #[serde(flatten)]
+    pub signature: Signature,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize)]
@@ -1349,11 +1372,10 @@ mod tests {
         ] {
             let cancellation = OrderCancellation {
                 order_uid: OrderUid(hex!("2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a")),
-                signature: EcdsaSignature::from_bytes(signature),
-                signing_scheme: *signing_scheme,
+                signature: EcdsaSignature::from_bytes(signature).to_signature(*signing_scheme),
             };
-            let owner = cancellation.validate(&domain_separator).unwrap();
-            assert_eq!(owner, expected_owner);
+            let owner = cancellation.recover_signer(&domain_separator).unwrap();
+            assert_eq!(owner, Some(expected_owner));
         }
     }
 
@@ -0,0 +1,110 @@
+//! Contains the order event types as described by the openapi documentation.
+
+use {
+    crate::order::OrderUid,
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+};
+
+/// Describes what kind of event was registered for an order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderEventLabel {
+    /// Order was added to the orderbook.
+    Created,
+    /// Order was included in an auction and got sent to the solvers.
+    Ready,
+    /// Order was filtered from the auction and did not get sent to the solvers.
+    Filtered,
+    /// Order can not be settled on-chain.
+    Invalid,
+    /// Order was included in the winning settlement and is in the process of
+    /// being submitted on-chain.
+    Executing,
+    /// Order was included in a valid settlement.
+    Considered,
+    /// Order was settled on-chain.
+    Traded,
+    /// Order was cancelled by the user.
+    Cancelled,
+}
+
+/// A single event of the life cycle of an order.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderEvent {
+    pub timestamp: DateTime<Utc>,
+    pub label: OrderEventLabel,
+    /// Why the event happened. Only set for some `filtered` and `invalid`
+    /// events (e.g. `insufficient_balance`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// An event of a specific order as it gets streamed to subscribers.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderEventUpdate {
+    pub order_uid: OrderUid,
+    #[serde(flatten)]
+    pub event: OrderEvent,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, chrono::TimeZone, serde_json::json};
+
+    #[test]
+    fn deserialization_and_back() {
+        let value = json!([
+            {
+                "timestamp": "2024-05-01T12:00:00Z",
+                "label": "created",
+            },
+            {
+                "timestamp": "2024-05-01T12:00:10Z",
+                "label": "filtered",
+                "reason": "missing_price",
+            },
+        ]);
+        let expected = vec![
+            OrderEvent {
+                timestamp: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap(),
+                label: OrderEventLabel::Created,
+                reason: None,
+            },
+            OrderEvent {
+                timestamp: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 10).unwrap(),
+                label: OrderEventLabel::Filtered,
+                reason: Some("missing_price".to_owned()),
+            },
+        ];
+        let deserialized: Vec<OrderEvent> = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(deserialized, expected);
+        let serialized = serde_json::to_value(expected).unwrap();
+        assert_eq!(serialized, value);
+    }
+
+    #[test]
+    fn deserializes_database_notifications() {
+        let payload = r#"{
+            "orderUid": "0x1111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111",
+            "timestamp": "2024-05-01T12:00:00.5+00:00",
+            "label": "traded",
+            "reason": null
+        }"#;
+        let update: OrderEventUpdate = serde_json::from_str(payload).unwrap();
+        assert_eq!(
+            update,
+            OrderEventUpdate {
+                order_uid: OrderUid([0x11; 56]),
+                event: OrderEvent {
+                    timestamp: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+                        + chrono::Duration::milliseconds(500),
+                    label: OrderEventLabel::Traded,
+                    reason: None,
+                },
+            }
+        );
+    }
+}
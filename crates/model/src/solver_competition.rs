@@ -18,6 +18,17 @@ pub struct SolverCompetitionDB {
     pub competition_simulation_block: u64,
     pub auction: CompetitionAuction,
     pub solutions: Vec<SolverSettlement>,
+    /// Rankings of the solutions that got settled. These are not necessarily
+    /// the winners because the auction gets handed to the next solution in
+    /// line if the winner fails to settle it in time. Auctions with multiple
+    /// winners can have multiple settled solutions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub settled_rankings: Vec<usize>,
+    /// Solutions that got excluded from the ranking because they executed
+    /// some orders materially worse for the user than another solution
+    /// offered.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected_solutions: Vec<RejectedSolution>,
 }
 
 /// Returned by the `/solver_competition` endpoint.
@@ -65,6 +76,18 @@ pub struct SolverSettlement {
     pub uninternalized_call_data: Option<Vec<u8>>,
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedSolution {
+    pub solver: String,
+    pub solver_address: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub score: U256,
+    /// The orders the solution executed worse than another solution.
+    pub unfair_orders: Vec<OrderUid>,
+}
+
 #[serde_as]
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum Score {
@@ -125,6 +148,15 @@ pub enum Order {
     },
 }
 
+impl Order {
+    pub fn id(&self) -> &OrderUid {
+        match self {
+            Self::Colocated { id, .. } => id,
+            Self::Legacy { id, .. } => id,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, maplit::btreemap};
@@ -180,6 +212,19 @@ mod tests {
                     "uninternalizedCallData": "0x1314",
                 },
             ],
+            "settledRankings": [1],
+            "rejectedSolutions": [
+                {
+                    "solver": "3",
+                    "solverAddress": "0x3333333333333333333333333333333333333333",
+                    "score": "2",
+                    "unfairOrders": [
+                        "0x3333333333333333333333333333333333333333333333333333333333333333\
+                           3333333333333333333333333333333333333333\
+                           33333333",
+                    ],
+                },
+            ],
         });
 
         let orig = SolverCompetitionAPI {
@@ -222,6 +267,13 @@ mod tests {
                     call_data: Some(vec![0x13]),
                     uninternalized_call_data: Some(vec![0x13, 0x14]),
                 }],
+                settled_rankings: vec![1],
+                rejected_solutions: vec![RejectedSolution {
+                    solver: "3".to_string(),
+                    solver_address: H160([0x33; 20]),
+                    score: 2.into(),
+                    unfair_orders: vec![OrderUid([0x33; 56])],
+                }],
             },
         };
 
@@ -0,0 +1,67 @@
+//! Reward statements of solvers per accounting period.
+
+use {
+    bigdecimal::BigDecimal,
+    primitive_types::H160,
+    serde::{Deserialize, Serialize},
+    serde_with::{serde_as, DisplayFromStr},
+};
+
+/// The rewards all solvers earned in an accounting period. A period covers
+/// all auctions with a block deadline in `[start_block, end_block)`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SolverRewards {
+    pub start_block: u64,
+    pub end_block: u64,
+    pub statements: Vec<Statement>,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+    pub solver: H160,
+    /// Number of auctions the solver won.
+    pub auctions: u64,
+    /// Number of won auctions the solver settled.
+    pub settled: u64,
+    /// Reward in wei, negative if the penalties outweigh the rewards.
+    #[serde_as(as = "DisplayFromStr")]
+    pub reward: BigDecimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn serialize() {
+        let rewards = SolverRewards {
+            start_block: 100,
+            end_block: 200,
+            statements: vec![Statement {
+                solver: H160([0x11; 20]),
+                auctions: 3,
+                settled: 2,
+                reward: (-5).into(),
+            }],
+        };
+        let json = json!({
+            "startBlock": 100,
+            "endBlock": 200,
+            "statements": [{
+                "solver": "0x1111111111111111111111111111111111111111",
+                "auctions": 3,
+                "settled": 2,
+                "reward": "-5",
+            }],
+        });
+
+        assert_eq!(serde_json::to_value(&rewards).unwrap(), json);
+        assert_eq!(
+            serde_json::from_value::<SolverRewards>(json).unwrap(),
+            rewards
+        );
+    }
+}
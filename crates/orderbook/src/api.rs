@@ -1,7 +1,15 @@
 use {
-    crate::{app_data, database::Postgres, orderbook::Orderbook, quoter::QuoteHandler},
+    crate::{
+        app_data,
+        database::Postgres,
+        order_events::OrderEventStream,
+        orderbook::Orderbook,
+        quoter::QuoteHandler,
+    },
+    ethrpc::current_block::first_block_at_or_after,
     shared::{
-        api::{box_filter, error, finalize_router, ApiReply},
+        api::{box_filter, error, finalize_router, rate_limiting::RateLimiter, ApiReply},
+        ethrpc::Web3,
         price_estimation::native::NativePriceEstimating,
     },
     std::sync::Arc,
@@ -13,15 +21,22 @@ mod cancel_orders;
 mod get_app_data;
 mod get_auction;
 mod get_native_price;
+mod get_native_price_history;
 mod get_order_by_uid;
+mod get_order_events;
+mod get_order_status;
 mod get_orders_by_tx;
 mod get_solver_competition;
+mod get_solver_rewards;
 mod get_total_surplus;
 mod get_trades;
 mod get_user_orders;
 mod post_order;
+mod post_orders_batch;
 mod post_quote;
+mod post_quote_batch;
 mod put_app_data;
+mod stream_order_events;
 mod version;
 
 pub fn handle_all_routes(
@@ -30,6 +45,9 @@ pub fn handle_all_routes(
     quotes: Arc<QuoteHandler>,
     app_data: Arc<app_data::Registry>,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    order_events: Arc<OrderEventStream>,
+    web3: Web3,
+    rate_limiter: Arc<RateLimiter>,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     // Note that we add a string with endpoint's name to all responses.
     // This string will be used later to report metrics.
@@ -40,13 +58,29 @@ pub fn handle_all_routes(
             "v1/create_order",
             box_filter(post_order::post_order(orderbook.clone())),
         ),
+        (
+            "v1/create_orders_batch",
+            box_filter(post_orders_batch::post_orders_batch(orderbook.clone())),
+        ),
         (
             "v1/get_order",
             box_filter(get_order_by_uid::get_order_by_uid(orderbook.clone())),
         ),
+        (
+            "v1/get_order_events",
+            box_filter(get_order_events::get_order_events(database.clone())),
+        ),
+        (
+            "v1/get_order_status",
+            box_filter(get_order_status::get_order_status(orderbook.clone())),
+        ),
+        (
+            "v1/stream_order_events",
+            stream_order_events::stream_order_events(order_events).boxed(),
+        ),
         (
             "v1/get_trades",
-            box_filter(get_trades::get_trades(database.clone())),
+            box_filter(get_trades::get_trades(database.clone(), web3.clone())),
         ),
         (
             "v1/cancel_order",
@@ -64,7 +98,14 @@ pub fn handle_all_routes(
             "v1/get_orders_by_tx",
             box_filter(get_orders_by_tx::get_orders_by_tx(orderbook.clone())),
         ),
-        ("v1/post_quote", box_filter(post_quote::post_quote(quotes))),
+        (
+            "v1/post_quote",
+            box_filter(post_quote::post_quote(quotes.clone())),
+        ),
+        (
+            "v1/post_quote_batch",
+            box_filter(post_quote_batch::post_quote_batch(quotes)),
+        ),
         (
             "v1/auction",
             box_filter(get_auction::get_auction(orderbook.clone())),
@@ -79,11 +120,22 @@ pub fn handle_all_routes(
                 database.clone(),
             ))),
         ),
+        (
+            "v1/solver_rewards",
+            box_filter(get_solver_rewards::get(database.clone())),
+        ),
         ("v1/version", box_filter(version::version())),
         (
             "v1/get_native_price",
             box_filter(get_native_price::get_native_price(native_price_estimator)),
         ),
+        (
+            "v1/get_native_price_history",
+            box_filter(get_native_price_history::get_native_price_history(
+                database.clone(),
+                web3,
+            )),
+        ),
         (
             "v1/get_app_data",
             get_app_data::get(database.clone()).boxed(),
@@ -98,5 +150,33 @@ pub fn handle_all_routes(
         ),
     ];
 
-    finalize_router(routes, "orderbook::api::request_summary")
+    finalize_router(routes, "orderbook::api::request_summary", rate_limiter)
+}
+
+/// Converts a time range into the range of blocks mined in it. The database
+/// only indexes block numbers, so time bounds get resolved with the node.
+/// `from` is inclusive and `to` exclusive, while the returned block bounds are
+/// both inclusive. Returns `None` if no block can have been mined in the range.
+async fn blocks_in_time_range(
+    web3: &Web3,
+    from: Option<u32>,
+    to: Option<u32>,
+) -> anyhow::Result<Option<(Option<u64>, Option<u64>)>> {
+    let from_block = match from {
+        Some(timestamp) => match first_block_at_or_after(web3, timestamp).await? {
+            Some(block) => Some(block),
+            // No block has been mined at that time yet.
+            None => return Ok(None),
+        },
+        None => None,
+    };
+    let to_block = match to {
+        Some(timestamp) => match first_block_at_or_after(web3, timestamp).await? {
+            Some(0) => return Ok(None),
+            Some(block) => Some(block - 1),
+            None => None,
+        },
+        None => None,
+    };
+    Ok(Some((from_block, to_block)))
 }
@@ -15,7 +15,6 @@ pub fn cancel_order_request(
         .map(|uid, payload: CancellationPayload| OrderCancellation {
             order_uid: uid,
             signature: payload.signature,
-            signing_scheme: payload.signing_scheme,
         })
 }
 
@@ -53,6 +52,13 @@ impl IntoWarpReply for OrderCancellationError {
                 super::error("OnChainOrder", "On-chain orders must be cancelled on-chain"),
                 StatusCode::BAD_REQUEST,
             ),
+            Self::InvalidEip1271Signature => with_status(
+                super::error(
+                    "InvalidEip1271Signature",
+                    "Order owner rejected the EIP-1271 signature",
+                ),
+                StatusCode::UNAUTHORIZED,
+            ),
             Self::Other(err) => {
                 tracing::error!(?err, "cancel_order");
                 shared::api::internal_error_reply()
@@ -83,7 +89,7 @@ mod tests {
         super::*,
         ethcontract::H256,
         hex_literal::hex,
-        model::signature::{EcdsaSignature, EcdsaSigningScheme},
+        model::signature::{EcdsaSignature, EcdsaSigningScheme, Signature},
         serde_json::json,
         warp::{test::request, Reply},
     };
@@ -108,8 +114,18 @@ mod tests {
                         "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f"
                     )),
                     v: 27,
-                },
-                signing_scheme: EcdsaSigningScheme::Eip712,
+                }
+                .to_signature(EcdsaSigningScheme::Eip712),
+            },
+        );
+        assert_eq!(
+            serde_json::from_value::<CancellationPayload>(json!({
+                "signature": "0x010203",
+                "signingScheme": "eip1271"
+            }))
+            .unwrap(),
+            CancellationPayload {
+                signature: Signature::Eip1271(vec![1, 2, 3]),
             },
         );
     }
@@ -124,8 +140,7 @@ mod tests {
             .method("DELETE")
             .header("content-type", "application/json")
             .json(&CancellationPayload {
-                signature: cancellation.signature,
-                signing_scheme: cancellation.signing_scheme,
+                signature: cancellation.signature.clone(),
             });
         let result = request.filter(&filter).await.unwrap();
         assert_eq!(result, cancellation);
@@ -159,6 +174,10 @@ mod tests {
             cancel_order_response(Err(OrderCancellationError::WrongOwner)).into_response();
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
+        let response = cancel_order_response(Err(OrderCancellationError::InvalidEip1271Signature))
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
         let response =
             cancel_order_response(Err(OrderCancellationError::OrderNotFound)).into_response();
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
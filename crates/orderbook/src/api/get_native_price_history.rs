@@ -0,0 +1,221 @@
+use {
+    crate::{database::Postgres, dto::AuctionPrice},
+    anyhow::{Context, Result},
+    primitive_types::H160,
+    serde::{Deserialize, Serialize},
+    shared::{
+        api::{error, ApiReply},
+        ethrpc::Web3,
+    },
+    std::convert::Infallible,
+    warp::{hyper::StatusCode, reply::with_status, Filter, Rejection},
+};
+
+const DEFAULT_MAX_POINTS: u64 = 100;
+const MAX_POINTS: u64 = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Query {
+    from_block: Option<u64>,
+    to_block: Option<u64>,
+    from_timestamp: Option<u32>,
+    to_timestamp: Option<u32>,
+    max_points: Option<u64>,
+}
+
+/// A validated price history query.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct HistoryRequest {
+    from_block: Option<u64>,
+    to_block: Option<u64>,
+    /// Unix timestamp (inclusive) that still needs to be resolved to a block.
+    from_timestamp: Option<u32>,
+    /// Unix timestamp (exclusive) that still needs to be resolved to a block.
+    to_timestamp: Option<u32>,
+    max_points: u64,
+}
+
+impl Query {
+    fn validate(&self) -> Result<HistoryRequest, String> {
+        if self.from_block.is_some() && self.from_timestamp.is_some()
+            || self.to_block.is_some() && self.to_timestamp.is_some()
+        {
+            return Err("Can't bound the same side of the range by block and timestamp.".into());
+        }
+        let max_points = self.max_points.unwrap_or(DEFAULT_MAX_POINTS);
+        if !(1..=MAX_POINTS).contains(&max_points) {
+            return Err(format!("maxPoints must be in [1,{MAX_POINTS}]."));
+        }
+        Ok(HistoryRequest {
+            from_block: self.from_block,
+            to_block: self.to_block,
+            from_timestamp: self.from_timestamp,
+            to_timestamp: self.to_timestamp,
+            max_points,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PriceHistory {
+    /// Price of the most recent auction that priced the token, regardless of
+    /// the requested range.
+    latest: Option<AuctionPrice>,
+    prices: Vec<AuctionPrice>,
+}
+
+fn get_native_price_history_request(
+) -> impl Filter<Extract = (H160, Result<HistoryRequest, String>), Error = Rejection> + Clone {
+    warp::path!("v1" / "token" / H160 / "native_price_history")
+        .and(warp::get())
+        .and(warp::query::<Query>())
+        .map(|token, query: Query| (token, query.validate()))
+        .untuple_one()
+}
+
+/// Auction prices are indexed by the block at which the auction got simulated,
+/// so time bounds are converted into block bounds with the node first.
+async fn price_history(
+    database: &Postgres,
+    web3: &Web3,
+    token: H160,
+    mut request: HistoryRequest,
+) -> Result<PriceHistory> {
+    let latest = database.latest_token_price(&token).await?;
+    let Some((from_block, to_block)) =
+        super::blocks_in_time_range(web3, request.from_timestamp, request.to_timestamp).await?
+    else {
+        return Ok(PriceHistory {
+            latest,
+            prices: Vec::new(),
+        });
+    };
+    request.from_block = request.from_block.or(from_block);
+    request.to_block = request.to_block.or(to_block);
+    let prices = database
+        .token_price_history(
+            &token,
+            request.from_block,
+            request.to_block,
+            request.max_points,
+        )
+        .await?;
+    Ok(PriceHistory { latest, prices })
+}
+
+pub fn get_native_price_history(
+    db: Postgres,
+    web3: Web3,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    get_native_price_history_request().and_then(move |token, request_result| {
+        let database = db.clone();
+        let web3 = web3.clone();
+        async move {
+            Result::<_, Infallible>::Ok(match request_result {
+                Ok(request) => {
+                    let result = price_history(&database, &web3, token, request)
+                        .await
+                        .context("get_native_price_history");
+                    match result {
+                        Ok(reply) => with_status(warp::reply::json(&reply), StatusCode::OK),
+                        Err(err) => {
+                            tracing::error!(?err, ?token, "get_native_price_history");
+                            shared::api::internal_error_reply()
+                        }
+                    }
+                }
+                Err(msg) => with_status(error("InvalidRange", msg), StatusCode::BAD_REQUEST),
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json, shared::addr, warp::test::request};
+
+    #[tokio::test]
+    async fn get_native_price_history_request_ok() {
+        let filter = get_native_price_history_request();
+        let path = "/v1/token/0x0000000000000000000000000000000000000001/native_price_history";
+
+        let (token, result) = request()
+            .path(path)
+            .method("GET")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(token, addr!("0000000000000000000000000000000000000001"));
+        assert_eq!(
+            result.unwrap(),
+            HistoryRequest {
+                max_points: DEFAULT_MAX_POINTS,
+                ..Default::default()
+            }
+        );
+
+        let (_, result) = request()
+            .path(&format!("{path}?fromTimestamp=10&toBlock=20&maxPoints=5"))
+            .method("GET")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.unwrap(),
+            HistoryRequest {
+                from_timestamp: Some(10),
+                to_block: Some(20),
+                max_points: 5,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_queries() {
+        for query in [
+            Query {
+                from_block: Some(1),
+                from_timestamp: Some(1),
+                ..Default::default()
+            },
+            Query {
+                to_block: Some(1),
+                to_timestamp: Some(1),
+                ..Default::default()
+            },
+            Query {
+                max_points: Some(0),
+                ..Default::default()
+            },
+            Query {
+                max_points: Some(MAX_POINTS + 1),
+                ..Default::default()
+            },
+        ] {
+            assert!(query.validate().is_err(), "{query:?}");
+        }
+    }
+
+    #[test]
+    fn serializes_price_history() {
+        let price = AuctionPrice {
+            auction_id: 1,
+            block: 2,
+            price: 3.into(),
+        };
+        let history = PriceHistory {
+            latest: Some(price.clone()),
+            prices: vec![price],
+        };
+        assert_eq!(
+            serde_json::to_value(history).unwrap(),
+            json!({
+                "latest": { "auctionId": 1, "block": 2, "price": "3" },
+                "prices": [{ "auctionId": 1, "block": 2, "price": "3" }],
+            })
+        );
+    }
+}
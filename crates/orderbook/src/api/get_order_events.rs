@@ -0,0 +1,69 @@
+use {
+    crate::database::Postgres,
+    anyhow::Result,
+    model::{order::OrderUid, order_event::OrderEvent},
+    std::convert::Infallible,
+    warp::{hyper::StatusCode, reply, Filter, Rejection},
+};
+
+fn get_order_events_request() -> impl Filter<Extract = (OrderUid,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / OrderUid / "events").and(warp::get())
+}
+
+fn get_order_events_response(result: Result<Vec<OrderEvent>>) -> super::ApiReply {
+    match result {
+        Ok(events) => reply::with_status(reply::json(&events), StatusCode::OK),
+        Err(err) => {
+            tracing::error!(?err, "get_order_events");
+            shared::api::internal_error_reply()
+        }
+    }
+}
+
+pub fn get_order_events(
+    db: Postgres,
+) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    get_order_events_request().and_then(move |uid: OrderUid| {
+        let db = db.clone();
+        async move {
+            let result = db.order_events(&uid).await;
+            Result::<_, Infallible>::Ok(get_order_events_response(result))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        chrono::Utc,
+        model::order_event::OrderEventLabel,
+        shared::api::response_body,
+        warp::{test::request, Reply},
+    };
+
+    #[tokio::test]
+    async fn get_order_events_request_ok() {
+        let uid = OrderUid::default();
+        let request = request()
+            .path(&format!("/v1/orders/{uid}/events"))
+            .method("GET");
+        let filter = get_order_events_request();
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, uid);
+    }
+
+    #[tokio::test]
+    async fn get_order_events_response_ok() {
+        let events = vec![OrderEvent {
+            timestamp: Utc::now(),
+            label: OrderEventLabel::Filtered,
+            reason: Some("missing_price".to_owned()),
+        }];
+        let response = get_order_events_response(Ok(events.clone())).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_body(response).await;
+        let response_events: Vec<OrderEvent> = serde_json::from_slice(body.as_slice()).unwrap();
+        assert_eq!(response_events, events);
+    }
+}
@@ -0,0 +1,89 @@
+use {
+    crate::orderbook::Orderbook,
+    anyhow::Result,
+    model::order::{OrderStatusDetails, OrderUid},
+    std::{convert::Infallible, sync::Arc},
+    warp::{hyper::StatusCode, reply, Filter, Rejection},
+};
+
+fn get_order_status_request() -> impl Filter<Extract = (OrderUid,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / OrderUid / "status").and(warp::get())
+}
+
+fn get_order_status_response(result: Result<Option<OrderStatusDetails>>) -> super::ApiReply {
+    match result {
+        Ok(Some(status)) => reply::with_status(reply::json(&status), StatusCode::OK),
+        Ok(None) => reply::with_status(
+            super::error("NotFound", "Order was not found"),
+            StatusCode::NOT_FOUND,
+        ),
+        Err(err) => {
+            tracing::error!(?err, "get_order_status");
+            shared::api::internal_error_reply()
+        }
+    }
+}
+
+pub fn get_order_status(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    get_order_status_request().and_then(move |uid: OrderUid| {
+        let orderbook = orderbook.clone();
+        async move {
+            let result = orderbook.get_order_status(&uid).await;
+            Result::<_, Infallible>::Ok(get_order_status_response(result))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        chrono::Utc,
+        model::{
+            order::OrderStatus,
+            order_event::{OrderEvent, OrderEventLabel},
+        },
+        shared::api::response_body,
+        warp::{test::request, Reply},
+    };
+
+    #[tokio::test]
+    async fn get_order_status_request_ok() {
+        let uid = OrderUid::default();
+        let request = request()
+            .path(&format!("/v1/orders/{uid}/status"))
+            .method("GET");
+        let filter = get_order_status_request();
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, uid);
+    }
+
+    #[tokio::test]
+    async fn get_order_status_response_ok() {
+        let status = OrderStatusDetails {
+            status: OrderStatus::Open,
+            latest_event: Some(OrderEvent {
+                timestamp: Utc::now(),
+                label: OrderEventLabel::Considered,
+                reason: None,
+            }),
+            latest_auction_id: Some(42),
+            in_latest_auction: true,
+            proposed_by: vec!["solver".to_owned()],
+            fill_ratio: 0.5,
+        };
+        let response = get_order_status_response(Ok(Some(status.clone()))).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_body(response).await;
+        let response_status: OrderStatusDetails = serde_json::from_slice(body.as_slice()).unwrap();
+        assert_eq!(response_status, status);
+    }
+
+    #[tokio::test]
+    async fn get_order_status_response_non_existent() {
+        let response = get_order_status_response(Ok(None)).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
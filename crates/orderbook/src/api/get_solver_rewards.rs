@@ -0,0 +1,74 @@
+use {
+    crate::database::Postgres,
+    reqwest::StatusCode,
+    std::convert::Infallible,
+    warp::{reply::with_status, Filter, Rejection},
+};
+
+fn request_start_block() -> impl Filter<Extract = (Option<u64>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "solver_rewards" / u64)
+        .and(warp::get())
+        .map(Some)
+}
+
+fn request_latest() -> impl Filter<Extract = (Option<u64>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "solver_rewards" / "latest")
+        .and(warp::get())
+        .map(|| None)
+}
+
+pub fn get(db: Postgres) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    request_latest()
+        .or(request_start_block())
+        .unify()
+        .and_then(move |start_block: Option<u64>| {
+            let db = db.clone();
+            async move {
+                let rewards = db.solver_rewards(start_block).await;
+                Result::<_, Infallible>::Ok(match rewards {
+                    Ok(Some(rewards)) => with_status(warp::reply::json(&rewards), StatusCode::OK),
+                    Ok(None) => with_status(
+                        super::error("NotFound", "no solver rewards found"),
+                        StatusCode::NOT_FOUND,
+                    ),
+                    Err(err) => {
+                        tracing::error!(?err, ?start_block, "failed to load solver rewards");
+                        shared::api::internal_error_reply()
+                    }
+                })
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, warp::test::request};
+
+    #[tokio::test]
+    async fn parses_periods() {
+        let filter = request_latest().or(request_start_block()).unify();
+
+        let start_block = request()
+            .path("/v1/solver_rewards/latest")
+            .method("GET")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(start_block, None);
+
+        let start_block = request()
+            .path("/v1/solver_rewards/100")
+            .method("GET")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(start_block, Some(100));
+
+        assert!(request()
+            .path("/v1/solver_rewards/first")
+            .method("GET")
+            .filter(&filter)
+            .await
+            .is_err());
+    }
+}
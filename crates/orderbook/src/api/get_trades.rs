@@ -4,19 +4,34 @@ use {
         Postgres,
     },
     anyhow::{Context, Result},
-    model::order::OrderUid,
-    primitive_types::H160,
+    model::{order::OrderUid, trade::Trade},
+    primitive_types::{H160, H256},
     serde::Deserialize,
-    shared::api::{error, ApiReply},
+    shared::{
+        api::{error, ApiReply},
+        ethrpc::Web3,
+    },
     std::convert::Infallible,
     warp::{hyper::StatusCode, reply::with_status, Filter, Rejection},
 };
 
+const MIN_LIMIT: u64 = 1;
+const MAX_LIMIT: u64 = 1000;
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Query {
     pub order_uid: Option<OrderUid>,
     pub owner: Option<H160>,
+    pub tx_hash: Option<H256>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    pub from_timestamp: Option<u32>,
+    pub to_timestamp: Option<u32>,
+    pub offset: Option<u64>,
+    pub limit: Option<u64>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -24,39 +39,100 @@ enum TradeFilterError {
     InvalidFilter(String),
 }
 
+/// A validated trades query.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct TradeRequest {
+    filter: TradeFilter,
+    /// Unix timestamp (inclusive) that still needs to be resolved to a block.
+    from_timestamp: Option<u32>,
+    /// Unix timestamp (exclusive) that still needs to be resolved to a block.
+    to_timestamp: Option<u32>,
+    offset: u64,
+    limit: Option<u64>,
+}
+
 impl Query {
     fn trade_filter(&self) -> TradeFilter {
         TradeFilter {
             order_uid: self.order_uid,
             owner: self.owner,
+            tx_hash: self.tx_hash,
+            sell_token: self.sell_token,
+            buy_token: self.buy_token,
+            from_block: self.from_block,
+            to_block: self.to_block,
         }
     }
 
-    fn validate(&self) -> Result<TradeFilter, TradeFilterError> {
-        match (self.order_uid.as_ref(), self.owner.as_ref()) {
-            (Some(_), None) | (None, Some(_)) => Ok(self.trade_filter()),
-            _ => Err(TradeFilterError::InvalidFilter(
-                "Must specify exactly one of owner and order_uid.".to_owned(),
-            )),
+    fn validate(&self) -> Result<TradeRequest, TradeFilterError> {
+        let invalid = |msg: &str| Err(TradeFilterError::InvalidFilter(msg.to_owned()));
+        let selectors = [
+            self.order_uid.is_some(),
+            self.owner.is_some(),
+            self.tx_hash.is_some(),
+        ];
+        if selectors.into_iter().filter(|selected| *selected).count() != 1 {
+            return invalid("Must specify exactly one of owner, orderUid and txHash.");
         }
+        if self.from_block.is_some() && self.from_timestamp.is_some()
+            || self.to_block.is_some() && self.to_timestamp.is_some()
+        {
+            return invalid("Can't bound the same side of the range by block and timestamp.");
+        }
+        if let Some(limit) = self.limit {
+            if !(MIN_LIMIT..=MAX_LIMIT).contains(&limit) {
+                return invalid(&format!(
+                    "The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."
+                ));
+            }
+        }
+        Ok(TradeRequest {
+            filter: self.trade_filter(),
+            from_timestamp: self.from_timestamp,
+            to_timestamp: self.to_timestamp,
+            offset: self.offset.unwrap_or_default(),
+            limit: self.limit,
+        })
     }
 }
 
 fn get_trades_request(
-) -> impl Filter<Extract = (Result<TradeFilter, TradeFilterError>,), Error = Rejection> + Clone {
+) -> impl Filter<Extract = (Result<TradeRequest, TradeFilterError>,), Error = Rejection> + Clone {
     warp::path!("v1" / "trades")
         .and(warp::get())
         .and(warp::query::<Query>())
         .map(|query: Query| query.validate())
 }
 
-pub fn get_trades(db: Postgres) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+/// Trade events only store the block number, so time bounds are converted into
+/// block bounds with the node before querying the database.
+async fn trades(database: &Postgres, web3: &Web3, request: TradeRequest) -> Result<Vec<Trade>> {
+    let mut filter = request.filter;
+    let Some((from_block, to_block)) =
+        super::blocks_in_time_range(web3, request.from_timestamp, request.to_timestamp).await?
+    else {
+        return Ok(Vec::new());
+    };
+    filter.from_block = filter.from_block.or(from_block);
+    filter.to_block = filter.to_block.or(to_block);
+    database
+        .trades(&filter, request.offset, request.limit)
+        .await
+}
+
+pub fn get_trades(
+    db: Postgres,
+    web3: Web3,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
     get_trades_request().and_then(move |request_result| {
         let database = db.clone();
+        let web3 = web3.clone();
         async move {
             Result::<_, Infallible>::Ok(match request_result {
-                Ok(trade_filter) => {
-                    let result = database.trades(&trade_filter).await.context("get_trades");
+                Ok(request) => {
+                    let result = trades(&database, &web3, request)
+                        .await
+                        .context("get_trades");
                     match result {
                         Ok(reply) => with_status(warp::reply::json(&reply), StatusCode::OK),
                         Err(err) => {
@@ -96,8 +172,8 @@ mod tests {
             .await
             .unwrap()
             .unwrap();
-        assert_eq!(result.owner, Some(owner));
-        assert_eq!(result.order_uid, None);
+        assert_eq!(result.filter.owner, Some(owner));
+        assert_eq!(result.filter.order_uid, None);
 
         let uid = OrderUid([1u8; 56]);
         let order_uid_path = format!("/v1/trades?orderUid={uid}");
@@ -105,8 +181,8 @@ mod tests {
             .await
             .unwrap()
             .unwrap();
-        assert_eq!(result.owner, None);
-        assert_eq!(result.order_uid, Some(uid));
+        assert_eq!(result.filter.owner, None);
+        assert_eq!(result.filter.order_uid, Some(uid));
     }
 
     #[tokio::test]
@@ -126,5 +202,56 @@ mod tests {
         let path = "/v1/trades";
         let result = trade_filter(request().path(path)).await.unwrap();
         assert!(result.is_err());
+
+        let path = format!("/v1/trades?owner=0x{owner:x}&fromBlock=1&fromTimestamp=2");
+        let result = trade_filter(request().path(path.as_str())).await.unwrap();
+        assert!(result.is_err());
+
+        let path = format!("/v1/trades?owner=0x{owner:x}&limit=0");
+        let result = trade_filter(request().path(path.as_str())).await.unwrap();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_trades_request_with_filters() {
+        let owner = H160::from_low_u64_be(1);
+        let token = H160::from_low_u64_be(2);
+        let path = format!(
+            "/v1/trades?owner=0x{owner:x}&sellToken=0x{token:x}&fromBlock=10&toTimestamp=20&\
+             offset=5&limit=100"
+        );
+        let result = request()
+            .path(path.as_str())
+            .method("GET")
+            .filter(&get_trades_request())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            TradeRequest {
+                filter: TradeFilter {
+                    owner: Some(owner),
+                    sell_token: Some(token),
+                    from_block: Some(10),
+                    ..Default::default()
+                },
+                to_timestamp: Some(20),
+                offset: 5,
+                limit: Some(100),
+                ..Default::default()
+            }
+        );
+
+        let hash = H256::from_low_u64_be(3);
+        let path = format!("/v1/trades?txHash=0x{hash:x}");
+        let result = request()
+            .path(path.as_str())
+            .method("GET")
+            .filter(&get_trades_request())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.filter.tx_hash, Some(hash));
     }
 }
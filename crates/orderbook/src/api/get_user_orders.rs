@@ -1,17 +1,61 @@
 use {
-    crate::orderbook::Orderbook,
+    crate::{database::orders::UserOrdersFilter, orderbook::Orderbook},
     anyhow::Result,
+    chrono::{DateTime, Utc},
+    model::order::{OrderClass, OrderStatus, OrderUid},
     primitive_types::H160,
     serde::Deserialize,
+    serde_with::{serde_as, DisplayFromStr},
     shared::api::ApiReply,
     std::{convert::Infallible, sync::Arc},
     warp::{hyper::StatusCode, reply::with_status, Filter, Rejection},
 };
 
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde_as]
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct Query {
     offset: Option<u64>,
     limit: Option<u64>,
+    /// UID of the last order of the previous page. Only orders created before
+    /// it are returned.
+    cursor: Option<OrderUid>,
+    status: Option<OrderStatus>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    class: Option<OrderClass>,
+    sell_token: Option<H160>,
+    buy_token: Option<H160>,
+    /// Unix timestamp (inclusive).
+    created_from: Option<i64>,
+    /// Unix timestamp (exclusive).
+    created_until: Option<i64>,
+}
+
+impl Query {
+    fn filter(&self) -> Result<UserOrdersFilter, &'static str> {
+        let timestamp = |secs: Option<i64>| match secs {
+            Some(secs) => DateTime::<Utc>::from_timestamp(secs, 0)
+                .map(Some)
+                .ok_or("The creation date bounds must be valid unix timestamps."),
+            None => Ok(None),
+        };
+        let filter = UserOrdersFilter {
+            cursor: self.cursor,
+            status: self.status,
+            class: self.class,
+            sell_token: self.sell_token,
+            buy_token: self.buy_token,
+            created_from: timestamp(self.created_from)?,
+            created_until: timestamp(self.created_until)?,
+        };
+        if let (Some(from), Some(until)) = (filter.created_from, filter.created_until) {
+            if from >= until {
+                return Err("createdFrom must be before createdUntil.");
+            }
+        }
+        Ok(filter)
+    }
 }
 
 fn request() -> impl Filter<Extract = (H160, Query), Error = Rejection> + Clone {
@@ -41,7 +85,39 @@ pub fn get_user_orders(
                     StatusCode::BAD_REQUEST,
                 ));
             }
-            let result = orderbook.get_user_orders(&owner, offset, limit).await;
+            let filter = match query.filter() {
+                Ok(filter) => filter,
+                Err(description) => {
+                    return Ok(with_status(
+                        super::error("InvalidFilter", description),
+                        StatusCode::BAD_REQUEST,
+                    ));
+                }
+            };
+            // An unknown cursor would silently result in an empty page.
+            if let Some(cursor) = &filter.cursor {
+                match orderbook.get_order(cursor).await {
+                    Ok(Some(order))
+                        if order.metadata.owner == owner
+                            || order.metadata.onchain_user == Some(owner) => {}
+                    Ok(_) => {
+                        return Ok(with_status(
+                            super::error(
+                                "InvalidCursor",
+                                "The cursor must be the UID of an order of the user.",
+                            ),
+                            StatusCode::BAD_REQUEST,
+                        ));
+                    }
+                    Err(err) => {
+                        tracing::error!(?err, "get_user_orders");
+                        return Ok(shared::api::internal_error_reply());
+                    }
+                }
+            }
+            let result = orderbook
+                .get_user_orders(&owner, offset, limit, &filter)
+                .await;
             Result::<_, Infallible>::Ok(match result {
                 Ok(reply) => with_status(warp::reply::json(&reply), StatusCode::OK),
                 Err(err) => {
@@ -69,6 +145,7 @@ mod tests {
         assert_eq!(result.0, addr!("0000000000000000000000000000000000000001"));
         assert_eq!(result.1.offset, None);
         assert_eq!(result.1.limit, None);
+        assert_eq!(result.1.filter().unwrap(), UserOrdersFilter::default());
 
         let path = "/v1/account/0x0000000000000000000000000000000000000001/orders?offset=1&limit=2";
         let result = warp::test::request()
@@ -80,4 +157,42 @@ mod tests {
         assert_eq!(result.1.offset, Some(1));
         assert_eq!(result.1.limit, Some(2));
     }
+
+    #[tokio::test]
+    async fn request_with_filters() {
+        let uid = OrderUid([0x11; 56]);
+        let path = format!(
+            "/v1/account/0x0000000000000000000000000000000000000001/orders?cursor={uid}&\
+             status=open&class=limit&sellToken=0x0000000000000000000000000000000000000002&\
+             buyToken=0x0000000000000000000000000000000000000003&createdFrom=1&createdUntil=2"
+        );
+        let result = warp::test::request()
+            .path(&path)
+            .method("GET")
+            .filter(&request())
+            .await
+            .unwrap();
+        assert_eq!(
+            result.1.filter().unwrap(),
+            UserOrdersFilter {
+                cursor: Some(uid),
+                status: Some(OrderStatus::Open),
+                class: Some(OrderClass::Limit),
+                sell_token: Some(addr!("0000000000000000000000000000000000000002")),
+                buy_token: Some(addr!("0000000000000000000000000000000000000003")),
+                created_from: DateTime::from_timestamp(1, 0),
+                created_until: DateTime::from_timestamp(2, 0),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_empty_creation_range() {
+        let query = Query {
+            created_from: Some(2),
+            created_until: Some(2),
+            ..Default::default()
+        };
+        assert!(query.filter().is_err());
+    }
 }
@@ -276,6 +276,10 @@ impl IntoWarpReply for AddOrderError {
                 super::error("InvalidReplacement", err.to_string()),
                 StatusCode::UNAUTHORIZED,
             ),
+            err @ AddOrderError::ReplacementInBatch => reply::with_status(
+                super::error("ReplacementInBatch", err.to_string()),
+                StatusCode::BAD_REQUEST,
+            ),
         }
     }
 }
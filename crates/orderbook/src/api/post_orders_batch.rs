@@ -0,0 +1,139 @@
+use {
+    crate::orderbook::{AddOrderError, Orderbook},
+    model::{
+        order::{OrderCreation, OrderUid},
+        quote::QuoteId,
+    },
+    serde::Serialize,
+    shared::api::{error, extract_payload_with_max_size, response_body, ApiReply, IntoWarpReply},
+    std::{convert::Infallible, sync::Arc},
+    warp::{hyper::StatusCode, reply::with_status, Filter, Rejection, Reply},
+};
+
+const MAX_BATCH_SIZE: usize = 100;
+const MAX_BATCH_PAYLOAD: u64 = 1024 * 1024;
+
+fn request() -> impl Filter<Extract = (Vec<OrderCreation>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "batch")
+        .and(warp::post())
+        .and(extract_payload_with_max_size(MAX_BATCH_PAYLOAD))
+}
+
+/// Outcome of a single order of the batch. Failed orders contain the same
+/// error that `POST /v1/orders` would have returned.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderResult {
+    status: u16,
+    #[serde(flatten)]
+    outcome: Outcome,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Outcome {
+    OrderUid(OrderUid),
+    Error(serde_json::Value),
+}
+
+async fn order_result(result: Result<(OrderUid, Option<QuoteId>), AddOrderError>) -> OrderResult {
+    match result {
+        Ok((uid, _)) => OrderResult {
+            status: StatusCode::CREATED.as_u16(),
+            outcome: Outcome::OrderUid(uid),
+        },
+        Err(err) => {
+            let response = err.into_warp_reply().into_response();
+            let status = response.status().as_u16();
+            let body = response_body(response).await;
+            OrderResult {
+                status,
+                outcome: Outcome::Error(serde_json::from_slice(&body).unwrap_or_default()),
+            }
+        }
+    }
+}
+
+async fn response(results: Vec<Result<(OrderUid, Option<QuoteId>), AddOrderError>>) -> ApiReply {
+    let results = futures::future::join_all(results.into_iter().map(order_result)).await;
+    with_status(warp::reply::json(&results), StatusCode::OK)
+}
+
+pub fn post_orders_batch(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    request().and_then(move |orders: Vec<OrderCreation>| {
+        let orderbook = orderbook.clone();
+        async move {
+            if orders.is_empty() || orders.len() > MAX_BATCH_SIZE {
+                return Result::<_, Infallible>::Ok(with_status(
+                    error(
+                        "InvalidBatchSize",
+                        format!("A batch must contain between 1 and {MAX_BATCH_SIZE} orders."),
+                    ),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+
+            let results = orderbook.add_orders(orders).await;
+            for result in &results {
+                match result {
+                    Ok((order_uid, quote_id)) => {
+                        tracing::debug!(%order_uid, ?quote_id, "order created")
+                    }
+                    Err(err) => tracing::debug!(?err, "error creating order in batch"),
+                }
+            }
+            Ok(response(results).await)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json, warp::test::request};
+
+    #[tokio::test]
+    async fn request_ok() {
+        let orders = vec![OrderCreation::default(), OrderCreation::default()];
+        let result = request()
+            .path("/v1/orders/batch")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&orders)
+            .filter(&super::request())
+            .await
+            .unwrap();
+        assert_eq!(result, orders);
+    }
+
+    #[tokio::test]
+    async fn response_contains_result_per_order() {
+        let uid = OrderUid([1u8; 56]);
+        let response = response(vec![
+            Ok((uid, Some(42))),
+            Err(AddOrderError::DuplicatedOrder),
+        ])
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_body(response).await;
+        let body: serde_json::Value = serde_json::from_slice(body.as_slice()).unwrap();
+        assert_eq!(
+            body,
+            json!([
+                {
+                    "status": 201,
+                    "orderUid": uid,
+                },
+                {
+                    "status": 400,
+                    "error": {
+                        "errorType": "DuplicatedOrder",
+                        "description": "order already exists",
+                    },
+                },
+            ])
+        );
+    }
+}
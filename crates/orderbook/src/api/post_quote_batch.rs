@@ -0,0 +1,143 @@
+use {
+    super::post_quote::OrderQuoteErrorWrapper,
+    crate::quoter::{OrderQuoteError, QuoteHandler},
+    model::quote::{OrderQuoteRequest, OrderQuoteResponse},
+    serde::Serialize,
+    shared::api::{error, extract_payload_with_max_size, response_body, ApiReply, IntoWarpReply},
+    std::{convert::Infallible, sync::Arc},
+    warp::{hyper::StatusCode, reply::with_status, Filter, Rejection, Reply},
+};
+
+const MAX_BATCH_SIZE: usize = 100;
+const MAX_BATCH_PAYLOAD: u64 = 1024 * 1024;
+
+fn request() -> impl Filter<Extract = (Vec<OrderQuoteRequest>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "quote" / "batch")
+        .and(warp::post())
+        .and(extract_payload_with_max_size(MAX_BATCH_PAYLOAD))
+}
+
+/// Outcome of a single quote of the batch. Failed quotes contain the same
+/// error that `POST /v1/quote` would have returned.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QuoteResult {
+    status: u16,
+    #[serde(flatten)]
+    outcome: Outcome,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Outcome {
+    Quote(Box<OrderQuoteResponse>),
+    Error(serde_json::Value),
+}
+
+async fn quote_result(result: Result<OrderQuoteResponse, OrderQuoteError>) -> QuoteResult {
+    match result {
+        Ok(quote) => QuoteResult {
+            status: StatusCode::OK.as_u16(),
+            outcome: Outcome::Quote(Box::new(quote)),
+        },
+        Err(err) => {
+            let response = OrderQuoteErrorWrapper(err)
+                .into_warp_reply()
+                .into_response();
+            let status = response.status().as_u16();
+            let body = response_body(response).await;
+            QuoteResult {
+                status,
+                outcome: Outcome::Error(serde_json::from_slice(&body).unwrap_or_default()),
+            }
+        }
+    }
+}
+
+async fn response(results: Vec<Result<OrderQuoteResponse, OrderQuoteError>>) -> ApiReply {
+    let results = futures::future::join_all(results.into_iter().map(quote_result)).await;
+    with_status(warp::reply::json(&results), StatusCode::OK)
+}
+
+pub fn post_quote_batch(
+    quotes: Arc<QuoteHandler>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    request().and_then(move |requests: Vec<OrderQuoteRequest>| {
+        let quotes = quotes.clone();
+        async move {
+            if requests.is_empty() || requests.len() > MAX_BATCH_SIZE {
+                return Result::<_, Infallible>::Ok(with_status(
+                    error(
+                        "InvalidBatchSize",
+                        format!("A batch must contain between 1 and {MAX_BATCH_SIZE} quotes."),
+                    ),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+
+            let results = quotes.calculate_quotes(&requests).await;
+            for (request, result) in requests.iter().zip(&results) {
+                if let Err(err) = result {
+                    tracing::warn!(?err, ?request, "post_quote_batch error");
+                }
+            }
+            Ok(response(results).await)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::json,
+        shared::{order_quoting::CalculateQuoteError, price_estimation::PriceEstimationError},
+        warp::test::request,
+    };
+
+    #[tokio::test]
+    async fn request_ok() {
+        let quote = json!({
+            "from": "0x0101010101010101010101010101010101010101",
+            "sellToken": "0x0202020202020202020202020202020202020202",
+            "buyToken": "0x0303030303030303030303030303030303030303",
+            "kind": "sell",
+            "sellAmountAfterFee": "1337",
+        });
+        let result = request()
+            .path("/v1/quote/batch")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&json!([quote, quote]))
+            .filter(&super::request())
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0],
+            serde_json::from_value::<OrderQuoteRequest>(quote).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn response_contains_result_per_quote() {
+        let response = response(vec![
+            Err(OrderQuoteError::CalculateQuote(CalculateQuoteError::Price(
+                PriceEstimationError::NoLiquidity,
+            ))),
+            Err(OrderQuoteError::CalculateQuote(
+                CalculateQuoteError::QuoteNotVerified,
+            )),
+        ])
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_body(response).await;
+        let body: serde_json::Value = serde_json::from_slice(body.as_slice()).unwrap();
+        assert_eq!(body[0]["status"], json!(404));
+        assert_eq!(body[0]["error"]["errorType"], json!("NoLiquidity"));
+        assert_eq!(body[1]["status"], json!(400));
+        assert_eq!(body[1]["error"]["errorType"], json!("QuoteNotVerified"));
+        assert!(body[0].get("quote").is_none());
+    }
+}
@@ -0,0 +1,240 @@
+use {
+    crate::order_events::OrderEventStream,
+    futures::{Stream, StreamExt},
+    model::{order::OrderUid, order_event::OrderEventUpdate},
+    primitive_types::H160,
+    serde::Deserialize,
+    shared::api::error,
+    std::{collections::HashSet, convert::Infallible, sync::Arc},
+    tokio::sync::broadcast::{self, error::RecvError},
+    warp::{hyper::StatusCode, reply, sse, Filter, Rejection, Reply},
+};
+
+/// Maximum number of orders that can be subscribed to by UID with a single
+/// request.
+const MAX_ORDER_UIDS: usize = 128;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Query {
+    owner: Option<H160>,
+    /// Comma separated list of order UIDs.
+    order_uids: Option<String>,
+}
+
+/// Which order events a client is interested in.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Subscription {
+    owner: Option<H160>,
+    order_uids: HashSet<OrderUid>,
+}
+
+impl Subscription {
+    fn matches(&self, uid: &OrderUid) -> bool {
+        let (_, owner, _) = uid.parts();
+        self.owner == Some(owner) || self.order_uids.contains(uid)
+    }
+}
+
+impl Query {
+    fn validate(self) -> Result<Subscription, String> {
+        let order_uids = self
+            .order_uids
+            .iter()
+            .flat_map(|uids| uids.split(','))
+            .map(|uid| uid.trim().parse::<OrderUid>())
+            .collect::<Result<HashSet<_>, _>>()
+            .map_err(|_| "Invalid order UID.".to_owned())?;
+        if order_uids.len() > MAX_ORDER_UIDS {
+            return Err(format!(
+                "Can not subscribe to more than {MAX_ORDER_UIDS} order UIDs."
+            ));
+        }
+        if self.owner.is_none() && order_uids.is_empty() {
+            return Err("Must specify owner or orderUids.".to_owned());
+        }
+        Ok(Subscription {
+            owner: self.owner,
+            order_uids,
+        })
+    }
+}
+
+/// An item of a subscriber's event stream.
+#[derive(Debug, PartialEq)]
+enum Message {
+    Update(OrderEventUpdate),
+    /// The subscriber fell behind and missed this many events.
+    Lagged(u64),
+}
+
+impl Message {
+    fn into_event(self) -> Result<sse::Event, warp::Error> {
+        match self {
+            Message::Update(update) => sse::Event::default().event("orderEvent").json_data(update),
+            Message::Lagged(skipped) => Ok(sse::Event::default()
+                .event("lagged")
+                .data(skipped.to_string())),
+        }
+    }
+}
+
+fn messages(receiver: broadcast::Receiver<OrderEventUpdate>) -> impl Stream<Item = Message> {
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        let message = match receiver.recv().await {
+            Ok(update) => Message::Update(update),
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::debug!(skipped, "order event subscriber lagged behind");
+                Message::Lagged(skipped)
+            }
+            Err(RecvError::Closed) => return None,
+        };
+        Some((message, receiver))
+    })
+}
+
+fn stream_order_events_request(
+) -> impl Filter<Extract = (Result<Subscription, String>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "stream")
+        .and(warp::get())
+        .and(warp::query::<Query>())
+        .map(Query::validate)
+}
+
+pub fn stream_order_events(
+    events: Arc<OrderEventStream>,
+) -> impl Filter<Extract = (Box<dyn Reply>,), Error = Rejection> + Clone {
+    stream_order_events_request().and_then(move |subscription: Result<Subscription, String>| {
+        let events = events.clone();
+        async move {
+            let subscription = match subscription {
+                Ok(subscription) => subscription,
+                Err(msg) => {
+                    return Result::<_, Infallible>::Ok(Box::new(reply::with_status(
+                        error("InvalidSubscription", msg),
+                        StatusCode::BAD_REQUEST,
+                    )) as Box<dyn Reply>);
+                }
+            };
+
+            // Lagging subscribers get told how many events they missed so that
+            // they know to refetch the state of their orders.
+            let updates = messages(events.subscribe())
+                .filter(move |message| {
+                    futures::future::ready(match message {
+                        Message::Update(update) => subscription.matches(&update.order_uid),
+                        Message::Lagged(_) => true,
+                    })
+                })
+                .map(Message::into_event);
+
+            Ok(Box::new(sse::reply(sse::keep_alive().stream(updates))))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        model::order_event::{OrderEvent, OrderEventLabel},
+        warp::test::request,
+    };
+
+    async fn subscription(path: &str) -> Result<Subscription, String> {
+        request()
+            .path(path)
+            .method("GET")
+            .filter(&stream_order_events_request())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn stream_order_events_request_ok() {
+        let owner = H160::from_low_u64_be(1);
+        let result = subscription(&format!("/v1/orders/stream?owner=0x{owner:x}"))
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            Subscription {
+                owner: Some(owner),
+                order_uids: Default::default(),
+            }
+        );
+
+        let (uid_a, uid_b) = (OrderUid([1; 56]), OrderUid([2; 56]));
+        let result = subscription(&format!("/v1/orders/stream?orderUids={uid_a},{uid_b}"))
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            Subscription {
+                owner: None,
+                order_uids: [uid_a, uid_b].into_iter().collect(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_order_events_request_err() {
+        assert!(subscription("/v1/orders/stream").await.is_err());
+        assert!(subscription("/v1/orders/stream?orderUids=0x01")
+            .await
+            .is_err());
+
+        let too_many = (0..=MAX_ORDER_UIDS)
+            .map(|i| OrderUid([i as u8; 56]).to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(
+            subscription(&format!("/v1/orders/stream?orderUids={too_many}"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn subscription_matches_owner_and_uids() {
+        let owner = H160([1; 20]);
+        let owned = OrderUid::from_parts(Default::default(), owner, 0);
+        let other = OrderUid([2; 56]);
+
+        let by_owner = Subscription {
+            owner: Some(owner),
+            order_uids: Default::default(),
+        };
+        assert!(by_owner.matches(&owned));
+        assert!(!by_owner.matches(&other));
+
+        let by_uid = Subscription {
+            owner: None,
+            order_uids: [other].into_iter().collect(),
+        };
+        assert!(!by_uid.matches(&owned));
+        assert!(by_uid.matches(&other));
+    }
+
+    #[tokio::test]
+    async fn reports_missed_events_to_lagging_subscribers() {
+        let (sender, receiver) = broadcast::channel(1);
+        let update = |uid: u8| OrderEventUpdate {
+            order_uid: OrderUid([uid; 56]),
+            event: OrderEvent {
+                timestamp: Default::default(),
+                label: OrderEventLabel::Created,
+                reason: None,
+            },
+        };
+        for uid in 1..=3 {
+            sender.send(update(uid)).unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(
+            messages(receiver).collect::<Vec<_>>().await,
+            vec![Message::Lagged(2), Message::Update(update(3))]
+        );
+    }
+}
@@ -2,6 +2,7 @@ use {
     primitive_types::H160,
     reqwest::Url,
     shared::{
+        api::rate_limiting::RouteRateLimit,
         arguments::{display_option, display_secret_option},
         bad_token::token_owner_finder,
         http_client,
@@ -147,6 +148,24 @@ pub struct Arguments {
     /// The maximum gas amount a single order can use for getting settled.
     #[clap(long, env, default_value = "8000000")]
     pub max_gas_per_order: u64,
+
+    /// Rate limits for incoming API requests, formatted as
+    /// `[METHOD ]PATH=REQUESTS/INTERVAL[@ip|@api-key]`. For example
+    /// `POST /v1/quote=100/1m@api-key` allows every `X-API-Key` (or IP address
+    /// for requests without a known key) 100 quotes per minute.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub api_rate_limits: Vec<RouteRateLimit>,
+
+    /// The `X-API-Key` values which get their own rate limits. Requests with
+    /// any other key are rate limited by their IP address.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub api_rate_limit_keys: Vec<String>,
+
+    /// Identify clients by the last address of the `X-Forwarded-For` header
+    /// instead of the connection's address when applying rate limits. Only
+    /// enable this when the API is served behind a trusted proxy.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub api_rate_limit_trust_forwarded_for: bool,
 }
 
 impl std::fmt::Display for Arguments {
@@ -178,6 +197,9 @@ impl std::fmt::Display for Arguments {
             app_data_size_limit,
             db_url,
             max_gas_per_order,
+            api_rate_limits,
+            api_rate_limit_keys,
+            api_rate_limit_trust_forwarded_for,
         } = self;
 
         write!(f, "{}", shared)?;
@@ -243,6 +265,13 @@ impl std::fmt::Display for Arguments {
         )?;
         writeln!(f, "app_data_size_limit: {}", app_data_size_limit)?;
         writeln!(f, "max_gas_per_order: {}", max_gas_per_order)?;
+        writeln!(f, "api_rate_limits: {:?}", api_rate_limits)?;
+        writeln!(f, "api_rate_limit_keys: {} keys", api_rate_limit_keys.len())?;
+        writeln!(
+            f,
+            "api_rate_limit_trust_forwarded_for: {}",
+            api_rate_limit_trust_forwarded_for
+        )?;
 
         Ok(())
     }
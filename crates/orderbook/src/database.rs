@@ -1,8 +1,10 @@
 pub mod app_data;
 pub mod auctions;
+pub mod order_events;
 pub mod orders;
 pub mod quotes;
 pub mod solver_competition;
+pub mod solver_rewards;
 pub mod total_surplus;
 pub mod trades;
 
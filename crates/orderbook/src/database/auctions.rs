@@ -1,4 +1,11 @@
-use {crate::dto, anyhow::Result};
+use {
+    crate::dto,
+    anyhow::{Context, Result},
+    database::{auction_prices::TokenPrice, byte_array::ByteArray},
+    model::{auction::AuctionId, order::OrderUid},
+    number::conversions::big_decimal_to_u256,
+    primitive_types::H160,
+};
 
 impl super::Postgres {
     pub async fn most_recent_auction(&self) -> Result<Option<dto::AuctionWithId>> {
@@ -16,4 +23,71 @@ impl super::Postgres {
         let auction = dto::AuctionWithId { id, auction };
         Ok(Some(auction))
     }
+
+    /// Returns the id of the most recent auction and whether it contained the
+    /// given order.
+    pub async fn latest_auction_contains(
+        &self,
+        uid: &OrderUid,
+    ) -> Result<Option<(AuctionId, bool)>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["latest_auction_contains"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        Ok(database::auction_orders::latest_auction_contains(&mut ex, &ByteArray(uid.0)).await?)
+    }
+
+    /// Returns the native prices of a token in the auctions simulated within
+    /// the (inclusive) block range, downsampled to at most `max_points`.
+    pub async fn token_price_history(
+        &self,
+        token: &H160,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        max_points: u64,
+    ) -> Result<Vec<dto::AuctionPrice>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["token_price_history"])
+            .start_timer();
+
+        let block = |block: Option<u64>| block.map(i64::try_from).transpose();
+        let mut ex = self.pool.acquire().await?;
+        database::auction_prices::fetch_token_history(
+            &mut ex,
+            &ByteArray(token.0),
+            block(from_block)?,
+            block(to_block)?,
+            max_points.try_into()?,
+        )
+        .await?
+        .into_iter()
+        .map(auction_price_from)
+        .collect()
+    }
+
+    /// Returns the native price of a token in the most recent auction that
+    /// priced it.
+    pub async fn latest_token_price(&self, token: &H160) -> Result<Option<dto::AuctionPrice>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["latest_token_price"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        database::auction_prices::fetch_latest_token_price(&mut ex, &ByteArray(token.0))
+            .await?
+            .map(auction_price_from)
+            .transpose()
+    }
+}
+
+fn auction_price_from(price: TokenPrice) -> Result<dto::AuctionPrice> {
+    Ok(dto::AuctionPrice {
+        auction_id: price.auction_id,
+        block: price.block_number.try_into().context("negative block")?,
+        price: big_decimal_to_u256(&price.price).context("price is not a u256")?,
+    })
 }
@@ -0,0 +1,58 @@
+use {
+    anyhow::Result,
+    database::{byte_array::ByteArray, order_events::OrderEventLabel as DbOrderEventLabel},
+    model::{
+        order::OrderUid,
+        order_event::{OrderEvent, OrderEventLabel},
+    },
+};
+
+impl super::Postgres {
+    /// Returns the life cycle events of an order sorted by their timestamp.
+    pub async fn order_events(&self, uid: &OrderUid) -> Result<Vec<OrderEvent>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["order_events"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let events = database::order_events::order_events(&mut ex, &ByteArray(uid.0)).await?;
+        Ok(events
+            .into_iter()
+            .map(|event| OrderEvent {
+                timestamp: event.timestamp,
+                label: label_from(event.label),
+                reason: event.reason,
+            })
+            .collect())
+    }
+
+    /// Returns the most recent life cycle event of an order.
+    pub async fn latest_order_event(&self, uid: &OrderUid) -> Result<Option<OrderEvent>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["latest_order_event"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let event = database::order_events::latest_order_event(&mut ex, &ByteArray(uid.0)).await?;
+        Ok(event.map(|event| OrderEvent {
+            timestamp: event.timestamp,
+            label: label_from(event.label),
+            reason: event.reason,
+        }))
+    }
+}
+
+fn label_from(label: DbOrderEventLabel) -> OrderEventLabel {
+    match label {
+        DbOrderEventLabel::Created => OrderEventLabel::Created,
+        DbOrderEventLabel::Ready => OrderEventLabel::Ready,
+        DbOrderEventLabel::Filtered => OrderEventLabel::Filtered,
+        DbOrderEventLabel::Invalid => OrderEventLabel::Invalid,
+        DbOrderEventLabel::Executing => OrderEventLabel::Executing,
+        DbOrderEventLabel::Considered => OrderEventLabel::Considered,
+        DbOrderEventLabel::Traded => OrderEventLabel::Traded,
+        DbOrderEventLabel::Cancelled => OrderEventLabel::Cancelled,
+    }
+}
@@ -57,6 +57,12 @@ use {
 pub trait OrderStoring: Send + Sync {
     async fn insert_order(&self, order: &Order, quote: Option<Quote>)
         -> Result<(), InsertionError>;
+    /// Inserts all orders in a single transaction. Orders that already exist
+    /// are skipped and their UIDs returned.
+    async fn insert_orders(
+        &self,
+        orders: &[(Order, Option<Quote>)],
+    ) -> Result<Vec<OrderUid>, InsertionError>;
     async fn cancel_orders(&self, order_uids: Vec<OrderUid>, now: DateTime<Utc>) -> Result<()>;
     async fn cancel_order(&self, order_uid: &OrderUid, now: DateTime<Utc>) -> Result<()>;
     async fn replace_order(
@@ -74,9 +80,42 @@ pub trait OrderStoring: Send + Sync {
         owner: &H160,
         offset: u64,
         limit: Option<u64>,
+        filter: &UserOrdersFilter,
     ) -> Result<Vec<Order>>;
 }
 
+/// Optional filters for [`OrderStoring::user_orders`]. Unset fields don't
+/// filter anything.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UserOrdersFilter {
+    /// Only return orders created before the order with this UID. Used for
+    /// keyset pagination by passing the UID of the last order of the previous
+    /// page.
+    pub cursor: Option<OrderUid>,
+    pub status: Option<OrderStatus>,
+    pub class: Option<OrderClass>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    /// Inclusive lower bound of the creation date.
+    pub created_from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound of the creation date.
+    pub created_until: Option<DateTime<Utc>>,
+}
+
+impl UserOrdersFilter {
+    fn into_db(self) -> database::orders::UserOrdersFilter {
+        database::orders::UserOrdersFilter {
+            cursor: self.cursor.map(|uid| ByteArray(uid.0)),
+            status: self.status.map(order_status_into),
+            class: self.class.as_ref().map(order_class_into),
+            sell_token: self.sell_token.map(|token| ByteArray(token.0)),
+            buy_token: self.buy_token.map(|token| ByteArray(token.0)),
+            created_from: self.created_from,
+            created_until: self.created_until,
+        }
+    }
+}
+
 pub struct SolvableOrders {
     pub orders: Vec<Order>,
     pub latest_settlement_block: u64,
@@ -109,6 +148,7 @@ async fn cancel_order(
             order_uid: uid,
             timestamp: now,
             label: OrderEventLabel::Cancelled,
+            reason: None,
         },
     )
     .await?;
@@ -123,10 +163,57 @@ async fn insert_order(order: &Order, ex: &mut PgConnection) -> Result<(), Insert
             order_uid: ByteArray(order.metadata.uid.0),
             timestamp: Utc::now(),
             label: OrderEventLabel::Created,
+            reason: None,
         },
     )
     .await?;
-    let interactions = std::iter::empty()
+    let interactions = interactions_into(order);
+    let order = order_into(order);
+
+    database::orders::insert_order(ex, &order)
+        .await
+        .map_err(|err| {
+            if database::orders::is_duplicate_record_error(&err) {
+                InsertionError::DuplicatedRecord
+            } else {
+                InsertionError::DbError(err)
+            }
+        })?;
+    database::orders::insert_interactions(ex, &order.uid, &interactions)
+        .await
+        .map_err(InsertionError::DbError)?;
+
+    Ok(())
+}
+
+fn order_into(order: &Order) -> database::orders::Order {
+    database::orders::Order {
+        uid: ByteArray(order.metadata.uid.0),
+        owner: ByteArray(order.metadata.owner.0),
+        creation_timestamp: order.metadata.creation_date,
+        sell_token: ByteArray(order.data.sell_token.0),
+        buy_token: ByteArray(order.data.buy_token.0),
+        receiver: order.data.receiver.map(|h160| ByteArray(h160.0)),
+        sell_amount: u256_to_big_decimal(&order.data.sell_amount),
+        buy_amount: u256_to_big_decimal(&order.data.buy_amount),
+        valid_to: order.data.valid_to as i64,
+        app_data: ByteArray(order.data.app_data.0),
+        fee_amount: u256_to_big_decimal(&order.data.fee_amount),
+        kind: order_kind_into(order.data.kind),
+        class: order_class_into(&order.metadata.class),
+        partially_fillable: order.data.partially_fillable,
+        signature: order.signature.to_bytes(),
+        signing_scheme: signing_scheme_into(order.signature.scheme()),
+        settlement_contract: ByteArray(order.metadata.settlement_contract.0),
+        sell_token_balance: sell_token_source_into(order.data.sell_token_balance),
+        buy_token_balance: buy_token_destination_into(order.data.buy_token_balance),
+        full_fee_amount: u256_to_big_decimal(&order.metadata.full_fee_amount),
+        cancellation_timestamp: None,
+    }
+}
+
+fn interactions_into(order: &Order) -> Vec<database::orders::Interaction> {
+    std::iter::empty()
         .chain(
             order
                 .interactions
@@ -153,46 +240,7 @@ async fn insert_order(order: &Order, ex: &mut PgConnection) -> Result<(), Insert
                 execution,
             },
         )
-        .collect::<Vec<_>>();
-
-    let order = database::orders::Order {
-        uid: ByteArray(order.metadata.uid.0),
-        owner: ByteArray(order.metadata.owner.0),
-        creation_timestamp: order.metadata.creation_date,
-        sell_token: ByteArray(order.data.sell_token.0),
-        buy_token: ByteArray(order.data.buy_token.0),
-        receiver: order.data.receiver.map(|h160| ByteArray(h160.0)),
-        sell_amount: u256_to_big_decimal(&order.data.sell_amount),
-        buy_amount: u256_to_big_decimal(&order.data.buy_amount),
-        valid_to: order.data.valid_to as i64,
-        app_data: ByteArray(order.data.app_data.0),
-        fee_amount: u256_to_big_decimal(&order.data.fee_amount),
-        kind: order_kind_into(order.data.kind),
-        class: order_class_into(&order.metadata.class),
-        partially_fillable: order.data.partially_fillable,
-        signature: order.signature.to_bytes(),
-        signing_scheme: signing_scheme_into(order.signature.scheme()),
-        settlement_contract: ByteArray(order.metadata.settlement_contract.0),
-        sell_token_balance: sell_token_source_into(order.data.sell_token_balance),
-        buy_token_balance: buy_token_destination_into(order.data.buy_token_balance),
-        full_fee_amount: u256_to_big_decimal(&order.metadata.full_fee_amount),
-        cancellation_timestamp: None,
-    };
-
-    database::orders::insert_order(ex, &order)
-        .await
-        .map_err(|err| {
-            if database::orders::is_duplicate_record_error(&err) {
-                InsertionError::DuplicatedRecord
-            } else {
-                InsertionError::DbError(err)
-            }
-        })?;
-    database::orders::insert_interactions(ex, &order.uid, &interactions)
-        .await
-        .map_err(InsertionError::DbError)?;
-
-    Ok(())
+        .collect()
 }
 
 async fn insert_quote(
@@ -241,6 +289,52 @@ impl OrderStoring for Postgres {
         Ok(())
     }
 
+    async fn insert_orders(
+        &self,
+        orders: &[(Order, Option<Quote>)],
+    ) -> Result<Vec<OrderUid>, InsertionError> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["insert_orders"])
+            .start_timer();
+
+        let mut ex = self.pool.begin().await?;
+
+        // Conflicts are detected by the insertion itself (instead of looking up
+        // the UIDs first) so that orders inserted concurrently don't fail the
+        // whole batch.
+        let mut existing = Vec::new();
+        for (order, quote) in orders {
+            if !database::orders::insert_order_if_new(&mut ex, &order_into(order)).await? {
+                existing.push(order.metadata.uid);
+                continue;
+            }
+            insert_order_event(
+                &mut ex,
+                &OrderEvent {
+                    order_uid: ByteArray(order.metadata.uid.0),
+                    timestamp: Utc::now(),
+                    label: OrderEventLabel::Created,
+                    reason: None,
+                },
+            )
+            .await?;
+            database::orders::insert_interactions(
+                &mut ex,
+                &ByteArray(order.metadata.uid.0),
+                &interactions_into(order),
+            )
+            .await?;
+            if let Some(quote) = quote {
+                insert_quote(&order.metadata.uid, quote, &mut ex).await?;
+            }
+            Self::insert_order_app_data(order, &mut ex).await?;
+        }
+
+        ex.commit().await?;
+        Ok(existing)
+    }
+
     async fn cancel_orders(&self, order_uids: Vec<OrderUid>, now: DateTime<Utc>) -> Result<()> {
         let _timer = super::Metrics::get()
             .database_queries
@@ -336,6 +430,7 @@ impl OrderStoring for Postgres {
         owner: &H160,
         offset: u64,
         limit: Option<u64>,
+        filter: &UserOrdersFilter,
     ) -> Result<Vec<Order>> {
         let _timer = super::Metrics::get()
             .database_queries
@@ -348,6 +443,7 @@ impl OrderStoring for Postgres {
             &ByteArray(owner.0),
             offset as i64,
             limit.map(|l| l as i64),
+            &filter.into_db(),
         )
         .map(|result| match result {
             Ok(order) => full_order_into_model_order(order),
@@ -428,6 +524,16 @@ fn calculate_status(order: &FullOrder) -> OrderStatus {
     OrderStatus::Open
 }
 
+fn order_status_into(status: OrderStatus) -> database::orders::OrderStatus {
+    match status {
+        OrderStatus::PresignaturePending => database::orders::OrderStatus::PresignaturePending,
+        OrderStatus::Open => database::orders::OrderStatus::Open,
+        OrderStatus::Fulfilled => database::orders::OrderStatus::Fulfilled,
+        OrderStatus::Cancelled => database::orders::OrderStatus::Cancelled,
+        OrderStatus::Expired => database::orders::OrderStatus::Expired,
+    }
+}
+
 fn full_order_into_model_order(order: FullOrder) -> Result<Order> {
     let status = calculate_status(&order);
     let pre_interactions = extract_interactions(&order, database::orders::ExecutionTime::Pre)?;
@@ -806,7 +912,7 @@ mod tests {
             .unwrap();
 
         let order_statuses = db
-            .user_orders(&owner, 0, None)
+            .user_orders(&owner, 0, None, &Default::default())
             .await
             .unwrap()
             .iter()
@@ -1034,4 +1140,47 @@ mod tests {
         let interactions = db.single_order(&uid).await.unwrap().unwrap().interactions;
         assert_eq!(interactions, order.interactions);
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_insert_orders_skips_existing() {
+        let db = Postgres::new("postgresql://").unwrap();
+        database::clear_DANGER(&db.pool).await.unwrap();
+
+        let order = |byte: u8| Order {
+            data: OrderData {
+                valid_to: u32::MAX,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                uid: OrderUid([byte; 56]),
+                ..Default::default()
+            },
+            interactions: Interactions {
+                pre: vec![InteractionData {
+                    target: H160([byte; 20]),
+                    value: byte.into(),
+                    call_data: vec![byte],
+                }],
+                post: vec![],
+            },
+            ..Default::default()
+        };
+
+        db.insert_order(&order(1), None).await.unwrap();
+        let existing = db
+            .insert_orders(&[(order(1), None), (order(2), None), (order(3), None)])
+            .await
+            .unwrap();
+        assert_eq!(existing, vec![OrderUid([1; 56])]);
+
+        for byte in [2, 3] {
+            let stored = db
+                .single_order(&OrderUid([byte; 56]))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.interactions, order(byte).interactions);
+        }
+    }
 }
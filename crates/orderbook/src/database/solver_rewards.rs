@@ -0,0 +1,51 @@
+use {
+    super::Postgres,
+    anyhow::{Context, Result},
+    model::solver_rewards::{SolverRewards, Statement},
+    primitive_types::H160,
+};
+
+impl Postgres {
+    /// Loads the reward statements of the accounting period starting at the
+    /// given block or of the latest period if no block is given.
+    pub async fn solver_rewards(&self, start_block: Option<u64>) -> Result<Option<SolverRewards>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["solver_rewards"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let period = match start_block {
+            Some(start_block) => {
+                database::solver_rewards::fetch_period(&mut ex, start_block.try_into()?)
+                    .await
+                    .context("solver_rewards::fetch_period")?
+            }
+            None => database::solver_rewards::latest_period(&mut ex)
+                .await
+                .context("solver_rewards::latest_period")?,
+        };
+        let Some(period) = period else {
+            return Ok(None);
+        };
+        let statements = database::solver_rewards::fetch_statements(&mut ex, period.start_block)
+            .await
+            .context("solver_rewards::fetch_statements")?;
+
+        Ok(Some(SolverRewards {
+            start_block: period.start_block.try_into()?,
+            end_block: period.end_block.try_into()?,
+            statements: statements
+                .into_iter()
+                .map(|statement| {
+                    Ok(Statement {
+                        solver: H160(statement.solver.0),
+                        auctions: statement.auctions.try_into()?,
+                        settled: statement.settled.try_into()?,
+                        reward: statement.reward,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        }))
+    }
+}
@@ -12,7 +12,14 @@ use {
 
 #[async_trait::async_trait]
 pub trait TradeRetrieving: Send + Sync {
-    async fn trades(&self, filter: &TradeFilter) -> Result<Vec<Trade>>;
+    /// Trades matching the filter ordered by their on-chain position (oldest
+    /// first).
+    async fn trades(
+        &self,
+        filter: &TradeFilter,
+        offset: u64,
+        limit: Option<u64>,
+    ) -> Result<Vec<Trade>>;
 }
 
 /// Any default value means that this field is unfiltered.
@@ -20,21 +27,43 @@ pub trait TradeRetrieving: Send + Sync {
 pub struct TradeFilter {
     pub owner: Option<H160>,
     pub order_uid: Option<OrderUid>,
+    pub tx_hash: Option<H256>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    /// Inclusive.
+    pub from_block: Option<u64>,
+    /// Inclusive.
+    pub to_block: Option<u64>,
 }
 
 #[async_trait::async_trait]
 impl TradeRetrieving for Postgres {
-    async fn trades(&self, filter: &TradeFilter) -> Result<Vec<Trade>> {
+    async fn trades(
+        &self,
+        filter: &TradeFilter,
+        offset: u64,
+        limit: Option<u64>,
+    ) -> Result<Vec<Trade>> {
         let _timer = super::Metrics::get()
             .database_queries
             .with_label_values(&["trades"])
             .start_timer();
 
         let mut ex = self.pool.acquire().await?;
+        let filter = database::trades::TradesFilter {
+            owner: filter.owner.map(|owner| ByteArray(owner.0)),
+            order_uid: filter.order_uid.map(|uid| ByteArray(uid.0)),
+            tx_hash: filter.tx_hash.map(|hash| ByteArray(hash.0)),
+            sell_token: filter.sell_token.map(|token| ByteArray(token.0)),
+            buy_token: filter.buy_token.map(|token| ByteArray(token.0)),
+            from_block: filter.from_block.map(|block| block as i64),
+            to_block: filter.to_block.map(|block| block as i64),
+        };
         database::trades::trades(
             &mut ex,
-            filter.owner.map(|owner| ByteArray(owner.0)).as_ref(),
-            filter.order_uid.map(|uid| ByteArray(uid.0)).as_ref(),
+            &filter,
+            offset as i64,
+            limit.map(|limit| limit as i64),
         )
         .map(|result| match result {
             Ok(row) => trade_from(row),
@@ -30,3 +30,15 @@ pub struct AuctionWithId {
     #[serde(flatten)]
     pub auction: Auction,
 }
+
+/// Native price of a token in an auction.
+#[serde_as]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuctionPrice {
+    pub auction_id: AuctionId,
+    /// Block at which the solutions of the auction were simulated.
+    pub block: u64,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub price: U256,
+}
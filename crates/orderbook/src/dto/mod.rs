@@ -2,6 +2,6 @@ pub mod auction;
 pub mod order;
 
 pub use {
-    auction::{Auction, AuctionId, AuctionWithId},
+    auction::{Auction, AuctionId, AuctionPrice, AuctionWithId},
     order::Order,
 };
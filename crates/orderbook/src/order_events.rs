@@ -0,0 +1,82 @@
+//! Pushes order events to API subscribers as soon as they get stored in the
+//! database.
+//!
+//! Every insert into the `order_events` table gets published on a Postgres
+//! notification channel. A single background task listens on that channel and
+//! broadcasts the events to all currently connected subscribers.
+
+use {
+    anyhow::{Context, Result},
+    model::order_event::OrderEventUpdate,
+    sqlx::{postgres::PgListener, PgPool},
+    std::{sync::Arc, time::Duration},
+    tokio::sync::broadcast,
+    tracing::Instrument,
+};
+
+/// How many events can be buffered per subscriber before slow subscribers
+/// start missing events.
+const CAPACITY: usize = 1024;
+
+/// How long to wait before trying to listen for notifications again after the
+/// listener failed.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+pub struct OrderEventStream {
+    sender: broadcast::Sender<OrderEventUpdate>,
+}
+
+impl OrderEventStream {
+    /// Creates a new stream and spawns the background task forwarding database
+    /// notifications to subscribers.
+    pub fn new(pool: PgPool) -> Arc<Self> {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        tokio::task::spawn(
+            listen(pool, sender.clone()).instrument(tracing::info_span!("order_event_stream")),
+        );
+        Arc::new(Self { sender })
+    }
+
+    /// Returns a receiver for all order events that get stored from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderEventUpdate> {
+        self.sender.subscribe()
+    }
+}
+
+async fn listen(pool: PgPool, sender: broadcast::Sender<OrderEventUpdate>) {
+    loop {
+        if let Err(err) = forward_notifications(&pool, &sender).await {
+            tracing::warn!(?err, "failed to listen for order events");
+        }
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
+
+async fn forward_notifications(
+    pool: &PgPool,
+    sender: &broadcast::Sender<OrderEventUpdate>,
+) -> Result<()> {
+    let mut listener = PgListener::connect_with(pool)
+        .await
+        .context("connect listener")?;
+    listener
+        .listen(database::order_events::NOTIFICATION_CHANNEL)
+        .await
+        .context("listen")?;
+    tracing::debug!("listening for order events");
+
+    loop {
+        let notification = listener.recv().await.context("receive notification")?;
+        match serde_json::from_str::<OrderEventUpdate>(notification.payload()) {
+            // Sending only fails if there are currently no subscribers.
+            Ok(update) => {
+                let _ = sender.send(update);
+            }
+            Err(err) => tracing::warn!(
+                ?err,
+                payload = notification.payload(),
+                "failed to parse order event notification"
+            ),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use {
     crate::{
-        database::orders::{InsertionError, OrderStoring},
+        database::orders::{InsertionError, OrderStoring, UserOrdersFilter},
         dto,
+        solver_competition::{Identifier, LoadSolverCompetitionError, SolverCompetitionStoring},
     },
     anyhow::{Context, Result},
     app_data::{AppDataHash, Validator},
@@ -14,20 +15,37 @@ use {
             OrderClass,
             OrderCreation,
             OrderCreationAppData,
+            OrderKind,
             OrderStatus,
+            OrderStatusDetails,
             OrderUid,
             SignedOrderCancellations,
         },
         quote::QuoteId,
+        signature::{hashed_eip712_message, Signature},
         DomainSeparator,
     },
+    num::ToPrimitive,
+    number::conversions::u256_to_big_rational,
     primitive_types::H160,
     shared::{
         metrics::LivenessChecking,
         order_quoting::Quote,
-        order_validation::{OrderValidating, ValidationError},
+        order_validation::{
+            is_order_outside_market_price,
+            Amounts,
+            LimitOrderCounting,
+            OrderValidating,
+            ValidationError,
+        },
+        remaining_amounts,
+        signature_validator::{SignatureCheck, SignatureValidating, SignatureValidationError},
+    },
+    std::{
+        borrow::Cow,
+        collections::{HashMap, HashSet},
+        sync::Arc,
     },
-    std::{borrow::Cow, sync::Arc},
     thiserror::Error,
 };
 
@@ -98,6 +116,8 @@ pub enum AddOrderError {
     InvalidAppData(#[source] anyhow::Error),
     #[error("the new order is not a valid replacement for the old one")]
     InvalidReplacement,
+    #[error("replacement orders can't be placed in a batch")]
+    ReplacementInBatch,
     #[error(
         "contract app data {contract_app_data:?} is associated with full app data {existing:?} \
          which is different from the provided {provided:?}"
@@ -156,6 +176,8 @@ pub enum OrderCancellationError {
     OrderExpired,
     #[error("on-chain orders cannot be cancelled with off-chain signature")]
     OnChainOrder,
+    #[error("EIP-1271 signature was rejected by the order owner")]
+    InvalidEip1271Signature,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -166,6 +188,8 @@ pub struct Orderbook {
     database: crate::database::Postgres,
     order_validator: Arc<dyn OrderValidating>,
     app_data: Arc<crate::app_data::Registry>,
+    signature_validator: Arc<dyn SignatureValidating>,
+    max_limit_orders_per_user: u64,
 }
 
 impl Orderbook {
@@ -176,6 +200,8 @@ impl Orderbook {
         database: crate::database::Postgres,
         order_validator: Arc<dyn OrderValidating>,
         app_data: Arc<crate::app_data::Registry>,
+        signature_validator: Arc<dyn SignatureValidating>,
+        max_limit_orders_per_user: u64,
     ) -> Self {
         Metrics::initialize();
         Self {
@@ -184,6 +210,8 @@ impl Orderbook {
             database,
             order_validator,
             app_data,
+            signature_validator,
+            max_limit_orders_per_user,
         }
     }
 
@@ -191,6 +219,139 @@ impl Orderbook {
         &self,
         payload: OrderCreation,
     ) -> Result<(OrderUid, Option<QuoteId>), AddOrderError> {
+        let (order, quote, replaced_order) = self.validate_order(payload).await?;
+
+        // Check if it has to replace an existing order
+        if let Some(old_order) = replaced_order {
+            self.replace_order(order, old_order, quote).await
+        } else {
+            let quote_id = quote.as_ref().and_then(|quote| quote.id);
+
+            self.database
+                .insert_order(&order, quote)
+                .await
+                .map_err(|err| AddOrderError::from_insertion(err, &order))?;
+            Metrics::on_order_operation(&order, OrderOperation::Created);
+
+            Ok((order.metadata.uid, quote_id))
+        }
+    }
+
+    /// Adds multiple orders at once. Every order is validated on its own and
+    /// all valid orders get stored in a single database transaction. The
+    /// results are in the same order as the payloads.
+    pub async fn add_orders(
+        &self,
+        payloads: Vec<OrderCreation>,
+    ) -> Vec<Result<(OrderUid, Option<QuoteId>), AddOrderError>> {
+        let validated = futures::future::join_all(payloads.into_iter().map(|payload| async {
+            match self.validate_order(payload).await? {
+                (order, quote, None) => Ok((order, quote)),
+                (_, _, Some(_)) => Err(AddOrderError::ReplacementInBatch),
+            }
+        }))
+        .await;
+
+        let mut results = Vec::with_capacity(validated.len());
+        let mut accepted = Vec::new();
+        let mut uids = HashSet::new();
+        let mut limit_orders = HashMap::new();
+        for result in validated {
+            match result {
+                Ok((order, _)) if !uids.insert(order.metadata.uid) => {
+                    results.push(Err(AddOrderError::DuplicatedOrder))
+                }
+                Ok((order, quote)) => {
+                    if let Err(err) = self
+                        .count_batch_limit_order(&mut limit_orders, &order, quote.as_ref())
+                        .await
+                    {
+                        results.push(Err(err));
+                        continue;
+                    }
+                    let quote_id = quote.as_ref().and_then(|quote| quote.id);
+                    results.push(Ok((order.metadata.uid, quote_id)));
+                    accepted.push((results.len() - 1, (order, quote)));
+                }
+                Err(err) => results.push(Err(err)),
+            }
+        }
+        if accepted.is_empty() {
+            return results;
+        }
+
+        let (indices, orders): (Vec<_>, Vec<_>) = accepted.into_iter().unzip();
+        match self.database.insert_orders(&orders).await {
+            Ok(existing) => {
+                for (index, (order, _)) in indices.into_iter().zip(&orders) {
+                    if existing.contains(&order.metadata.uid) {
+                        results[index] = Err(AddOrderError::DuplicatedOrder);
+                    } else {
+                        Metrics::on_order_operation(order, OrderOperation::Created);
+                    }
+                }
+            }
+            Err(err) => {
+                tracing::warn!(?err, "failed to insert order batch");
+                let err = format!("{err:?}");
+                for index in indices {
+                    results[index] = Err(AddOrderError::Database(anyhow::anyhow!(
+                        "batch insertion failed: {err}"
+                    )));
+                }
+            }
+        }
+        results
+    }
+
+    /// Counts a limit order of a batch against the cap of open limit orders of
+    /// its owner. Order validation only counts the already stored orders, so
+    /// without this a single batch could exceed the cap.
+    async fn count_batch_limit_order(
+        &self,
+        counts: &mut HashMap<H160, u64>,
+        order: &Order,
+        quote: Option<&Quote>,
+    ) -> Result<(), AddOrderError> {
+        let outside_market_price = quote.map_or(true, |quote| {
+            is_order_outside_market_price(
+                &Amounts {
+                    sell: order.data.sell_amount,
+                    buy: order.data.buy_amount,
+                    fee: order.data.fee_amount,
+                },
+                &Amounts {
+                    sell: quote.sell_amount,
+                    buy: quote.buy_amount,
+                    fee: quote.fee_amount,
+                },
+                order.data.kind,
+            )
+        });
+        if order.metadata.class != OrderClass::Limit || !outside_market_price {
+            return Ok(());
+        }
+
+        let owner = order.metadata.owner;
+        let count = match counts.get(&owner) {
+            Some(count) => *count,
+            None => self.database.count(owner).await?,
+        };
+        if count >= self.max_limit_orders_per_user {
+            return Err(AddOrderError::OrderValidation(
+                ValidationError::TooManyLimitOrders,
+            ));
+        }
+        counts.insert(owner, count + 1);
+        Ok(())
+    }
+
+    /// Validates an order and constructs it. Also returns the quote the order
+    /// was created with and the existing order it replaces, if any.
+    async fn validate_order(
+        &self,
+        payload: OrderCreation,
+    ) -> Result<(Order, Option<Quote>, Option<Order>), AddOrderError> {
         let full_app_data_override = match payload.app_data {
             OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await?,
             _ => None,
@@ -210,20 +371,7 @@ impl Orderbook {
             )
             .await?;
 
-        // Check if it has to replace an existing order
-        if let Some(old_order) = replaced_order {
-            self.replace_order(order, old_order, quote).await
-        } else {
-            let quote_id = quote.as_ref().and_then(|quote| quote.id);
-
-            self.database
-                .insert_order(&order, quote)
-                .await
-                .map_err(|err| AddOrderError::from_insertion(err, &order))?;
-            Metrics::on_order_operation(&order, OrderOperation::Created);
-
-            Ok((order.metadata.uid, quote_id))
-        }
+        Ok((order, quote, replaced_order))
     }
 
     /// Finds an order for cancellation.
@@ -238,19 +386,47 @@ impl Orderbook {
             .single_order(order_uid)
             .await?
             .ok_or(OrderCancellationError::OrderNotFound)?;
+        ensure_cancellable_off_chain(&order)?;
+        Ok(order)
+    }
 
-        match order.metadata.status {
-            OrderStatus::PresignaturePending => return Err(OrderCancellationError::OnChainOrder),
-            OrderStatus::Open if !order.signature.scheme().is_ecdsa_scheme() => {
-                return Err(OrderCancellationError::OnChainOrder);
+    /// Verifies that a cancellation was signed by the owner of the cancelled
+    /// orders. ECDSA signatures have to be signed by the owner itself while
+    /// EIP-1271 signatures get verified by calling the owner contract.
+    async fn verify_cancellation_signer(
+        &self,
+        owner: H160,
+        recovered_signer: Option<H160>,
+        signature: &Signature,
+        struct_hash: &[u8; 32],
+    ) -> Result<(), OrderCancellationError> {
+        match (signature, recovered_signer) {
+            (_, Some(signer)) if signer == owner => Ok(()),
+            (_, Some(_)) => Err(OrderCancellationError::WrongOwner),
+            (Signature::Eip1271(bytes), None) => {
+                let check = SignatureCheck {
+                    signer: owner,
+                    hash: hashed_eip712_message(&self.domain_separator, struct_hash),
+                    signature: bytes.clone(),
+                    interactions: Vec::new(),
+                };
+                match self
+                    .signature_validator
+                    .validate_signatures(vec![check])
+                    .await
+                    .pop()
+                    .expect("one result per signature check")
+                {
+                    Ok(()) => Ok(()),
+                    Err(SignatureValidationError::Invalid) => {
+                        Err(OrderCancellationError::InvalidEip1271Signature)
+                    }
+                    Err(SignatureValidationError::Other(err)) => Err(err.into()),
+                }
             }
-            OrderStatus::Fulfilled => return Err(OrderCancellationError::OrderFullyExecuted),
-            OrderStatus::Cancelled => return Err(OrderCancellationError::AlreadyCancelled),
-            OrderStatus::Expired => return Err(OrderCancellationError::OrderExpired),
-            _ => {}
+            // Cancellations can't be pre-signed.
+            (_, None) => Err(OrderCancellationError::InvalidSignature),
         }
-
-        Ok(order)
     }
 
     pub async fn cancel_orders(
@@ -263,12 +439,23 @@ impl Orderbook {
         }
 
         // Verify the cancellation signer is the same as the order signers
+        let owner = match orders.first() {
+            Some(order) => order.metadata.owner,
+            None => return Ok(()),
+        };
+        if orders.iter().any(|order| order.metadata.owner != owner) {
+            return Err(OrderCancellationError::WrongOwner);
+        }
         let signer = cancellation
-            .validate(&self.domain_separator)
+            .recover_signer(&self.domain_separator)
             .map_err(|_| OrderCancellationError::InvalidSignature)?;
-        if orders.iter().any(|order| signer != order.metadata.owner) {
-            return Err(OrderCancellationError::WrongOwner);
-        };
+        self.verify_cancellation_signer(
+            owner,
+            signer,
+            &cancellation.signature,
+            &cancellation.data.hash_struct(),
+        )
+        .await?;
 
         // orders are already known to exist in DB at this point, and signer is
         // known to be correct!
@@ -294,11 +481,15 @@ impl Orderbook {
 
         // Verify the cancellation signer is the same as the order signer.
         let signer = cancellation
-            .validate(&self.domain_separator)
+            .recover_signer(&self.domain_separator)
             .map_err(|_| OrderCancellationError::InvalidSignature)?;
-        if signer != order.metadata.owner {
-            return Err(OrderCancellationError::WrongOwner);
-        };
+        self.verify_cancellation_signer(
+            order.metadata.owner,
+            signer,
+            &cancellation.signature,
+            &cancellation.hash_struct(),
+        )
+        .await?;
 
         // order is already known to exist in DB at this point, and signer is
         // known to be correct!
@@ -377,6 +568,48 @@ impl Orderbook {
         self.database.single_order(uid).await
     }
 
+    /// Returns where the order currently stands in the settlement process.
+    pub async fn get_order_status(&self, uid: &OrderUid) -> Result<Option<OrderStatusDetails>> {
+        let Some(order) = self.database.single_order(uid).await? else {
+            return Ok(None);
+        };
+        let (latest_event, latest_auction) = futures::try_join!(
+            self.database.latest_order_event(uid),
+            self.database.latest_auction_contains(uid),
+        )?;
+
+        let proposed_by = match latest_auction {
+            Some((auction_id, _)) => {
+                match self
+                    .database
+                    .load_competition(Identifier::Id(auction_id))
+                    .await
+                {
+                    Ok(competition) => competition
+                        .common
+                        .solutions
+                        .into_iter()
+                        .filter(|solution| solution.orders.iter().any(|o| o.id() == uid))
+                        .map(|solution| solution.solver)
+                        .collect(),
+                    // The competition gets stored after the auction has been solved.
+                    Err(LoadSolverCompetitionError::NotFound) => Vec::new(),
+                    Err(LoadSolverCompetitionError::Other(err)) => return Err(err),
+                }
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Some(OrderStatusDetails {
+            status: order.metadata.status,
+            fill_ratio: fill_ratio(&order)?,
+            latest_event,
+            latest_auction_id: latest_auction.map(|(id, _)| id),
+            in_latest_auction: latest_auction.is_some_and(|(_, contained)| contained),
+            proposed_by,
+        }))
+    }
+
     pub async fn get_orders_for_tx(&self, hash: &H256) -> Result<Vec<Order>> {
         self.database.orders_for_tx(hash).await
     }
@@ -397,14 +630,33 @@ impl Orderbook {
         owner: &H160,
         offset: u64,
         limit: u64,
+        filter: &UserOrdersFilter,
     ) -> Result<Vec<Order>> {
         self.database
-            .user_orders(owner, offset, Some(limit))
+            .user_orders(owner, offset, Some(limit), filter)
             .await
             .context("get_user_orders error")
     }
 }
 
+/// Returns the executed fraction of an order between 0 and 1.
+fn fill_ratio(order: &Order) -> Result<f64> {
+    let total = match order.data.kind {
+        OrderKind::Buy => order.data.buy_amount,
+        OrderKind::Sell => order.data.sell_amount,
+    };
+    if total.is_zero() {
+        return Ok(0.);
+    }
+    let remaining = remaining_amounts::Remaining::from_order(&order.into())?.remaining(total)?;
+    let executed = total.saturating_sub(remaining);
+    Ok(
+        (u256_to_big_rational(&executed) / u256_to_big_rational(&total))
+            .to_f64()
+            .unwrap_or_default(),
+    )
+}
+
 #[async_trait::async_trait]
 impl LivenessChecking for Orderbook {
     async fn is_alive(&self) -> bool {
@@ -412,6 +664,24 @@ impl LivenessChecking for Orderbook {
     }
 }
 
+/// Checks that an order can be cancelled with a signed off-chain cancellation.
+fn ensure_cancellable_off_chain(order: &Order) -> Result<(), OrderCancellationError> {
+    // Orders placed with an on-chain transaction (e.g. EthFlow orders) and
+    // pre-signed orders can only be cancelled on-chain. Other EIP-1271 orders
+    // can be cancelled with a signature the owner contract accepts.
+    if order.metadata.onchain_order_data.is_some() || matches!(order.signature, Signature::PreSign)
+    {
+        return Err(OrderCancellationError::OnChainOrder);
+    }
+    match order.metadata.status {
+        OrderStatus::PresignaturePending => Err(OrderCancellationError::OnChainOrder),
+        OrderStatus::Fulfilled => Err(OrderCancellationError::OrderFullyExecuted),
+        OrderStatus::Cancelled => Err(OrderCancellationError::AlreadyCancelled),
+        OrderStatus::Expired => Err(OrderCancellationError::OrderExpired),
+        _ => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -421,11 +691,171 @@ mod tests {
         mockall::predicate::eq,
         model::{
             order::{OrderData, OrderMetadata},
-            signature::Signature,
+            signature::{Signature, SigningScheme},
+        },
+        shared::{
+            order_validation::MockOrderValidating,
+            signature_validator::MockSignatureValidating,
         },
-        shared::order_validation::MockOrderValidating,
     };
 
+    fn orderbook_with_signature_validator(validator: MockSignatureValidating) -> Orderbook {
+        let database = crate::database::Postgres::new("postgresql://").unwrap();
+        Orderbook {
+            app_data: Arc::new(crate::app_data::Registry::new(
+                Validator::new(8192),
+                database.clone(),
+                None,
+            )),
+            database,
+            order_validator: Arc::new(MockOrderValidating::new()),
+            domain_separator: Default::default(),
+            settlement_contract: H160([0xba; 20]),
+            signature_validator: Arc::new(validator),
+            max_limit_orders_per_user: u64::MAX,
+        }
+    }
+
+    #[tokio::test]
+    async fn verifies_ecdsa_cancellation_signer() {
+        let orderbook = orderbook_with_signature_validator(MockSignatureValidating::new());
+        let owner = H160([1; 20]);
+        let signature = Signature::default_with(SigningScheme::Eip712);
+
+        assert!(orderbook
+            .verify_cancellation_signer(owner, Some(owner), &signature, &[0; 32])
+            .await
+            .is_ok());
+        assert!(matches!(
+            orderbook
+                .verify_cancellation_signer(owner, Some(H160([2; 20])), &signature, &[0; 32])
+                .await,
+            Err(OrderCancellationError::WrongOwner)
+        ));
+        assert!(matches!(
+            orderbook
+                .verify_cancellation_signer(owner, None, &Signature::PreSign, &[0; 32])
+                .await,
+            Err(OrderCancellationError::InvalidSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn verifies_eip1271_cancellation_with_owner_contract() {
+        let owner = H160([1; 20]);
+        let cancellation = OrderCancellation {
+            order_uid: OrderUid([2; 56]),
+            signature: Signature::Eip1271(vec![1, 2, 3]),
+        };
+        let struct_hash = cancellation.hash_struct();
+
+        let mut validator = MockSignatureValidating::new();
+        validator
+            .expect_validate_signatures()
+            .with(eq(vec![SignatureCheck {
+                signer: owner,
+                hash: hashed_eip712_message(&Default::default(), &struct_hash),
+                signature: vec![1, 2, 3],
+                interactions: Vec::new(),
+            }]))
+            .times(1)
+            .returning(|_| vec![Ok(())]);
+        let orderbook = orderbook_with_signature_validator(validator);
+        assert!(orderbook
+            .verify_cancellation_signer(owner, None, &cancellation.signature, &struct_hash)
+            .await
+            .is_ok());
+
+        let mut validator = MockSignatureValidating::new();
+        validator
+            .expect_validate_signatures()
+            .returning(|_| vec![Err(SignatureValidationError::Invalid)]);
+        let orderbook = orderbook_with_signature_validator(validator);
+        assert!(matches!(
+            orderbook
+                .verify_cancellation_signer(owner, None, &cancellation.signature, &struct_hash)
+                .await,
+            Err(OrderCancellationError::InvalidEip1271Signature)
+        ));
+    }
+
+    #[test]
+    fn only_allows_off_chain_cancellations_of_signed_orders() {
+        let order = |signature: Signature, status: OrderStatus| Order {
+            metadata: OrderMetadata {
+                status,
+                ..Default::default()
+            },
+            signature,
+            ..Default::default()
+        };
+
+        assert!(ensure_cancellable_off_chain(&order(
+            Signature::default_with(SigningScheme::Eip712),
+            OrderStatus::Open
+        ))
+        .is_ok());
+        assert!(ensure_cancellable_off_chain(&order(
+            Signature::Eip1271(vec![1, 2, 3]),
+            OrderStatus::Open
+        ))
+        .is_ok());
+        assert!(matches!(
+            ensure_cancellable_off_chain(&order(Signature::PreSign, OrderStatus::Open)),
+            Err(OrderCancellationError::OnChainOrder)
+        ));
+        assert!(matches!(
+            ensure_cancellable_off_chain(&order(
+                Signature::PreSign,
+                OrderStatus::PresignaturePending
+            )),
+            Err(OrderCancellationError::OnChainOrder)
+        ));
+        assert!(matches!(
+            ensure_cancellable_off_chain(&Order {
+                metadata: OrderMetadata {
+                    onchain_order_data: Some(Default::default()),
+                    ..Default::default()
+                },
+                signature: Signature::Eip1271(vec![1, 2, 3]),
+                ..Default::default()
+            }),
+            Err(OrderCancellationError::OnChainOrder)
+        ));
+        assert!(matches!(
+            ensure_cancellable_off_chain(&order(
+                Signature::Eip1271(vec![1, 2, 3]),
+                OrderStatus::Fulfilled
+            )),
+            Err(OrderCancellationError::OrderFullyExecuted)
+        ));
+    }
+
+    #[test]
+    fn computes_fill_ratio() {
+        let order = |partially_fillable: bool, executed: u64| Order {
+            data: OrderData {
+                kind: OrderKind::Sell,
+                sell_amount: 100.into(),
+                buy_amount: 50.into(),
+                partially_fillable,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                executed_sell_amount_before_fees: executed.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(fill_ratio(&order(true, 0)).unwrap(), 0.);
+        assert_eq!(fill_ratio(&order(true, 25)).unwrap(), 0.25);
+        assert_eq!(fill_ratio(&order(true, 100)).unwrap(), 1.);
+        assert_eq!(fill_ratio(&order(false, 0)).unwrap(), 0.);
+        assert_eq!(fill_ratio(&order(false, 100)).unwrap(), 1.);
+        assert_eq!(fill_ratio(&Order::default()).unwrap(), 0.);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_replace_order_verifies_signer_and_app_data() {
@@ -486,6 +916,8 @@ mod tests {
             domain_separator: Default::default(),
             settlement_contract: H160([0xba; 20]),
             app_data,
+            signature_validator: Arc::new(MockSignatureValidating::new()),
+            max_limit_orders_per_user: u64::MAX,
         };
 
         // Different owner
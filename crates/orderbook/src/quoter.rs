@@ -1,6 +1,7 @@
 use {
     crate::app_data,
     chrono::{TimeZone, Utc},
+    futures::{stream, StreamExt},
     model::{
         order::OrderCreationAppData,
         quote::{OrderQuote, OrderQuoteRequest, OrderQuoteResponse, PriceQuality},
@@ -13,10 +14,16 @@ use {
             PartialValidationError,
             PreOrderData,
         },
-        price_estimation::Verification,
+        price_estimation::{native::NativePriceEstimating, PriceEstimationError, Verification},
         trade_finding,
     },
-    std::sync::Arc,
+    std::{
+        collections::HashSet,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    },
     thiserror::Error,
 };
 
@@ -26,19 +33,25 @@ pub struct QuoteHandler {
     optimal_quoter: Arc<dyn OrderQuoting>,
     fast_quoter: Arc<dyn OrderQuoting>,
     app_data: Arc<app_data::Registry>,
+    native_price_estimator: Arc<dyn NativePriceEstimating>,
 }
 
+/// How many quotes of a batch get computed at the same time.
+const BATCH_CONCURRENCY: usize = 10;
+
 impl QuoteHandler {
     pub fn new(
         order_validator: Arc<dyn OrderValidating>,
         quoter: Arc<dyn OrderQuoting>,
         app_data: Arc<app_data::Registry>,
+        native_price_estimator: Arc<dyn NativePriceEstimating>,
     ) -> Self {
         Self {
             order_validator,
             optimal_quoter: quoter.clone(),
             fast_quoter: quoter,
             app_data,
+            native_price_estimator,
         }
     }
 
@@ -133,6 +146,57 @@ impl QuoteHandler {
         tracing::debug!(?response, "finished computing quote");
         Ok(response)
     }
+
+    /// Computes quotes for all requests concurrently. The results are in the
+    /// same order as the requests.
+    ///
+    /// Native prices of all involved tokens get fetched once up front so the
+    /// individual quotes can use the cached prices instead of issuing the same
+    /// requests again. Once the price estimators report that they are rate
+    /// limited the remaining quotes fail right away instead of adding to the
+    /// load.
+    pub async fn calculate_quotes(
+        &self,
+        requests: &[OrderQuoteRequest],
+    ) -> Vec<Result<OrderQuoteResponse, OrderQuoteError>> {
+        let tokens: HashSet<_> = requests
+            .iter()
+            .flat_map(|request| [request.sell_token, request.buy_token])
+            .collect();
+        stream::iter(tokens)
+            .for_each_concurrent(BATCH_CONCURRENCY, |token| async move {
+                // Errors get reported by the individual quotes.
+                let _ = self
+                    .native_price_estimator
+                    .estimate_native_price(token)
+                    .await;
+            })
+            .await;
+
+        let rate_limited = AtomicBool::new(false);
+        stream::iter(requests)
+            .map(|request| {
+                let rate_limited = &rate_limited;
+                async move {
+                    if rate_limited.load(Ordering::Relaxed) {
+                        return Err(OrderQuoteError::CalculateQuote(CalculateQuoteError::Price(
+                            PriceEstimationError::RateLimited,
+                        )));
+                    }
+                    let result = self.calculate_quote(request).await;
+                    if let Err(OrderQuoteError::CalculateQuote(CalculateQuoteError::Price(
+                        PriceEstimationError::RateLimited,
+                    ))) = &result
+                    {
+                        rate_limited.store(true, Ordering::Relaxed);
+                    }
+                    result
+                }
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
 }
 
 /// Result from handling a quote request.
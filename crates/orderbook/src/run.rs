@@ -5,6 +5,7 @@ use {
         database::Postgres,
         ipfs::Ipfs,
         ipfs_app_data::IpfsAppData,
+        order_events::OrderEventStream,
         orderbook::Orderbook,
         quoter::QuoteHandler,
     },
@@ -18,6 +19,7 @@ use {
     order_validation,
     shared::{
         account_balances,
+        api::rate_limiting::RateLimiter,
         bad_token::{
             cache::CachingDetector,
             instrumented::InstrumentedBadTokenDetectorExt,
@@ -27,6 +29,7 @@ use {
         },
         baseline_solver::BaseTokens,
         code_fetching::CachedCodeFetcher,
+        ethrpc::Web3,
         gas_price::InstrumentedGasEstimator,
         http_client::HttpClientFactory,
         metrics::{serve_metrics, DEFAULT_METRICS_PORT},
@@ -346,7 +349,7 @@ pub async fn run(args: Arguments) {
         hooks_contract,
         optimal_quoter.clone(),
         balance_fetcher,
-        signature_validator,
+        signature_validator.clone(),
         Arc::new(postgres.clone()),
         args.max_limit_orders_per_user,
         Arc::new(CachedCodeFetcher::new(Arc::new(web3.clone()))),
@@ -375,14 +378,23 @@ pub async fn run(args: Arguments) {
         postgres.clone(),
         order_validator.clone(),
         app_data.clone(),
+        signature_validator,
+        args.max_limit_orders_per_user,
     ));
 
     check_database_connection(orderbook.as_ref()).await;
     let quotes = Arc::new(
-        QuoteHandler::new(order_validator, optimal_quoter, app_data.clone())
-            .with_fast_quoter(fast_quoter),
+        QuoteHandler::new(
+            order_validator,
+            optimal_quoter,
+            app_data.clone(),
+            native_price_estimator.clone(),
+        )
+        .with_fast_quoter(fast_quoter),
     );
 
+    let order_events = OrderEventStream::new(postgres.pool.clone());
+
     let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
     let serve_api = serve_api(
         postgres,
@@ -394,6 +406,13 @@ pub async fn run(args: Arguments) {
             let _ = shutdown_receiver.await;
         },
         native_price_estimator,
+        order_events,
+        web3,
+        Arc::new(RateLimiter::new(
+            args.api_rate_limits.clone(),
+            args.api_rate_limit_keys.iter().cloned().collect(),
+            args.api_rate_limit_trust_forwarded_for,
+        )),
     );
 
     let mut metrics_address = args.bind_address;
@@ -460,6 +479,9 @@ fn serve_api(
     address: SocketAddr,
     shutdown_receiver: impl Future<Output = ()> + Send + 'static,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    order_events: Arc<OrderEventStream>,
+    web3: Web3,
+    rate_limiter: Arc<RateLimiter>,
 ) -> JoinHandle<()> {
     let filter = api::handle_all_routes(
         database,
@@ -467,6 +489,9 @@ fn serve_api(
         quotes,
         app_data,
         native_price_estimator,
+        order_events,
+        web3,
+        rate_limiter,
     )
     .boxed();
     tracing::info!(%address, "serving order book");
@@ -1,8 +1,11 @@
+pub mod rate_limiting;
+
 use {
     crate::price_estimation::PriceEstimationError,
     anyhow::Result,
+    rate_limiting::{RateLimited, RateLimiter},
     serde::{de::DeserializeOwned, Serialize},
-    std::{convert::Infallible, fmt::Debug, time::Instant},
+    std::{convert::Infallible, fmt::Debug, sync::Arc, time::Instant},
     warp::{
         filters::BoxedFilter,
         hyper::StatusCode,
@@ -18,7 +21,18 @@ pub type ApiReply = WithStatus<Json>;
 // We turn Rejection into Reply to workaround warp not setting CORS headers on
 // rejections.
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
-    let response = err.default_response();
+    let response = match err.find::<RateLimited>() {
+        Some(limited) => warp::reply::with_header(
+            with_status(
+                error("RateLimited", "too many requests, try again later"),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            "Retry-After",
+            limited.retry_after.as_secs_f64().ceil().to_string(),
+        )
+        .into_response(),
+        None => err.default_response(),
+    };
 
     let metrics = ApiMetrics::instance(observe::metrics::get_storage_registry()).unwrap();
     metrics
@@ -55,6 +69,7 @@ impl ApiMetrics {
         StatusCode::UNAUTHORIZED,
         StatusCode::FORBIDDEN,
         StatusCode::NOT_FOUND,
+        StatusCode::TOO_MANY_REQUESTS,
         StatusCode::INTERNAL_SERVER_ERROR,
         StatusCode::SERVICE_UNAVAILABLE,
     ];
@@ -174,7 +189,8 @@ where
     filter.map(|a| Box::new(a) as Box<dyn Reply>).boxed()
 }
 
-/// Sets up basic metrics, cors and proper log tracing for all routes.
+/// Sets up basic metrics, rate limiting, cors and proper log tracing for all
+/// routes.
 ///
 /// # Panics
 ///
@@ -182,6 +198,7 @@ where
 pub fn finalize_router(
     routes: Vec<(&'static str, BoxedRoute)>,
     log_prefix: &'static str,
+    rate_limiter: Arc<RateLimiter>,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     let metrics = ApiMetrics::instance(observe::metrics::get_storage_registry()).unwrap();
     metrics.reset_requests_rejected();
@@ -218,9 +235,16 @@ pub fn finalize_router(
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS", "PUT", "PATCH"])
-        .allow_headers(vec!["Origin", "Content-Type", "X-Auth-Token", "X-AppId"]);
+        .allow_headers(vec![
+            "Origin",
+            "Content-Type",
+            "X-Auth-Token",
+            "X-AppId",
+            "X-API-Key",
+        ]);
 
     warp::path!("api" / ..)
+        .and(rate_limiting::rate_limit(rate_limiter))
         .and(instrumented)
         .recover(handle_rejection)
         .with(cors)
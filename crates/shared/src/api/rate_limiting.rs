@@ -0,0 +1,508 @@
+//! Inbound rate limiting of API requests.
+//!
+//! Every configured [`RouteRateLimit`] owns a set of token buckets, one per
+//! client. A client is identified either by its IP address or by the value of
+//! its `X-API-Key` header if that key is one of the configured API keys.
+//! Requests that find their bucket empty are rejected with a [`RateLimited`]
+//! rejection which gets turned into a `429 Too Many Requests` response.
+
+use {
+    anyhow::{anyhow, ensure, Context, Result},
+    std::{
+        collections::{HashMap, HashSet},
+        fmt::{self, Display, Formatter},
+        net::{IpAddr, SocketAddr},
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    warp::{filters::path::FullPath, http::Method, Filter, Rejection},
+};
+
+/// Number of tracked buckets after which buckets that refilled completely get
+/// garbage collected.
+const GC_THRESHOLD: usize = 10_000;
+
+/// How clients get identified when rate limiting requests.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RateLimitKey {
+    /// The IP address of the client.
+    #[default]
+    Ip,
+    /// The value of the `X-API-Key` header. Requests without that header or
+    /// with an unknown key are keyed by their IP address, otherwise clients
+    /// could avoid limits by sending a different key with every request.
+    ApiKey,
+}
+
+impl RateLimitKey {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ip => "ip",
+            Self::ApiKey => "api-key",
+        }
+    }
+}
+
+/// Rate limit for all requests with the given method whose path (without the
+/// `/api` prefix) starts with `path`.
+///
+/// Parsed from strings like `POST /v1/quote=100/1m@api-key` which allows 100
+/// requests per minute per API key. The method and the key are optional, so
+/// `/v1/orders=10/1s` limits every method to 10 requests per second per IP.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouteRateLimit {
+    pub method: Option<Method>,
+    pub path: String,
+    /// Maximum number of requests (size of the bucket).
+    pub requests: u32,
+    /// Time it takes to completely refill an empty bucket.
+    pub interval: Duration,
+    pub key: RateLimitKey,
+}
+
+impl RouteRateLimit {
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        self.method.as_ref().map_or(true, |m| m == method)
+            && path
+                .strip_prefix(&self.path)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        f64::from(self.requests) / self.interval.as_secs_f64()
+    }
+}
+
+impl FromStr for RouteRateLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (route, limit) = s
+            .split_once('=')
+            .context("missing '=' between route and limit")?;
+        let (method, path) = match route.trim().split_once(' ') {
+            Some((method, path)) => (
+                Some(Method::from_str(method).context("invalid method")?),
+                path.trim(),
+            ),
+            None => (None, route.trim()),
+        };
+        ensure!(path.starts_with('/'), "path must start with '/'");
+        let (limit, key) = match limit.split_once('@') {
+            Some((limit, "ip")) => (limit, RateLimitKey::Ip),
+            Some((limit, "api-key")) => (limit, RateLimitKey::ApiKey),
+            Some((_, key)) => return Err(anyhow!("unknown rate limit key {key:?}")),
+            None => (limit, RateLimitKey::Ip),
+        };
+        let (requests, interval) = limit
+            .split_once('/')
+            .context("missing '/' between requests and interval")?;
+        let requests: u32 = requests.trim().parse().context("invalid requests")?;
+        let interval = humantime::parse_duration(interval.trim()).context("invalid interval")?;
+        ensure!(requests > 0, "requests must be positive");
+        ensure!(!interval.is_zero(), "interval must be positive");
+
+        Ok(Self {
+            method,
+            path: path.trim_end_matches('/').to_owned(),
+            requests,
+            interval,
+            key,
+        })
+    }
+}
+
+impl Display for RouteRateLimit {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(method) = &self.method {
+            write!(f, "{method} ")?;
+        }
+        write!(
+            f,
+            "{}={}/{}@{}",
+            self.path,
+            self.requests,
+            humantime::format_duration(self.interval),
+            self.key.as_str(),
+        )
+    }
+}
+
+/// Rejection for requests that exceeded their rate limit.
+#[derive(Debug)]
+pub struct RateLimited {
+    /// Time until the next request would be accepted.
+    pub retry_after: Duration,
+}
+
+impl warp::reject::Reject for RateLimited {}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, limit: &RouteRateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * limit.tokens_per_second()).min(f64::from(limit.requests));
+        self.updated_at = now;
+    }
+}
+
+#[derive(Debug)]
+struct Buckets {
+    /// Buckets indexed by the position of their limit and the client key.
+    buckets: HashMap<(usize, String), Bucket>,
+    next_gc: usize,
+}
+
+/// Token bucket rate limiter for incoming API requests.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: Vec<RouteRateLimit>,
+    api_keys: HashSet<String>,
+    trust_forwarded_for: bool,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter. Only the given `api_keys` get their own
+    /// buckets. If `trust_forwarded_for` is set, the client IP is taken from
+    /// the last entry of the `X-Forwarded-For` header (the one added by the
+    /// proxy in front of the API) instead of the connection.
+    pub fn new(
+        limits: Vec<RouteRateLimit>,
+        api_keys: HashSet<String>,
+        trust_forwarded_for: bool,
+    ) -> Self {
+        Self {
+            limits,
+            api_keys,
+            trust_forwarded_for,
+            buckets: Mutex::new(Buckets {
+                buckets: Default::default(),
+                next_gc: GC_THRESHOLD,
+            }),
+        }
+    }
+
+    /// Takes a token from every bucket the request belongs to. Requests are
+    /// only accepted (and tokens only taken) if none of the buckets is empty.
+    fn check(&self, request: &Request, now: Instant) -> Result<(), RateLimited> {
+        let mut state = self.buckets.lock().unwrap();
+        let mut keys = Vec::new();
+        for (index, limit) in self.limits.iter().enumerate() {
+            if !limit.matches(&request.method, &request.path) {
+                continue;
+            }
+            let key = (index, self.client_key(limit.key, request));
+            let bucket = state.buckets.entry(key.clone()).or_insert_with(|| Bucket {
+                tokens: f64::from(limit.requests),
+                updated_at: now,
+            });
+            bucket.refill(limit, now);
+            if bucket.tokens < 1. {
+                Metrics::get()
+                    .requests_rate_limited
+                    .with_label_values(&[&limit.to_string(), limit.key.as_str()])
+                    .inc();
+                let missing = 1. - bucket.tokens;
+                return Err(RateLimited {
+                    retry_after: Duration::from_secs_f64(missing / limit.tokens_per_second()),
+                });
+            }
+            keys.push(key);
+        }
+        for key in keys {
+            if let Some(bucket) = state.buckets.get_mut(&key) {
+                bucket.tokens -= 1.;
+            }
+        }
+
+        if state.buckets.len() >= state.next_gc {
+            self.collect_garbage(&mut state, now);
+        }
+        Ok(())
+    }
+
+    /// Drops buckets that would be full by now since they are equivalent to
+    /// new ones.
+    fn collect_garbage(&self, state: &mut Buckets, now: Instant) {
+        state.buckets.retain(|(index, _), bucket| {
+            let limit = &self.limits[*index];
+            bucket.refill(limit, now);
+            bucket.tokens < f64::from(limit.requests)
+        });
+        state.next_gc = (state.buckets.len() * 2).max(GC_THRESHOLD);
+    }
+
+    fn client_key(&self, key: RateLimitKey, request: &Request) -> String {
+        if let (RateLimitKey::ApiKey, Some(api_key)) = (key, &request.api_key) {
+            if self.api_keys.contains(api_key) {
+                return format!("key:{api_key}");
+            }
+        }
+        let forwarded = self
+            .trust_forwarded_for
+            .then(|| request.forwarded_for.as_deref())
+            .flatten()
+            .and_then(|header| header.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+        match forwarded.or(request.remote.map(|addr| addr.ip())) {
+            Some(ip) => format!("ip:{ip}"),
+            None => "ip:unknown".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Request {
+    method: Method,
+    path: String,
+    api_key: Option<String>,
+    forwarded_for: Option<String>,
+    remote: Option<SocketAddr>,
+}
+
+/// Filter rejecting requests that exceed the limits of the rate limiter. Limits
+/// are matched against the request path without its `/api` prefix.
+pub fn rate_limit(
+    limiter: Arc<RateLimiter>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("x-api-key"))
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .and(warp::addr::remote())
+        .and_then(
+            move |method: Method,
+                  path: FullPath,
+                  api_key: Option<String>,
+                  forwarded_for: Option<String>,
+                  remote: Option<SocketAddr>| {
+                let limiter = limiter.clone();
+                async move {
+                    if limiter.limits.is_empty() {
+                        return Ok(());
+                    }
+                    let request = Request {
+                        method,
+                        path: path
+                            .as_str()
+                            .strip_prefix("/api")
+                            .unwrap_or(path.as_str())
+                            .to_owned(),
+                        api_key,
+                        forwarded_for,
+                        remote,
+                    };
+                    limiter
+                        .check(&request, Instant::now())
+                        .map_err(warp::reject::custom)
+                }
+            },
+        )
+        .untuple_one()
+}
+
+#[derive(prometheus_metric_storage::MetricStorage, Clone, Debug)]
+#[metric(subsystem = "api")]
+struct Metrics {
+    /// Number of API requests rejected because of rate limits.
+    #[metric(labels("limit", "key"))]
+    requests_rate_limited: prometheus::IntCounterVec,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Metrics::instance(observe::metrics::get_storage_registry()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, path: &str, api_key: Option<&str>, ip: [u8; 4]) -> Request {
+        Request {
+            method,
+            path: path.to_owned(),
+            api_key: api_key.map(str::to_owned),
+            forwarded_for: None,
+            remote: Some(SocketAddr::from((ip, 1234))),
+        }
+    }
+
+    #[test]
+    fn parses_route_rate_limits() {
+        assert_eq!(
+            "POST /v1/quote=100/1m@api-key"
+                .parse::<RouteRateLimit>()
+                .unwrap(),
+            RouteRateLimit {
+                method: Some(Method::POST),
+                path: "/v1/quote".to_owned(),
+                requests: 100,
+                interval: Duration::from_secs(60),
+                key: RateLimitKey::ApiKey,
+            }
+        );
+        assert_eq!(
+            "/v1/orders/=10/1s".parse::<RouteRateLimit>().unwrap(),
+            RouteRateLimit {
+                method: None,
+                path: "/v1/orders".to_owned(),
+                requests: 10,
+                interval: Duration::from_secs(1),
+                key: RateLimitKey::Ip,
+            }
+        );
+
+        for invalid in [
+            "POST /v1/quote",
+            "POST /v1/quote=100",
+            "POST v1/quote=100/1m",
+            "POST /v1/quote=0/1m",
+            "POST /v1/quote=100/0s",
+            "POST /v1/quote=100/1m@user",
+        ] {
+            assert!(invalid.parse::<RouteRateLimit>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn display_roundtrips() {
+        for limit in ["POST /v1/quote=100/1m@api-key", "/v1/orders=10/1s@ip"] {
+            let parsed = limit.parse::<RouteRateLimit>().unwrap();
+            assert_eq!(parsed.to_string(), limit);
+        }
+    }
+
+    #[test]
+    fn matches_path_segments() {
+        let limit: RouteRateLimit = "POST /v1/orders=1/1s".parse().unwrap();
+        assert!(limit.matches(&Method::POST, "/v1/orders"));
+        assert!(limit.matches(&Method::POST, "/v1/orders/batch"));
+        assert!(!limit.matches(&Method::GET, "/v1/orders"));
+        assert!(!limit.matches(&Method::POST, "/v1/ordersfoo"));
+    }
+
+    #[test]
+    fn limits_and_refills_buckets() {
+        let limiter = RateLimiter::new(
+            vec!["POST /v1/quote=2/1s".parse().unwrap()],
+            Default::default(),
+            false,
+        );
+        let quote = request(Method::POST, "/v1/quote", None, [1, 1, 1, 1]);
+        let now = Instant::now();
+
+        assert!(limiter.check(&quote, now).is_ok());
+        assert!(limiter.check(&quote, now).is_ok());
+        let err = limiter.check(&quote, now).unwrap_err();
+        assert_eq!(err.retry_after, Duration::from_millis(500));
+
+        // Other routes and clients are not affected.
+        let orders = request(Method::POST, "/v1/orders", None, [1, 1, 1, 1]);
+        assert!(limiter.check(&orders, now).is_ok());
+        let other = request(Method::POST, "/v1/quote", None, [2, 2, 2, 2]);
+        assert!(limiter.check(&other, now).is_ok());
+
+        // Half the interval refills one token.
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.check(&quote, later).is_ok());
+        assert!(limiter.check(&quote, later).is_err());
+    }
+
+    #[test]
+    fn keys_by_api_key_with_ip_fallback() {
+        let limiter = RateLimiter::new(
+            vec!["/v1/quote=1/1m@api-key".parse().unwrap()],
+            ["a".to_owned(), "b".to_owned()].into(),
+            false,
+        );
+        let now = Instant::now();
+
+        let a = request(Method::POST, "/v1/quote", Some("a"), [1, 1, 1, 1]);
+        let b = request(Method::POST, "/v1/quote", Some("b"), [1, 1, 1, 1]);
+        assert!(limiter.check(&a, now).is_ok());
+        assert!(limiter.check(&b, now).is_ok());
+        assert!(limiter.check(&a, now).is_err());
+
+        let anonymous = request(Method::POST, "/v1/quote", None, [1, 1, 1, 1]);
+        assert!(limiter.check(&anonymous, now).is_ok());
+        assert!(limiter.check(&anonymous, now).is_err());
+
+        // Unknown keys share the bucket of their IP address.
+        let unknown = request(Method::POST, "/v1/quote", Some("c"), [2, 2, 2, 2]);
+        let rotated = request(Method::POST, "/v1/quote", Some("d"), [2, 2, 2, 2]);
+        assert!(limiter.check(&unknown, now).is_ok());
+        assert!(limiter.check(&rotated, now).is_err());
+    }
+
+    #[test]
+    fn uses_forwarded_for_when_trusted() {
+        let limits = vec!["/v1/quote=1/1m".parse().unwrap()];
+        let mut first = request(Method::POST, "/v1/quote", None, [1, 1, 1, 1]);
+        first.forwarded_for = Some("9.9.9.9, 3.3.3.3".to_owned());
+        let mut second = request(Method::POST, "/v1/quote", None, [1, 1, 1, 1]);
+        second.forwarded_for = Some("4.4.4.4".to_owned());
+
+        let trusting = RateLimiter::new(limits.clone(), Default::default(), true);
+        assert!(trusting.check(&first, Instant::now()).is_ok());
+        assert!(trusting.check(&second, Instant::now()).is_ok());
+
+        let distrusting = RateLimiter::new(limits, Default::default(), false);
+        assert!(distrusting.check(&first, Instant::now()).is_ok());
+        assert!(distrusting.check(&second, Instant::now()).is_err());
+    }
+
+    #[test]
+    fn garbage_collects_full_buckets() {
+        let limiter = RateLimiter::new(
+            vec!["/v1/quote=1/1s".parse().unwrap()],
+            Default::default(),
+            false,
+        );
+        let now = Instant::now();
+        for i in 0..GC_THRESHOLD - 1 {
+            let [a, b, c, d] = (i as u32).to_be_bytes();
+            let request = request(Method::POST, "/v1/quote", None, [a, b, c, d]);
+            limiter.check(&request, now).unwrap();
+        }
+        assert_eq!(
+            limiter.buckets.lock().unwrap().buckets.len(),
+            GC_THRESHOLD - 1
+        );
+
+        let request = request(Method::POST, "/v1/quote", None, [255, 255, 255, 255]);
+        limiter
+            .check(&request, now + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(limiter.buckets.lock().unwrap().buckets.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn filter_rejects_limited_requests() {
+        let limiter = Arc::new(RateLimiter::new(
+            vec!["POST /v1/quote=1/1m".parse().unwrap()],
+            Default::default(),
+            false,
+        ));
+        let filter = rate_limit(limiter);
+        let request = || {
+            warp::test::request()
+                .method("POST")
+                .path("/api/v1/quote")
+                .remote_addr(SocketAddr::from(([1, 1, 1, 1], 1234)))
+        };
+
+        assert!(request().filter(&filter).await.is_ok());
+        let rejection = request().filter(&filter).await.unwrap_err();
+        assert!(rejection.find::<RateLimited>().is_some());
+    }
+}
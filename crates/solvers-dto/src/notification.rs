@@ -49,6 +49,7 @@ pub enum Kind {
         tokens: BTreeSet<H160>,
     },
     SolverAccountInsufficientBalance {
+        account: H160,
         #[serde_as(as = "HexOrDecimalU256")]
         required: U256,
     },
@@ -64,6 +65,20 @@ pub enum Kind {
     Cancelled,
     Fail,
     PostprocessingTimedOut,
+    Contributed {
+        solver: String,
+        settlement: Settlement,
+    },
+}
+
+/// The outcome of a settlement a solution was merged into.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Settlement {
+    Success { transaction: H256 },
+    Revert { transaction: H256 },
+    Cancelled,
+    Fail,
 }
 
 type BlockNo = u64;
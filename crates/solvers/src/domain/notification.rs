@@ -38,10 +38,12 @@ pub enum Kind {
     SimulationFailed(BlockNo, Transaction, SimulationSucceededAtLeastOnce),
     ScoringFailed(ScoreKind),
     NonBufferableTokensUsed(TokensUsed),
-    SolverAccountInsufficientBalance(RequiredEther),
+    SolverAccountInsufficientBalance(eth::Address, RequiredEther),
     Settled(Settlement),
     DriverError(String),
     PostprocessingTimedOut,
+    /// The solution was merged into the settlement of another solver.
+    Contributed(String, Settlement),
 }
 
 /// The result of winning solver trying to settle the transaction onchain.
@@ -0,0 +1,5 @@
+-- Optionally store why an order was filtered or marked invalid by the autopilot
+-- (e.g. `insufficient_balance`, `missing_price`) so the event history of an
+-- order can be explained to users.
+ALTER TABLE order_events
+    ADD COLUMN reason text;
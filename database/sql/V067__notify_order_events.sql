@@ -0,0 +1,21 @@
+-- Publish every new order event on the `order_events` notification channel so
+-- that the orderbook can push order status changes to API subscribers instead
+-- of them having to poll.
+CREATE FUNCTION notify_order_event() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify(
+        'order_events',
+        json_build_object(
+            'orderUid', '0x' || encode(NEW.order_uid, 'hex'),
+            'timestamp', NEW.timestamp,
+            'label', NEW.label,
+            'reason', NEW.reason
+        )::text
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER order_events_notify
+    AFTER INSERT ON order_events
+    FOR EACH ROW EXECUTE FUNCTION notify_order_event();
@@ -0,0 +1,2 @@
+-- Optimization for querying the price history of a single token.
+CREATE INDEX auction_prices_token_auction_id ON auction_prices USING BTREE (token, auction_id);
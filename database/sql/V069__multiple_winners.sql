@@ -0,0 +1,14 @@
+-- Auctions can have multiple winners (one per solver) that settle disjoint
+-- solutions in separate transactions.
+ALTER TABLE settlement_scores DROP CONSTRAINT settlement_scores_pkey;
+ALTER TABLE settlement_scores ADD PRIMARY KEY (auction_id, winner);
+
+-- Call data is stored for every winner of the auction.
+ALTER TABLE settlement_call_data ADD COLUMN solver bytea NOT NULL DEFAULT '\x0000000000000000000000000000000000000000';
+UPDATE settlement_call_data cd
+SET solver = ss.winner
+FROM settlement_scores ss
+WHERE ss.auction_id = cd.auction_id;
+ALTER TABLE settlement_call_data ALTER COLUMN solver DROP DEFAULT;
+ALTER TABLE settlement_call_data DROP CONSTRAINT settlement_call_data_pkey;
+ALTER TABLE settlement_call_data ADD PRIMARY KEY (auction_id, solver);
@@ -0,0 +1,3 @@
+-- The conditions (tokens, order size, app code) an order had to satisfy for a
+-- fee policy to apply. NULL for policies that apply to all orders.
+ALTER TABLE fee_policies ADD COLUMN scope text;
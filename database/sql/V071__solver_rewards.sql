@@ -0,0 +1,17 @@
+-- Accounting periods for which solver rewards have been computed. A period
+-- covers all auctions with a block deadline in [start_block, end_block).
+CREATE TABLE reward_periods (
+    start_block bigint PRIMARY KEY,
+    end_block bigint NOT NULL,
+    computed_at timestamptz NOT NULL
+);
+
+-- The reward every solver that won an auction earned in an accounting period.
+CREATE TABLE solver_reward_statements (
+    start_block bigint NOT NULL,
+    solver bytea NOT NULL,
+    auctions bigint NOT NULL,
+    settled bigint NOT NULL,
+    reward numeric NOT NULL,
+    PRIMARY KEY (start_block, solver)
+);